@@ -0,0 +1,267 @@
+// 图结构分析命令：路径、环等查询在后端完成，前端只负责高亮返回的节点
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use crate::error::AppError;
+use crate::graph::{parse_graph, GraphModel};
+use crate::run_blocking;
+
+/// 堆中的候选项：距离小的先出，距离相同按节点序号，保证结果稳定
+struct Candidate {
+    distance: f64,
+    node: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// Dijkstra 最短路径；不可达时返回空列表
+pub(crate) fn find_shortest_path(graph: &GraphModel, from: &str, to: &str) -> Result<Vec<String>, AppError> {
+    let start = graph.node_index(from)?;
+    let goal = graph.node_index(to)?;
+    if let Some(edge) = graph.edges.iter().find(|edge| edge.weight < 0.0) {
+        return Err(AppError::InvalidInput(format!(
+            "Dijkstra 不支持负权重: {} → {} 的权重为 {}",
+            graph.node_ids[edge.source], graph.node_ids[edge.target], edge.weight
+        )));
+    }
+
+    let adjacency = graph.adjacency();
+    let mut distances = vec![f64::INFINITY; graph.node_count()];
+    let mut previous = vec![usize::MAX; graph.node_count()];
+    let mut heap = BinaryHeap::new();
+    distances[start] = 0.0;
+    heap.push(Candidate {
+        distance: 0.0,
+        node: start,
+    });
+    while let Some(Candidate { distance, node }) = heap.pop() {
+        if node == goal {
+            break;
+        }
+        if distance > distances[node] {
+            continue;
+        }
+        for &(next, edge_index) in &adjacency[node] {
+            let candidate = distance + graph.edges[edge_index].weight;
+            if candidate < distances[next] {
+                distances[next] = candidate;
+                previous[next] = node;
+                heap.push(Candidate {
+                    distance: candidate,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    if distances[goal].is_infinite() {
+        return Ok(Vec::new());
+    }
+    let mut path = vec![goal];
+    let mut current = goal;
+    while current != start {
+        current = previous[current];
+        path.push(current);
+    }
+    path.reverse();
+    Ok(path.into_iter().map(|node| graph.node_ids[node].clone()).collect())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    Unvisited,
+    OnStack,
+    Done,
+}
+
+/// 迭代式 DFS 找环：每条指向栈中节点的回边对应一个环，按栈中顺序输出；自环输出为单节点环。
+/// 起点与后继都按文档顺序遍历，同一份数据每次得到相同的结果
+pub(crate) fn detect_cycles(graph: &GraphModel) -> Vec<Vec<String>> {
+    let successors = graph.successors();
+    let mut state = vec![VisitState::Unvisited; graph.node_count()];
+    let mut cycles = Vec::new();
+    // 显式栈：(节点, 下一个待访问后继的位置)
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    for root in 0..graph.node_count() {
+        if state[root] != VisitState::Unvisited {
+            continue;
+        }
+        state[root] = VisitState::OnStack;
+        stack.push((root, 0));
+        while let Some(&mut (node, ref mut cursor)) = stack.last_mut() {
+            let Some(&next) = successors[node].get(*cursor) else {
+                state[node] = VisitState::Done;
+                stack.pop();
+                continue;
+            };
+            *cursor += 1;
+            match state[next] {
+                VisitState::Unvisited => {
+                    state[next] = VisitState::OnStack;
+                    stack.push((next, 0));
+                }
+                VisitState::OnStack => {
+                    let start = stack.iter().rposition(|&(member, _)| member == next).unwrap_or(0);
+                    cycles.push(stack[start..].iter().map(|&(member, _)| graph.node_ids[member].clone()).collect());
+                }
+                VisitState::Done => {}
+            }
+        }
+    }
+    cycles
+}
+
+/// Kahn 拓扑排序：按连线方向（source 先于 target）输出节点 id，可同时排出的节点按 id 排序，结果确定。
+/// 不是 DAG 时返回 Cycle 错误，附带 detect_cycles 找到的第一个环
+pub(crate) fn topological_order(graph: &GraphModel) -> Result<Vec<String>, AppError> {
+    let successors = graph.successors();
+    let mut in_degree = vec![0usize; graph.node_count()];
+    for &next in successors.iter().flatten() {
+        in_degree[next] += 1;
+    }
+    let mut ready: BinaryHeap<Reverse<(&str, usize)>> = (0..graph.node_count())
+        .filter(|&node| in_degree[node] == 0)
+        .map(|node| Reverse((graph.node_ids[node].as_str(), node)))
+        .collect();
+    let mut order = Vec::with_capacity(graph.node_count());
+    while let Some(Reverse((id, node))) = ready.pop() {
+        order.push(String::from(id));
+        for &next in &successors[node] {
+            in_degree[next] -= 1;
+            if in_degree[next] == 0 {
+                ready.push(Reverse((graph.node_ids[next].as_str(), next)));
+            }
+        }
+    }
+    if order.len() < graph.node_count() {
+        let ids = detect_cycles(graph).into_iter().next().unwrap_or_default();
+        return Err(AppError::Cycle {
+            message: format!("图中存在环，无法拓扑排序: {}", ids.join(" → ")),
+            ids,
+        });
+    }
+    Ok(order)
+}
+
+/// 迭代式 Tarjan 强连通分量，沿连线方向（忽略 directed 标记）
+pub(crate) fn strongly_connected_components(graph: &GraphModel) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let successors = graph.successors();
+    let mut indices = vec![UNVISITED; graph.node_count()];
+    let mut low_links = vec![0; graph.node_count()];
+    let mut on_stack = vec![false; graph.node_count()];
+    let mut members: Vec<usize> = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+    // 显式调用栈：(节点, 下一个待访问后继的位置)
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+
+    for root in 0..graph.node_count() {
+        if indices[root] != UNVISITED {
+            continue;
+        }
+        stack.push((root, 0));
+        while let Some(&mut (node, ref mut cursor)) = stack.last_mut() {
+            if *cursor == 0 && indices[node] == UNVISITED {
+                indices[node] = next_index;
+                low_links[node] = next_index;
+                next_index += 1;
+                members.push(node);
+                on_stack[node] = true;
+            }
+            if let Some(&next) = successors[node].get(*cursor) {
+                *cursor += 1;
+                if indices[next] == UNVISITED {
+                    stack.push((next, 0));
+                } else if on_stack[next] {
+                    low_links[node] = low_links[node].min(indices[next]);
+                }
+                continue;
+            }
+            stack.pop();
+            if let Some(&(parent, _)) = stack.last() {
+                low_links[parent] = low_links[parent].min(low_links[node]);
+            }
+            if low_links[node] == indices[node] {
+                let mut component = Vec::new();
+                while let Some(member) = members.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// 分量内按节点 id 排序，分量之间按各自最小的 id 排序
+fn sort_components(graph: &GraphModel, components: Vec<Vec<usize>>) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = components
+        .into_iter()
+        .map(|component| {
+            let mut ids: Vec<String> = component.into_iter().map(|node| graph.node_ids[node].clone()).collect();
+            ids.sort();
+            ids
+        })
+        .collect();
+    groups.sort();
+    groups
+}
+
+#[tauri::command]
+pub(crate) async fn shortest_path(data: String, from: String, to: String) -> Result<Vec<String>, AppError> {
+    run_blocking(move || find_shortest_path(&parse_graph(&data)?, &from, &to)).await
+}
+
+/// 返回空列表表示图是 DAG
+#[tauri::command]
+pub(crate) async fn find_cycles(data: String) -> Result<Vec<Vec<String>>, AppError> {
+    run_blocking(move || Ok(detect_cycles(&parse_graph(&data)?))).await
+}
+
+#[tauri::command]
+pub(crate) async fn topo_sort(data: String) -> Result<Vec<String>, AppError> {
+    run_blocking(move || topological_order(&parse_graph(&data)?)).await
+}
+
+/// 默认忽略方向求连通分量；strongly 为 true 时按连线方向求强连通分量。孤立节点单独成组
+#[tauri::command]
+pub(crate) async fn connected_components(data: String, strongly: Option<bool>) -> Result<Vec<Vec<String>>, AppError> {
+    run_blocking(move || {
+        let graph = parse_graph(&data)?;
+        let components = if strongly.unwrap_or(false) {
+            strongly_connected_components(&graph)
+        } else {
+            graph.undirected_components()
+        };
+        Ok(sort_components(&graph, components))
+    })
+    .await
+}
@@ -0,0 +1,389 @@
+// 后台自动保存：queue_autosave 只记下每个文档最新的待写内容，连续编辑期间不断推迟写入，
+// 安静 autosaveDebounceMs（设置项，默认 2 秒）后由后台线程一次写盘，把一连串编辑合并为一次写入
+// flush_autosave 立即写出全部待写内容；应用退出前也会调用，保证退出时不丢编辑
+// 自动保存写盘时每 10 分钟最多备份一次旧文件，频繁写入不会把滚动备份很快轮换掉
+// 后台写入失败时发 autosave-failed 事件，数据保留在待写表中，下次编辑、flush 或一段时间后再试
+// 入队时立即把待写内容写入 <name>.recovery（gzip，带密码时加密），真正保存成功后删除；
+// 异常退出后残留的恢复文件由 check_recovery 列出，restore_recovery 取回内容
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Condvar, LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppError;
+use crate::{
+    backups, crypto, decode_graph_bytes, encode_graph_bytes, file_modified_ms, graph_document_is_encrypted,
+    is_compressed_graph_path, json_format, normalize_password, resolve_app_data_dir, resolve_existing_graph_path,
+    run_blocking, sanitize_graph_name, sanitize_required_graph_name, save_named_graph, settings, validate,
+    write_file_atomic, GraphWriteOptions,
+};
+
+pub(crate) const AUTOSAVE_FAILED_EVENT: &str = "autosave-failed";
+
+const DEBOUNCE_SETTING: &str = "autosaveDebounceMs";
+const DEFAULT_DEBOUNCE_MS: u64 = 2000;
+// 过短等于不防抖，过长则异常退出时丢失的编辑太多
+const MIN_DEBOUNCE_MS: u64 = 200;
+const MAX_DEBOUNCE_MS: u64 = 60_000;
+// 失败后的重试间隔，避免磁盘满或缺少密码时每个防抖周期都报一次错
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+const RECOVERY_EXTENSION: &str = ".recovery";
+// 判断是否加密只需文件头
+const RECOVERY_HEADER_LEN: u64 = 64;
+
+struct PendingWrite {
+    data: String,
+    password: Option<String>,
+    due: Instant,
+}
+
+struct Autosave {
+    pending: Mutex<HashMap<String, PendingWrite>>,
+    wake: Condvar,
+    // 取出待写内容到写完为止都持有，保证同一文档先取出的内容先落盘，新内容不会被旧内容覆盖
+    write_lock: Mutex<()>,
+}
+
+static AUTOSAVE: LazyLock<Autosave> = LazyLock::new(|| Autosave {
+    pending: Mutex::new(HashMap::new()),
+    wake: Condvar::new(),
+    write_lock: Mutex::new(()),
+});
+
+// 恢复文件的写入与删除串行执行；删除前确认没有更新的待写内容，不会删掉刚写入的恢复文件
+static RECOVERY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecoveryEntry {
+    name: String,
+    modified_at: Option<u64>,
+    byte_size: u64,
+    encrypted: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AutosaveFailure {
+    name: String,
+    code: &'static str,
+    message: String,
+}
+
+fn debounce_interval() -> Duration {
+    let millis = settings::read_setting(DEBOUNCE_SETTING).unwrap_or(DEFAULT_DEBOUNCE_MS);
+    Duration::from_millis(millis.clamp(MIN_DEBOUNCE_MS, MAX_DEBOUNCE_MS))
+}
+
+fn lock_pending() -> Result<MutexGuard<'static, HashMap<String, PendingWrite>>, AppError> {
+    AUTOSAVE
+        .pending
+        .lock()
+        .map_err(|_| AppError::Io(String::from("自动保存状态不可用")))
+}
+
+fn lock_writes() -> Result<MutexGuard<'static, ()>, AppError> {
+    AUTOSAVE
+        .write_lock
+        .lock()
+        .map_err(|_| AppError::Io(String::from("自动保存状态不可用")))
+}
+
+fn lock_recovery() -> Result<MutexGuard<'static, ()>, AppError> {
+    RECOVERY_LOCK
+        .lock()
+        .map_err(|_| AppError::Io(String::from("恢复文件状态不可用")))
+}
+
+fn resolve_recovery_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}{}", name, RECOVERY_EXTENSION)))
+}
+
+/// 写入该文档当前的待写内容；已被写出（待写表中没有）时跳过
+/// 加密文档在没有密码时不写恢复文件，避免内容以明文落盘
+fn write_recovery(name: &str) -> Result<(), AppError> {
+    let _recovery = lock_recovery()?;
+    let Some((data, password)) = lock_pending()?
+        .get(name)
+        .map(|write| (write.data.clone(), write.password.clone()))
+    else {
+        return Ok(());
+    };
+    let password = normalize_password(password.as_deref());
+    if password.is_none() && graph_document_is_encrypted(name)? {
+        return Ok(());
+    }
+    let options = GraphWriteOptions {
+        password,
+        ..GraphWriteOptions::default()
+    };
+    write_file_atomic(&resolve_recovery_path(name)?, &encode_graph_bytes(data.as_bytes(), options)?)
+}
+
+/// 文档保存成功后调用；期间又有新的待写内容时保留恢复文件
+pub(crate) fn clear_recovery(name: &str) {
+    let Ok(_recovery) = lock_recovery() else {
+        return;
+    };
+    if lock_pending().map_or(true, |pending| pending.contains_key(name)) {
+        return;
+    }
+    if let Ok(path) = resolve_recovery_path(name) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// 文档删除后调用
+pub(crate) fn delete_recovery(name: &str) {
+    let Ok(_recovery) = lock_recovery() else {
+        return;
+    };
+    if let Ok(path) = resolve_recovery_path(name) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+pub(crate) fn rename_recovery(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    let _recovery = lock_recovery()?;
+    let old_path = resolve_recovery_path(old_name)?;
+    let new_path = resolve_recovery_path(new_name)?;
+    if old_path.is_file() && !new_path.exists() {
+        std::fs::rename(old_path, new_path).map_err(|e| AppError::io("重命名恢复文件失败", e))?;
+    }
+    Ok(())
+}
+
+fn read_recovery_entry(name: String, path: &std::path::Path) -> Option<RecoveryEntry> {
+    let metadata = std::fs::metadata(path).ok().filter(|metadata| metadata.is_file())?;
+    let mut header = Vec::new();
+    std::fs::File::open(path)
+        .and_then(|file| file.take(RECOVERY_HEADER_LEN).read_to_end(&mut header))
+        .ok()?;
+    Some(RecoveryEntry {
+        name,
+        modified_at: file_modified_ms(&metadata),
+        byte_size: metadata.len(),
+        encrypted: crypto::is_encrypted(&header),
+    })
+}
+
+/// 定时快照使用：没有设置密码的待写内容
+pub(crate) fn pending_plain_data(name: &str) -> Option<String> {
+    let pending = lock_pending().ok()?;
+    let write = pending.get(name)?;
+    normalize_password(write.password.as_deref())
+        .is_none()
+        .then(|| write.data.clone())
+}
+
+/// 没有密码时不覆盖已加密的文档，避免自动保存把加密文档降级为明文
+/// 与显式保存经过同样的校验，返回按 danglingEdges 设置剔除的悬空连线
+fn write_pending(name: &str, pending: &PendingWrite) -> Result<Vec<String>, AppError> {
+    let password = normalize_password(pending.password.as_deref());
+    if password.is_none() && graph_document_is_encrypted(name)? {
+        return Err(AppError::PasswordRequired(format!("文档已加密，自动保存需要密码: {}", name)));
+    }
+    let prepared = validate::prepare_document(pending.data.clone(), json_format::default_format())?;
+    // 沿用文档现有的压缩格式，不把纯 JSON 文档改写为 .json.gz
+    let compress = resolve_existing_graph_path(name)?
+        .map(|path| is_compressed_graph_path(&path))
+        .unwrap_or(true);
+    let options = GraphWriteOptions {
+        compress,
+        password,
+        backup_interval: Some(backups::AUTOSAVE_BACKUP_INTERVAL),
+        ..GraphWriteOptions::default()
+    };
+    save_named_graph(Some(name), &prepared.data, options)?;
+    Ok(prepared.dropped_edges)
+}
+
+/// 写出已到期（force 时为全部）的待写内容；失败的条目放回待写表，除非期间已有更新的内容
+fn write_due(force: bool) -> Vec<(String, AppError)> {
+    let Ok(_writes) = lock_writes() else {
+        return Vec::new();
+    };
+    let taken: Vec<(String, PendingWrite)> = match lock_pending() {
+        Ok(mut pending) => {
+            let now = Instant::now();
+            let names: Vec<String> = pending
+                .iter()
+                .filter(|(_, write)| force || write.due <= now)
+                .map(|(name, _)| name.clone())
+                .collect();
+            names
+                .into_iter()
+                .filter_map(|name| pending.remove(&name).map(|write| (name, write)))
+                .collect()
+        }
+        Err(_) => return Vec::new(),
+    };
+
+    let mut failures = Vec::new();
+    for (name, write) in taken {
+        match write_pending(&name, &write) {
+            Ok(dropped_edges) => {
+                if !dropped_edges.is_empty() {
+                    log::warn!("自动保存剔除悬空连线: name={} edges={:?}", name, dropped_edges);
+                }
+                log::info!("自动保存完成: name={} bytes={}", name, write.data.len());
+            }
+            Err(error) => {
+                log::error!("自动保存失败: name={} code={} error={}", name, error.code(), error);
+                if let Ok(mut pending) = lock_pending() {
+                    pending.entry(name.clone()).or_insert(PendingWrite {
+                        due: Instant::now() + RETRY_DELAY,
+                        ..write
+                    });
+                }
+                failures.push((name, error));
+            }
+        }
+    }
+    failures
+}
+
+fn run_worker(app: AppHandle) {
+    loop {
+        let Ok(pending) = lock_pending() else {
+            return;
+        };
+        let now = Instant::now();
+        match pending.values().map(|write| write.due).min() {
+            Some(due) if due <= now => {
+                drop(pending);
+                for (name, error) in write_due(false) {
+                    let _ = app.emit(
+                        AUTOSAVE_FAILED_EVENT,
+                        AutosaveFailure {
+                            name,
+                            code: error.code(),
+                            message: error.to_string(),
+                        },
+                    );
+                }
+            }
+            Some(due) => {
+                drop(AUTOSAVE.wake.wait_timeout(pending, due - now));
+            }
+            None => {
+                drop(AUTOSAVE.wake.wait(pending));
+            }
+        }
+    }
+}
+
+/// 在 setup 中调用：启动后台写入线程
+pub(crate) fn install(app: &AppHandle) {
+    let app = app.clone();
+    if let Err(error) = std::thread::Builder::new()
+        .name(String::from("autosave"))
+        .spawn(move || run_worker(app))
+    {
+        log::error!("启动自动保存线程失败: {}", error);
+    }
+}
+
+/// 显式保存优先于自动保存：丢弃该文档的待写内容，并等待正在进行的自动保存写完
+pub(crate) fn discard(name: &str) {
+    let _writes = lock_writes();
+    if let Ok(mut pending) = lock_pending() {
+        pending.remove(name);
+    }
+}
+
+/// 同步写出全部待写内容，返回第一个错误；退出前与 flush_autosave 使用
+pub(crate) fn flush_pending() -> Result<(), AppError> {
+    match write_due(true).into_iter().next() {
+        Some((_, error)) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// 同一文档的多次调用只保留最后一次的内容，并从本次调用起重新计时
+/// 恢复文件写入失败只记录日志，不影响自动保存本身；超过 maxDocumentSize 的内容直接返回 TOO_LARGE
+#[tauri::command]
+pub(crate) async fn queue_autosave(name: String, data: String, password: Option<String>) -> Result<(), AppError> {
+    let name = sanitize_graph_name(Some(&name))?;
+    // 超限的内容不进入待写表，也不写恢复文件
+    validate::check_document_size(data.len())?;
+    let due = Instant::now() + debounce_interval();
+    lock_pending()?.insert(name.clone(), PendingWrite { data, password, due });
+    AUTOSAVE.wake.notify_all();
+    run_blocking(move || {
+        if let Err(error) = write_recovery(&name) {
+            log::warn!("写入恢复文件失败 {}: {}", name, error);
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn flush_autosave() -> Result<(), AppError> {
+    run_blocking(flush_pending).await
+}
+
+/// 列出上次异常退出残留的恢复文件，按名称排序；本次运行中仍在待写的文档不算
+#[tauri::command]
+pub(crate) async fn check_recovery() -> Result<Vec<RecoveryEntry>, AppError> {
+    run_blocking(|| {
+        let app_dir = resolve_app_data_dir()?;
+        let entries = match std::fs::read_dir(&app_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::io("读取目录失败", e)),
+        };
+        let pending: Vec<String> = lock_pending()?.keys().cloned().collect();
+        let mut recovered: Vec<RecoveryEntry> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let name = file_name.strip_suffix(RECOVERY_EXTENSION)?;
+                let name = sanitize_required_graph_name(name).ok()?;
+                if pending.contains(&name) {
+                    return None;
+                }
+                read_recovery_entry(name, &entry.path())
+            })
+            .collect();
+        recovered.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(recovered)
+    })
+    .await
+}
+
+/// 返回恢复文件中的文档内容并删除该文件；内容不写回文档，由前端决定是否保存
+#[tauri::command]
+pub(crate) async fn restore_recovery(name: String, password: Option<String>) -> Result<String, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        let _recovery = lock_recovery()?;
+        let path = resolve_recovery_path(&graph_name)?;
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AppError::NotFound(format!("恢复文件不存在: {}", graph_name)))
+            }
+            Err(e) => return Err(AppError::io("读取恢复文件失败", e)),
+        };
+        let contents = decode_graph_bytes(bytes, true, password.as_deref())?;
+        std::fs::remove_file(&path).map_err(|e| AppError::io("删除恢复文件失败", e))?;
+        Ok(contents)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn discard_recovery(name: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        delete_recovery(&graph_name);
+        Ok(())
+    })
+    .await
+}
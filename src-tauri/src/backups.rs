@@ -0,0 +1,349 @@
+// 文档滚动备份：覆盖前把旧文件原样复制到 backups/<name>-YYYYMMDD-HHMMSSmmm.json[.gz]，按保留策略清理旧备份
+// 时间戳精确到毫秒，同一毫秒内的多次备份顺延到下一毫秒，不会互相覆盖；旧版按秒命名的备份仍可识别
+// 备份保留原文件的压缩/加密格式，加密文档不会以明文形式落入备份目录
+// 自动保存按间隔限流：距最新备份不足 AUTOSAVE_BACKUP_INTERVAL 时不再备份，避免频繁写入把备份轮换殆尽
+// 保留策略（设置项 backupRetention）：备份同时超出数量上限与天数上限才删除，未设置的条件视为已超出；
+// 缺省只按数量保留最近 10 份
+
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::{resolve_app_data_dir, run_blocking, sanitize_graph_name, settings, write_file_atomic};
+
+pub(crate) const MAX_BACKUP_COUNT: usize = 10;
+pub(crate) const AUTOSAVE_BACKUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const BACKUP_RETENTION_SETTING: &str = "backupRetention";
+pub(crate) const BACKUP_DIR_NAME: &str = "backups";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+// "YYYYMMDD-HHMMSSmmm" 的固定长度
+const BACKUP_TIMESTAMP_LEN: usize = 18;
+// 旧版按秒命名的 "YYYYMMDD-HHMMSS"
+const LEGACY_BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+const LEGACY_BACKUP_TIMESTAMP_LEN: usize = 15;
+const BACKUP_EXTENSIONS: [&str; 2] = [".json.gz", ".json"];
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BackupRetention {
+    // 始终保留最新的 max_count 份
+    max_count: Option<usize>,
+    // 始终保留 max_age_days 天内的备份
+    max_age_days: Option<u32>,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            max_count: Some(MAX_BACKUP_COUNT),
+            max_age_days: None,
+        }
+    }
+}
+
+impl BackupRetention {
+    pub(crate) fn keep_latest(count: usize) -> Self {
+        Self {
+            max_count: Some(count),
+            max_age_days: None,
+        }
+    }
+
+    /// 两个条件都未设置时会删除全部备份，拒绝；设置的上限至少为 1
+    fn validate(&self) -> Result<(), AppError> {
+        if self.max_count.is_none() && self.max_age_days.is_none() {
+            return Err(AppError::InvalidInput(String::from("备份保留策略至少需要设置数量或天数之一")));
+        }
+        if self.max_count == Some(0) || self.max_age_days == Some(0) {
+            return Err(AppError::InvalidInput(String::from("备份保留的数量与天数须大于 0")));
+        }
+        Ok(())
+    }
+
+    /// newer_count 为比该备份更新的备份数
+    fn should_prune(&self, newer_count: usize, created_at: NaiveDateTime, now: NaiveDateTime) -> bool {
+        let over_count = self.max_count.is_none_or(|max_count| newer_count >= max_count);
+        let over_age = self
+            .max_age_days
+            .is_none_or(|days| now - created_at > TimeDelta::days(i64::from(days)));
+        over_count && over_age
+    }
+}
+
+/// 已保存的策略无效时回退到缺省策略
+pub(crate) fn backup_retention() -> BackupRetention {
+    settings::read_setting::<BackupRetention>(BACKUP_RETENTION_SETTING)
+        .filter(|retention| retention.validate().is_ok())
+        .unwrap_or_default()
+}
+
+pub(crate) struct BackupEntry {
+    pub(crate) file_name: String,
+    pub(crate) created_at: NaiveDateTime,
+    pub(crate) extension: &'static str,
+}
+
+pub(crate) fn resolve_backup_dir() -> Result<PathBuf, AppError> {
+    let backup_dir = resolve_app_data_dir()?.join(BACKUP_DIR_NAME);
+    std::fs::create_dir_all(&backup_dir).map_err(|e| AppError::io("创建备份目录失败", e))?;
+    Ok(backup_dir)
+}
+
+fn split_backup_stem(stem: &str, timestamp_len: usize, format: &str) -> Option<(String, NaiveDateTime)> {
+    if stem.len() <= timestamp_len + 1 || !stem.is_char_boundary(stem.len() - timestamp_len) {
+        return None;
+    }
+    let (head, timestamp) = stem.split_at(stem.len() - timestamp_len);
+    let graph_name = head.strip_suffix('-')?;
+    if graph_name.is_empty() {
+        return None;
+    }
+    let created_at = NaiveDateTime::parse_from_str(timestamp, format).ok()?;
+    Some((String::from(graph_name), created_at))
+}
+
+/// 从备份文件名解析出文档名、时间戳与扩展名；不符合命名规则的文件返回 None
+pub(crate) fn parse_backup_file_name(file_name: &str) -> Option<(String, NaiveDateTime, &'static str)> {
+    let (stem, extension) = BACKUP_EXTENSIONS
+        .iter()
+        .find_map(|extension| file_name.strip_suffix(extension).map(|stem| (stem, *extension)))?;
+    let (graph_name, created_at) = split_backup_stem(stem, BACKUP_TIMESTAMP_LEN, BACKUP_TIMESTAMP_FORMAT)
+        .or_else(|| split_backup_stem(stem, LEGACY_BACKUP_TIMESTAMP_LEN, LEGACY_BACKUP_TIMESTAMP_FORMAT))?;
+    Some((graph_name, created_at, extension))
+}
+
+fn backup_file_name(graph_name: &str, created_at: NaiveDateTime, extension: &str) -> String {
+    format!("{}-{}{}", graph_name, created_at.format(BACKUP_TIMESTAMP_FORMAT), extension)
+}
+
+/// 列出某个文档的全部备份，按时间从旧到新排序
+pub(crate) fn list_backup_entries(graph_name: &str) -> Result<Vec<BackupEntry>, AppError> {
+    let backup_dir = resolve_backup_dir()?;
+    let entries = std::fs::read_dir(&backup_dir).map_err(|e| AppError::io("读取备份目录失败", e))?;
+    let mut backups: Vec<BackupEntry> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let (name, created_at, extension) = parse_backup_file_name(&file_name)?;
+            (name == graph_name).then_some(BackupEntry {
+                file_name,
+                created_at,
+                extension,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.file_name.cmp(&b.file_name)));
+    Ok(backups)
+}
+
+/// 按保留策略删除旧备份，返回删除的文件数；文件名不符合命名规则的文件不在列表中，不会被删除
+fn prune_with_retention(graph_name: &str, retention: BackupRetention) -> Result<usize, AppError> {
+    let backup_dir = resolve_backup_dir()?;
+    let backups = list_backup_entries(graph_name)?;
+    let now = Local::now().naive_local();
+    let mut removed = 0;
+    for (index, entry) in backups.iter().enumerate() {
+        let newer_count = backups.len() - index - 1;
+        if retention.should_prune(newer_count, entry.created_at, now)
+            && std::fs::remove_file(backup_dir.join(&entry.file_name)).is_ok()
+        {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// 备份目录中出现过的全部文档名，包括文档已不存在的残留备份
+fn list_backup_graph_names() -> Result<BTreeSet<String>, AppError> {
+    let backup_dir = resolve_backup_dir()?;
+    let entries = std::fs::read_dir(&backup_dir).map_err(|e| AppError::io("读取备份目录失败", e))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_backup_file_name(&entry.file_name().to_string_lossy()).map(|(name, _, _)| name))
+        .collect())
+}
+
+/// 对所有文档执行 prune_with_retention；单个文档失败时跳过，返回删除的文件总数
+pub(crate) fn prune_all_backups(retention: BackupRetention) -> Result<usize, AppError> {
+    let mut removed = 0;
+    for graph_name in list_backup_graph_names()? {
+        match prune_with_retention(&graph_name, retention) {
+            Ok(count) => removed += count,
+            Err(error) => log::warn!("清理备份失败 {}: {}", graph_name, error),
+        }
+    }
+    Ok(removed)
+}
+
+fn is_within_interval(created_at: NaiveDateTime, interval: Duration, now: NaiveDateTime) -> bool {
+    now - created_at < TimeDelta::from_std(interval).unwrap_or(TimeDelta::MAX)
+}
+
+/// 最新一份备份距今不足 interval
+fn has_recent_backup(graph_name: &str, interval: Duration, now: NaiveDateTime) -> Result<bool, AppError> {
+    Ok(list_backup_entries(graph_name)?
+        .last()
+        .is_some_and(|entry| is_within_interval(entry.created_at, interval, now)))
+}
+
+/// 本次备份的目标路径；min_interval 为 Some 且最新备份距今不足该间隔时返回 None
+fn next_backup_path(
+    graph_name: &str,
+    extension: &str,
+    min_interval: Option<Duration>,
+) -> Result<Option<PathBuf>, AppError> {
+    let now = Local::now().naive_local();
+    if let Some(interval) = min_interval {
+        if has_recent_backup(graph_name, interval, now)? {
+            return Ok(None);
+        }
+    }
+    let backup_dir = resolve_backup_dir()?;
+    let mut created_at = now;
+    let mut backup_path = backup_dir.join(backup_file_name(graph_name, created_at, extension));
+    while backup_path.exists() {
+        created_at += TimeDelta::milliseconds(1);
+        backup_path = backup_dir.join(backup_file_name(graph_name, created_at, extension));
+    }
+    Ok(Some(backup_path))
+}
+
+/// 覆盖文档前调用：旧文件不存在时跳过；min_interval 为 Some 时最新备份距今不足该间隔也跳过；
+/// 清理旧备份失败不影响本次保存
+pub(crate) fn backup_before_overwrite(
+    graph_name: &str,
+    file_path: &Path,
+    min_interval: Option<Duration>,
+) -> Result<(), AppError> {
+    if !file_path.is_file() {
+        return Ok(());
+    }
+    let extension = if file_path.extension().map(|ext| ext == "gz").unwrap_or(false) {
+        ".json.gz"
+    } else {
+        ".json"
+    };
+    let Some(backup_path) = next_backup_path(graph_name, extension, min_interval)? else {
+        return Ok(());
+    };
+    std::fs::copy(file_path, backup_path).map_err(|e| AppError::io("创建备份失败", e))?;
+    let _ = prune_with_retention(graph_name, backup_retention());
+    Ok(())
+}
+
+/// SQLite 存储的文档没有可直接复制的文档文件，覆盖前把当前内容以纯 JSON 写入备份
+pub(crate) fn backup_contents(
+    graph_name: &str,
+    contents: &[u8],
+    min_interval: Option<Duration>,
+) -> Result<(), AppError> {
+    let Some(backup_path) = next_backup_path(graph_name, ".json", min_interval)? else {
+        return Ok(());
+    };
+    write_file_atomic(&backup_path, contents)?;
+    let _ = prune_with_retention(graph_name, backup_retention());
+    Ok(())
+}
+
+pub(crate) fn delete_backups(graph_name: &str) -> Result<(), AppError> {
+    let backup_dir = resolve_backup_dir()?;
+    for entry in list_backup_entries(graph_name)? {
+        let _ = std::fs::remove_file(backup_dir.join(entry.file_name));
+    }
+    Ok(())
+}
+
+pub(crate) fn rename_backups(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    let backup_dir = resolve_backup_dir()?;
+    for entry in list_backup_entries(old_name)? {
+        let next_file_name = backup_file_name(new_name, entry.created_at, entry.extension);
+        let _ = std::fs::rename(backup_dir.join(&entry.file_name), backup_dir.join(next_file_name));
+    }
+    Ok(())
+}
+
+/// 校验备份文件名并定位备份文件，返回 (文档名, 备份路径)
+pub(crate) fn resolve_backup_file(file_name: &str) -> Result<(String, PathBuf), AppError> {
+    let trimmed = file_name.trim();
+    if trimmed.contains("..") || trimmed.chars().any(|ch| std::path::is_separator(ch) || ch == '/' || ch == '\\') {
+        return Err(AppError::InvalidName(format!("备份文件名无效: {}", trimmed)));
+    }
+    let Some((graph_name, _, _)) = parse_backup_file_name(trimmed) else {
+        return Err(AppError::InvalidName(format!("备份文件名无效: {}", trimmed)));
+    };
+    let backup_path = resolve_backup_dir()?.join(trimmed);
+    if !backup_path.is_file() {
+        return Err(AppError::NotFound(format!("备份不存在: {}", trimmed)));
+    }
+    Ok((graph_name, backup_path))
+}
+
+#[tauri::command]
+pub(crate) fn get_backup_retention() -> BackupRetention {
+    backup_retention()
+}
+
+#[tauri::command]
+pub(crate) fn set_backup_retention(retention: BackupRetention) -> Result<(), AppError> {
+    retention.validate()?;
+    settings::write_setting(BACKUP_RETENTION_SETTING, retention)
+}
+
+/// 立即按当前策略清理该文档的备份，返回删除的文件数
+#[tauri::command]
+pub(crate) async fn prune_backups(name: Option<String>) -> Result<usize, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_graph_name(name.as_deref())?;
+        prune_with_retention(&graph_name, backup_retention())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_millisecond_backup_names() {
+        let (name, created_at, extension) = parse_backup_file_name("notes-20260102-030405678.json.gz").unwrap();
+        assert_eq!(name, "notes");
+        assert_eq!(extension, ".json.gz");
+        assert_eq!(created_at.format("%Y-%m-%d %H:%M:%S%.3f").to_string(), "2026-01-02 03:04:05.678");
+    }
+
+    #[test]
+    fn parses_legacy_second_backup_names() {
+        let (name, created_at, extension) = parse_backup_file_name("my-doc-20260102-030405.json").unwrap();
+        assert_eq!(name, "my-doc");
+        assert_eq!(extension, ".json");
+        assert_eq!(created_at.format("%Y-%m-%d %H:%M:%S%.3f").to_string(), "2026-01-02 03:04:05.000");
+    }
+
+    #[test]
+    fn formatted_names_round_trip() {
+        let created_at = NaiveDateTime::parse_from_str("20260102-030405678", BACKUP_TIMESTAMP_FORMAT).unwrap();
+        let file_name = backup_file_name("doc", created_at, ".json");
+        assert_eq!(file_name, "doc-20260102-030405678.json");
+        assert_eq!(parse_backup_file_name(&file_name).map(|(_, at, _)| at), Some(created_at));
+    }
+
+    #[test]
+    fn rejects_names_without_timestamp() {
+        assert!(parse_backup_file_name("doc.json").is_none());
+        assert!(parse_backup_file_name("-20260102-030405678.json").is_none());
+    }
+
+    #[test]
+    fn autosave_interval_throttles_recent_backups() {
+        let created_at = NaiveDateTime::parse_from_str("20260102-030405678", BACKUP_TIMESTAMP_FORMAT).unwrap();
+        let interval = AUTOSAVE_BACKUP_INTERVAL;
+        assert!(is_within_interval(created_at, interval, created_at + TimeDelta::seconds(2)));
+        assert!(!is_within_interval(created_at, interval, created_at + TimeDelta::from_std(interval).unwrap()));
+        // 系统时间回拨时最新备份晚于当前时间，同样视为刚备份过
+        assert!(is_within_interval(created_at, interval, created_at - TimeDelta::seconds(5)));
+    }
+}
@@ -0,0 +1,216 @@
+// 导入时规范化节点与连线 ID：去掉首尾空白，按 on_duplicate 处理重复的节点 ID，连线端点随之改写
+// 所有生成图文档的导入命令在返回前经过这里；表格导入（CSV / Excel）没有节点 ID，不涉及
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+use crate::error::AppError;
+
+/// 重复节点 ID 的处理方式：Error 报错；Rename 为后出现的节点加 `_<n>` 后缀，连线仍指向首个节点；
+/// Merge 把后出现的节点并入首个节点，只补充首个节点缺少的字段（data 内逐项补充）
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DuplicateIdPolicy {
+    #[default]
+    Error,
+    Rename,
+    Merge,
+}
+
+/// 原始 ID 与规范化后的 ID，供调用方核对外部引用
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IdMapping {
+    original: String,
+    id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImportedGraph {
+    // 应用文档结构的 JSON 文本
+    document: String,
+    // 发生变化的节点 ID，按文档顺序
+    renamed_ids: Vec<IdMapping>,
+}
+
+/// 在已用 ID 之外找 `<base>_<n>`，n 从 2 开始
+fn unique_id(base: &str, used: &HashSet<String>) -> String {
+    (2..)
+        .map(|suffix| format!("{}_{}", base, suffix))
+        .find(|candidate| !used.contains(candidate))
+        .unwrap_or_default()
+}
+
+fn merge_node(target: &mut Map<String, Value>, source: Map<String, Value>) {
+    for (key, value) in source {
+        match (target.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(incoming)) if key == "data" => {
+                for (data_key, data_value) in incoming {
+                    existing.entry(data_key).or_insert(data_value);
+                }
+            }
+            (Some(_), _) => {}
+            (None, value) => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+fn trim_field(object: &mut Value, key: &str) {
+    if let Some(Value::String(text)) = object.get_mut(key) {
+        let trimmed = text.trim();
+        if trimmed.len() != text.len() {
+            *text = String::from(trimmed);
+        }
+    }
+}
+
+fn canonicalize_nodes(nodes: &mut Vec<Value>, policy: DuplicateIdPolicy) -> Result<Vec<IdMapping>, AppError> {
+    let mut used: HashSet<String> = nodes
+        .iter()
+        .filter_map(|node| node.get("id").and_then(Value::as_str))
+        .map(|id| String::from(id.trim()))
+        .collect();
+    let mut renamed = Vec::new();
+    let mut position: HashMap<String, usize> = HashMap::new();
+    let mut kept: Vec<Value> = Vec::with_capacity(nodes.len());
+    for mut node in std::mem::take(nodes) {
+        let Some(original) = node.get("id").and_then(Value::as_str).map(String::from) else {
+            kept.push(node);
+            continue;
+        };
+        let trimmed = original.trim();
+        if trimmed.is_empty() {
+            return Err(AppError::InvalidInput(format!("第 {} 个节点的 ID 为空", kept.len() + 1)));
+        }
+        let id = match (position.get(trimmed), policy) {
+            (None, _) => String::from(trimmed),
+            (Some(_), DuplicateIdPolicy::Error) => {
+                return Err(AppError::InvalidInput(format!(
+                    "存在重复的节点 ID: {}（可选择重命名或合并重复的节点）",
+                    trimmed
+                )))
+            }
+            (Some(&index), DuplicateIdPolicy::Merge) => {
+                if let (Some(target), Value::Object(source)) = (kept[index].as_object_mut(), node) {
+                    merge_node(target, source);
+                }
+                continue;
+            }
+            (Some(_), DuplicateIdPolicy::Rename) => {
+                let next = unique_id(trimmed, &used);
+                used.insert(next.clone());
+                next
+            }
+        };
+        if id != original {
+            node["id"] = Value::String(id.clone());
+            renamed.push(IdMapping {
+                original,
+                id: id.clone(),
+            });
+        }
+        position.insert(id, kept.len());
+        kept.push(node);
+    }
+    *nodes = kept;
+    Ok(renamed)
+}
+
+/// 端点去掉空白后仍找不到对应节点的连线直接报错；去掉空白后重复的连线 ID 加后缀区分
+fn canonicalize_edges(edges: &mut [Value], node_ids: &HashSet<String>) -> Result<(), AppError> {
+    // 预先收集全部连线 ID，重命名出的 ID 不会与后面原本就叫该名字的连线冲突
+    let mut used: HashSet<String> = edges
+        .iter()
+        .filter_map(|edge| edge.get("id").and_then(Value::as_str))
+        .map(|id| String::from(id.trim()))
+        .collect();
+    let mut seen = HashSet::new();
+    for edge in edges.iter_mut() {
+        trim_field(edge, "source");
+        trim_field(edge, "target");
+        trim_field(edge, "id");
+        for key in ["source", "target"] {
+            if let Some(endpoint) = edge.get(key).and_then(Value::as_str) {
+                if !node_ids.contains(endpoint) {
+                    return Err(AppError::InvalidInput(format!(
+                        "连线 {} 引用了不存在的节点: {}",
+                        edge.get("id").and_then(Value::as_str).unwrap_or("(无 id)"),
+                        endpoint
+                    )));
+                }
+            }
+        }
+        if let Some(id) = edge.get("id").and_then(Value::as_str).map(String::from) {
+            let id = if seen.contains(&id) {
+                let next = unique_id(&id, &used);
+                used.insert(next.clone());
+                next
+            } else {
+                id
+            };
+            edge["id"] = Value::String(id.clone());
+            seen.insert(id);
+        }
+    }
+    Ok(())
+}
+
+/// 返回 ID 发生变化（去空白或重命名）的节点
+pub(crate) fn canonicalize_ids(document: &mut Value, policy: DuplicateIdPolicy) -> Result<Vec<IdMapping>, AppError> {
+    let renamed = match document.get_mut("nodes").and_then(Value::as_array_mut) {
+        Some(nodes) => canonicalize_nodes(nodes, policy)?,
+        None => Vec::new(),
+    };
+    let node_ids: HashSet<String> = document
+        .get("nodes")
+        .and_then(Value::as_array)
+        .map(|nodes| {
+            nodes
+                .iter()
+                .filter_map(|node| node.get("id").and_then(Value::as_str).map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(edges) = document.get_mut("edges").and_then(Value::as_array_mut) {
+        canonicalize_edges(edges, &node_ids)?;
+    }
+    Ok(renamed)
+}
+
+/// 导入命令的最后一步：规范化 ID 后序列化
+pub(crate) fn finish_import(mut document: Value, policy: DuplicateIdPolicy) -> Result<ImportedGraph, AppError> {
+    let renamed_ids = canonicalize_ids(&mut document, policy)?;
+    let document =
+        serde_json::to_string(&document).map_err(|e| AppError::Serialization(format!("序列化图数据失败: {}", e)))?;
+    Ok(ImportedGraph { document, renamed_ids })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn renamed_edge_ids_skip_later_original_ids() {
+        let mut document = json!({
+            "nodes": [{ "id": "a" }, { "id": "b" }],
+            "edges": [
+                { "id": "e", "source": "a", "target": "b" },
+                { "id": " e ", "source": "a", "target": "b" },
+                { "id": "e_2", "source": "b", "target": "a" },
+            ],
+        });
+        canonicalize_ids(&mut document, DuplicateIdPolicy::Error).unwrap();
+        let ids: Vec<&str> = document["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| edge["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["e", "e_3", "e_2"]);
+    }
+}
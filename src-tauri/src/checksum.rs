@@ -0,0 +1,168 @@
+// 完整性校验：保存文档时在旁边写 `<name>.json.sha256`，内容为磁盘上文档字节的 SHA-256 十六进制摘要
+// 加载时若存在校验文件则比对，不一致说明文件被截断或损坏；功能上线前保存的文档没有校验文件，照常加载
+// 保存时先把新摘要写入 `<name>.json.sha256.pending`，文档替换完成后再改名为正式校验文件；
+// 两次改名之间崩溃时，文档可能是旧版也可能是新版，比对时两个文件中的摘要都接受，不会误报损坏
+
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::storage::{self, StorageBackend};
+use crate::{
+    resolve_app_data_dir, resolve_existing_graph_path, run_blocking, sanitize_required_graph_name, write_file_atomic,
+};
+
+fn resolve_checksum_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}.json.sha256", name)))
+}
+
+fn resolve_pending_checksum_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}.json.sha256.pending", name)))
+}
+
+fn checksum_line(bytes: &[u8]) -> String {
+    format!("{}\n", sha256_hex(bytes))
+}
+
+fn digest_hex(digest: &[u8]) -> String {
+    digest.iter().fold(String::with_capacity(64), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    })
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    digest_hex(Sha256::digest(bytes).as_slice())
+}
+
+/// 写入校验文件；失败时删除旧校验文件，避免过期摘要让刚保存的文档被误判为损坏
+pub(crate) fn write_checksum(name: &str, bytes: &[u8]) -> Result<(), AppError> {
+    let path = resolve_checksum_path(name)?;
+    let result = write_file_atomic(&path, checksum_line(bytes).as_bytes());
+    if result.is_err() {
+        let _ = std::fs::remove_file(&path);
+    }
+    result
+}
+
+/// 替换文档之前调用：写入待生效的摘要，替换完成后调用 commit_checksum，替换失败时调用 discard_checksum
+pub(crate) fn stage_checksum(name: &str, bytes: &[u8]) -> Result<(), AppError> {
+    write_file_atomic(&resolve_pending_checksum_path(name)?, checksum_line(bytes).as_bytes())
+}
+
+/// 待生效的摘要改名为正式校验文件；失败时删除旧校验文件，只留下与新文档一致的待生效摘要
+pub(crate) fn commit_checksum(name: &str) -> Result<(), AppError> {
+    let path = resolve_checksum_path(name)?;
+    let result = std::fs::rename(resolve_pending_checksum_path(name)?, &path)
+        .map_err(|e| AppError::io("更新校验文件失败", e));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&path);
+    }
+    result
+}
+
+pub(crate) fn discard_checksum(name: &str) {
+    if let Ok(path) = resolve_pending_checksum_path(name) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// 文档改由 SQLite 存储后调用，删除不再对应任何文件的校验文件
+pub(crate) fn delete_checksum(name: &str) {
+    for path in [resolve_checksum_path(name), resolve_pending_checksum_path(name)].into_iter().flatten() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+pub(crate) fn has_checksum(name: &str) -> bool {
+    [resolve_checksum_path(name), resolve_pending_checksum_path(name)]
+        .into_iter()
+        .any(|path| path.map(|path| path.is_file()).unwrap_or(false))
+}
+
+/// 没有校验文件时视为通过；校验文件格式与 sha256sum 输出兼容（取第一个字段）
+pub(crate) fn verify_checksum(name: &str, bytes: &[u8]) -> Result<bool, AppError> {
+    verify_checksum_hex(name, &sha256_hex(bytes))
+}
+
+/// 校验文件中的摘要；文件不存在时为 None
+fn read_expected_hex(path: &Path) -> Result<Option<String>, AppError> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(String::from(contents.split_whitespace().next().unwrap_or_default()))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AppError::io("读取校验文件失败", e)),
+    }
+}
+
+fn verify_checksum_hex(name: &str, actual: &str) -> Result<bool, AppError> {
+    let committed = read_expected_hex(&resolve_checksum_path(name)?)?;
+    let pending = read_expected_hex(&resolve_pending_checksum_path(name)?)?;
+    Ok(matches_expected(committed.as_deref(), pending.as_deref(), actual))
+}
+
+/// 两个校验文件都不存在时视为通过，否则与其中任一摘要一致即可
+fn matches_expected(committed: Option<&str>, pending: Option<&str>, actual: &str) -> bool {
+    if committed.is_none() && pending.is_none() {
+        return true;
+    }
+    committed
+        .into_iter()
+        .chain(pending)
+        .any(|expected| expected.eq_ignore_ascii_case(actual))
+}
+
+fn ensure_checksum_hex(name: &str, actual: &str) -> Result<(), AppError> {
+    if verify_checksum_hex(name, actual)? {
+        Ok(())
+    } else {
+        Err(AppError::ChecksumMismatch(format!("文档校验失败，文件可能已损坏或被截断: {}", name)))
+    }
+}
+
+pub(crate) fn ensure_checksum(name: &str, bytes: &[u8]) -> Result<(), AppError> {
+    ensure_checksum_hex(name, &sha256_hex(bytes))
+}
+
+/// 流式读取时增量计算的摘要，读完后调用 ensure_digest 比对
+pub(crate) fn ensure_digest(name: &str, hasher: Sha256) -> Result<(), AppError> {
+    ensure_checksum_hex(name, &digest_hex(hasher.finalize().as_slice()))
+}
+
+/// 不解码、不加载，只比对磁盘字节与校验文件；没有校验文件的旧文档返回 true
+/// SQLite 存储的文档没有校验文件，改为执行 SQLite 自带的完整性检查
+#[tauri::command]
+pub(crate) async fn verify_graph(name: String) -> Result<bool, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        if storage::backend_for(&graph_name) == StorageBackend::Sqlite {
+            return storage::verify_sqlite(&graph_name);
+        }
+        let Some(file_path) = resolve_existing_graph_path(&graph_name)? else {
+            return Err(AppError::NotFound(format!("文档不存在: {}", graph_name)));
+        };
+        let bytes = std::fs::read(&file_path).map_err(|e| AppError::io("读取文件失败", e))?;
+        verify_checksum(&graph_name, &bytes)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_matches_committed_or_pending() {
+        let old = sha256_hex(b"old");
+        let new = sha256_hex(b"new");
+        assert!(matches_expected(None, None, &new));
+        assert!(matches_expected(Some(&old), None, &old));
+        assert!(!matches_expected(Some(&old), None, &new));
+        // 文档已替换但摘要尚未生效：新文档与待生效摘要一致
+        assert!(matches_expected(Some(&old), Some(&new), &new));
+        // 摘要已写入但文档未替换：旧文档与正式摘要一致
+        assert!(matches_expected(Some(&old), Some(&new), &old));
+        assert!(!matches_expected(Some(&old), Some(&new), &sha256_hex(b"corrupt")));
+        assert!(matches_expected(None, Some(&new.to_uppercase()), &new));
+    }
+}
@@ -0,0 +1,62 @@
+// 剪贴板：表格以 TSV（制表符分隔、首行为表头）读写系统剪贴板，可直接与 Excel 等电子表格互相粘贴
+// 含制表符、换行或双引号的单元格按电子表格的约定加双引号，内部的双引号写成两个
+
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::error::AppError;
+use crate::export::render_delimited;
+use crate::import::{build_columns, build_table};
+use crate::run_blocking;
+use crate::table::{parse_table, TableData};
+
+/// 电子表格复制出的内容常省略行尾的空单元格，短行按表头列数补空，多出的单元格丢弃
+pub(crate) fn parse_tsv(text: &str) -> Result<TableData, AppError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    if text.trim().is_empty() {
+        return Ok(TableData::default());
+    }
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| AppError::InvalidInput(format!("解析剪贴板表格失败: {}", e)))?
+        .iter()
+        .map(String::from)
+        .collect();
+    let mut records = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| AppError::InvalidInput(format!("解析剪贴板表格失败: {}", e)))?;
+        let mut cells: Vec<String> = record.iter().map(String::from).collect();
+        cells.resize(headers.len(), String::new());
+        records.push(cells);
+    }
+    Ok(build_table(build_columns(&headers), records))
+}
+
+#[tauri::command]
+pub(crate) async fn copy_table_tsv(app: AppHandle, data: String) -> Result<(), AppError> {
+    let contents = run_blocking(move || {
+        let table = parse_table(&data)?;
+        String::from_utf8(render_delimited(&table, b'\t')?)
+            .map_err(|e| AppError::Serialization(format!("生成 TSV 失败: {}", e)))
+    })
+    .await?;
+    app.clipboard()
+        .write_text(contents)
+        .map_err(|e| AppError::Io(format!("写入剪贴板失败: {}", e)))
+}
+
+/// 剪贴板为空或不含文本时返回空表格而不是错误
+#[tauri::command]
+pub(crate) async fn paste_table_tsv(app: AppHandle) -> Result<String, AppError> {
+    let text = app.clipboard().read_text().unwrap_or_default();
+    run_blocking(move || {
+        let table = parse_tsv(&text)?;
+        serde_json::to_string(&table).map_err(|e| AppError::Serialization(format!("序列化表格失败: {}", e)))
+    })
+    .await
+}
@@ -0,0 +1,178 @@
+// 崩溃报告：命令中发生 panic 时，把 panic 消息、位置、命令名与调用栈写入默认数据目录下的 crashes/，
+// 前端经用户明确同意后通过 get_pending_crashes 展示或提交，clear_crashes 清除
+// 是否记录由设置项 crashReportsEnabled 决定，缺省关闭；报告中只有程序自身的信息，不含任何文档内容，
+// panic 消息也截断到 MAX_MESSAGE_LEN，避免意外带出大段数据
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::error::AppError;
+use crate::{now_ms, resolve_default_data_dir, settings};
+
+const CRASH_DIR_NAME: &str = "crashes";
+const CRASH_REPORTS_SETTING: &str = "crashReportsEnabled";
+const MAX_MESSAGE_LEN: usize = 2000;
+// 只保留最近若干份，反复崩溃时不会堆积
+const MAX_CRASH_FILES: usize = 20;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+// 最近一次调用的命令：异步命令在其他线程上执行，panic 时只能据此推断
+static LAST_COMMAND: Mutex<Option<String>> = Mutex::new(None);
+
+thread_local! {
+    // 同步命令在分发线程上执行，panic 时可以确定是哪个命令
+    static CURRENT_COMMAND: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CrashReport {
+    id: String,
+    timestamp: u64,
+    app_version: String,
+    os: String,
+    arch: String,
+    thread: Option<String>,
+    message: String,
+    location: Option<String>,
+    command: Option<String>,
+    last_command: Option<String>,
+    backtrace: String,
+}
+
+fn resolve_crash_dir() -> Result<PathBuf, AppError> {
+    let dir = resolve_default_data_dir()?.join(CRASH_DIR_NAME);
+    std::fs::create_dir_all(&dir).map_err(|e| AppError::io("创建崩溃报告目录失败", e))?;
+    Ok(dir)
+}
+
+fn truncate_message(message: &str) -> String {
+    match message.char_indices().nth(MAX_MESSAGE_LEN) {
+        Some((index, _)) => format!("{}…", &message[..index]),
+        None => String::from(message),
+    }
+}
+
+/// panic 钩子中调用：不能再 panic，也不能阻塞在可能已被 panic 线程持有的锁上
+fn write_crash_report(info: &std::panic::PanicHookInfo) -> Result<(), AppError> {
+    let timestamp = now_ms();
+    let id = format!("crash-{}-{}", timestamp, std::process::id());
+    let report = CrashReport {
+        app_version: String::from(env!("CARGO_PKG_VERSION")),
+        os: String::from(std::env::consts::OS),
+        arch: String::from(std::env::consts::ARCH),
+        thread: std::thread::current().name().map(String::from),
+        message: truncate_message(info.payload_as_str().unwrap_or("未知 panic")),
+        location: info
+            .location()
+            .map(|location| format!("{}:{}:{}", location.file(), location.line(), location.column())),
+        command: CURRENT_COMMAND
+            .try_with(|current| current.try_borrow().ok().and_then(|current| current.clone()))
+            .ok()
+            .flatten(),
+        last_command: LAST_COMMAND.try_lock().ok().and_then(|last| last.clone()),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        id,
+        timestamp,
+    };
+    let contents = serde_json::to_vec_pretty(&report)
+        .map_err(|e| AppError::Serialization(format!("序列化崩溃报告失败: {}", e)))?;
+    let dir = resolve_crash_dir()?;
+    std::fs::write(dir.join(format!("{}.json", report.id)), contents).map_err(|e| AppError::io("写入崩溃报告失败", e))?;
+    prune_crash_files(&dir);
+    Ok(())
+}
+
+fn list_crash_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect();
+    // 文件名以毫秒时间戳开头，按名称排序即按时间排序
+    files.sort();
+    files
+}
+
+fn prune_crash_files(dir: &Path) {
+    let files = list_crash_files(dir);
+    let overflow = files.len().saturating_sub(MAX_CRASH_FILES);
+    for path in files.iter().take(overflow) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// 在构建应用前调用：读取设置并安装 panic 钩子，原有钩子（输出到 stderr）照常执行
+pub(crate) fn install() {
+    ENABLED.store(settings::read_setting(CRASH_REPORTS_SETTING).unwrap_or(false), Ordering::Relaxed);
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if ENABLED.load(Ordering::Relaxed) {
+            if let Err(error) = write_crash_report(info) {
+                log::error!("写入崩溃报告失败: {}", error);
+            }
+        }
+        previous(info);
+    }));
+}
+
+/// 包装命令分发：记录正在执行的命令名，供崩溃报告使用
+pub(crate) fn track_commands<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return handler(invoke);
+        }
+        let command = String::from(invoke.message.command());
+        if let Ok(mut last) = LAST_COMMAND.lock() {
+            *last = Some(command.clone());
+        }
+        CURRENT_COMMAND.with(|current| *current.borrow_mut() = Some(command));
+        let handled = handler(invoke);
+        CURRENT_COMMAND.with(|current| *current.borrow_mut() = None);
+        handled
+    }
+}
+
+/// 按时间从旧到新返回；无法解析的文件跳过
+#[tauri::command]
+pub(crate) fn get_pending_crashes() -> Result<Vec<CrashReport>, AppError> {
+    Ok(list_crash_files(&resolve_crash_dir()?)
+        .into_iter()
+        .filter_map(|path| std::fs::read(path).ok())
+        .filter_map(|contents| serde_json::from_slice(&contents).ok())
+        .collect())
+}
+
+/// 返回删除的报告数
+#[tauri::command]
+pub(crate) fn clear_crashes() -> Result<usize, AppError> {
+    let mut removed = 0;
+    for path in list_crash_files(&resolve_crash_dir()?) {
+        if std::fs::remove_file(path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub(crate) fn get_crash_reports_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+#[tauri::command]
+pub(crate) fn set_crash_reports_enabled(enabled: bool) -> Result<(), AppError> {
+    settings::write_setting(CRASH_REPORTS_SETTING, enabled)?;
+    ENABLED.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
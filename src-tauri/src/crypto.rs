@@ -0,0 +1,56 @@
+// 文档静态加密：Argon2 由密码派生密钥，ChaCha20-Poly1305 加密内容
+// 文件格式：MAGIC | salt(16) | nonce(12) | ciphertext，加载时据此重建密钥
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+
+use crate::error::AppError;
+
+const MAGIC: &[u8] = b"GTENC\x01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+
+pub(crate) fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Key, AppError> {
+    let mut key = Key::default();
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Io(format!("派生密钥失败: {}", e)))?;
+    Ok(key)
+}
+
+pub(crate) fn encrypt(plaintext: &[u8], password: &str) -> Result<Vec<u8>, AppError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(password, &salt)?;
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = ChaCha20Poly1305::new(&key)
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| AppError::Io(String::from("加密数据失败")))?;
+
+    let mut output = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    output.extend_from_slice(MAGIC);
+    output.extend_from_slice(&salt);
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// 密码错误与数据被篡改都会导致 AEAD 校验失败，统一返回 DecryptionFailed
+pub(crate) fn decrypt(data: &[u8], password: &str) -> Result<Vec<u8>, AppError> {
+    if !is_encrypted(data) || data.len() < HEADER_LEN {
+        return Err(AppError::DecryptionFailed(String::from("加密文件头无效")));
+    }
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = Nonce::from_slice(&data[MAGIC.len() + SALT_LEN..HEADER_LEN]);
+    let key = derive_key(password, salt)?;
+    ChaCha20Poly1305::new(&key)
+        .decrypt(nonce, &data[HEADER_LEN..])
+        .map_err(|_| AppError::DecryptionFailed(String::from("解密失败：密码错误或文件已损坏")))
+}
@@ -0,0 +1,191 @@
+// 自检：用户反馈“无法保存”时一键收集诊断信息，依次检查存储目录可写、设置文件可解析、各文档可读且为有效 JSON，
+// 并报告存储目录所在磁盘的剩余空间；每项检查互不影响，某项失败不会中断后续检查
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::error::AppError;
+use crate::{
+    graph_document_is_encrypted, list_graph_names, read_graph_document, resolve_app_data_dir, run_blocking, settings,
+};
+
+const PROBE_FILE_NAME: &str = ".self-test.tmp";
+// 剩余空间低于该值时磁盘检查判为失败，保存大文档与备份很可能失败
+const LOW_DISK_SPACE_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiagnosticCheck {
+    // dataDir / settings / document / diskSpace
+    kind: &'static str,
+    // document 检查为文档名，其余为 None
+    target: Option<String>,
+    passed: bool,
+    detail: String,
+    // 失败时对应的错误码，前端可据此给出处理建议
+    error_code: Option<&'static str>,
+}
+
+impl DiagnosticCheck {
+    fn pass(kind: &'static str, target: Option<String>, detail: String) -> Self {
+        Self {
+            kind,
+            target,
+            passed: true,
+            detail,
+            error_code: None,
+        }
+    }
+
+    fn fail(kind: &'static str, target: Option<String>, error: &AppError) -> Self {
+        Self {
+            kind,
+            target,
+            passed: false,
+            detail: error.to_string(),
+            error_code: Some(error.code()),
+        }
+    }
+
+    fn from_result(kind: &'static str, target: Option<String>, result: Result<String, AppError>) -> Self {
+        match result {
+            Ok(detail) => Self::pass(kind, target, detail),
+            Err(error) => Self::fail(kind, target, &error),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiagnosticReport {
+    // 全部检查通过
+    passed: bool,
+    storage_dir: Option<String>,
+    // 获取失败时为 None
+    free_bytes: Option<u64>,
+    checks: Vec<DiagnosticCheck>,
+}
+
+#[cfg(unix)]
+fn available_space(path: &Path) -> std::io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // 非特权进程可用的块数
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn available_space(path: &Path) -> std::io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn available_space(_path: &Path) -> std::io::Result<u64> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "当前平台不支持查询磁盘空间"))
+}
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// 写入并删除探测文件；探测文件残留不影响文档，删除失败同样视为不可写
+fn check_data_dir(dir: &Path) -> Result<String, AppError> {
+    if !dir.is_dir() {
+        return Err(AppError::DataDirUnavailable(format!("存储目录不存在: {}", dir.to_string_lossy())));
+    }
+    let probe = dir.join(PROBE_FILE_NAME);
+    std::fs::write(&probe, b"self-test").map_err(|e| AppError::io("存储目录不可写", e))?;
+    std::fs::remove_file(&probe).map_err(|e| AppError::io("删除探测文件失败", e))?;
+    Ok(format!("存储目录可写: {}", dir.to_string_lossy()))
+}
+
+fn check_settings() -> Result<String, AppError> {
+    Ok(match settings::check_settings_file()? {
+        Some(count) => format!("设置文件有效，共 {} 项", count),
+        None => String::from("设置文件不存在，使用默认设置"),
+    })
+}
+
+/// 加密文档没有密码无法校验内容，只确认文件可读取
+fn check_document(name: &str) -> Result<String, AppError> {
+    if graph_document_is_encrypted(name)? {
+        return Ok(String::from("文档已加密，未校验内容"));
+    }
+    let Some(contents) = read_graph_document(name, None)? else {
+        return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+    };
+    serde_json::from_str::<serde_json::Value>(&contents)
+        .map_err(|e| AppError::Serialization(format!("文档不是有效的 JSON: {}", e)))?;
+    Ok(format!("文档可读取，{} 字节", contents.len()))
+}
+
+fn check_disk_space(dir: &Path) -> (Option<u64>, DiagnosticCheck) {
+    match available_space(dir) {
+        Ok(bytes) if bytes < LOW_DISK_SPACE_BYTES => {
+            let error = AppError::Io(format!("磁盘剩余空间不足: {}", format_megabytes(bytes)));
+            (Some(bytes), DiagnosticCheck::fail("diskSpace", None, &error))
+        }
+        Ok(bytes) => (
+            Some(bytes),
+            DiagnosticCheck::pass("diskSpace", None, format!("磁盘剩余空间 {}", format_megabytes(bytes))),
+        ),
+        Err(e) => (None, DiagnosticCheck::fail("diskSpace", None, &AppError::io("查询磁盘空间失败", e))),
+    }
+}
+
+fn run_self_test() -> DiagnosticReport {
+    let mut checks = Vec::new();
+    let mut free_bytes = None;
+    let dir = match resolve_app_data_dir() {
+        Ok(dir) => {
+            checks.push(DiagnosticCheck::from_result("dataDir", None, check_data_dir(&dir)));
+            Some(dir)
+        }
+        Err(error) => {
+            checks.push(DiagnosticCheck::fail("dataDir", None, &error));
+            None
+        }
+    };
+    checks.push(DiagnosticCheck::from_result("settings", None, check_settings()));
+    if let Some(dir) = &dir {
+        match list_graph_names() {
+            Ok(names) => checks.extend(names.into_iter().map(|name| {
+                let result = check_document(&name);
+                DiagnosticCheck::from_result("document", Some(name), result)
+            })),
+            Err(error) => checks.push(DiagnosticCheck::fail("document", None, &error)),
+        }
+        let (bytes, check) = check_disk_space(dir);
+        free_bytes = bytes;
+        checks.push(check);
+    }
+    DiagnosticReport {
+        passed: checks.iter().all(|check| check.passed),
+        storage_dir: dir.map(|dir| dir.to_string_lossy().to_string()),
+        free_bytes,
+        checks,
+    }
+}
+
+/// 诊断面板使用；各项检查的失败记录在报告中，命令本身不因检查失败返回错误
+#[tauri::command]
+pub(crate) async fn self_test() -> Result<DiagnosticReport, AppError> {
+    run_blocking(|| Ok(run_self_test())).await
+}
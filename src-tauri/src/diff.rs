@@ -0,0 +1,172 @@
+// 图数据比较：按 id 匹配节点与连线，与数组顺序无关；输出顺序跟随文档顺序，结果稳定可复现
+// 典型用途是把当前文档与某个备份比较，生成变更摘要
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+use crate::error::AppError;
+use crate::run_blocking;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AttributeChange {
+    path: String,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NodeChange {
+    id: String,
+    changes: Vec<AttributeChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EdgeSummary {
+    id: String,
+    source: String,
+    target: String,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GraphDiff {
+    added_nodes: Vec<String>,
+    removed_nodes: Vec<String>,
+    modified_nodes: Vec<NodeChange>,
+    added_edges: Vec<EdgeSummary>,
+    removed_edges: Vec<EdgeSummary>,
+    modified_edges: Vec<String>,
+}
+
+fn parse_document(data: &str, label: &str) -> Result<Value, AppError> {
+    serde_json::from_str(data).map_err(|e| AppError::Serialization(format!("解析{}失败: {}", label, e)))
+}
+
+fn items<'a>(document: &'a Value, key: &str) -> Vec<&'a Value> {
+    document
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|items| items.iter().collect())
+        .unwrap_or_default()
+}
+
+fn item_id(item: &Value) -> Option<&str> {
+    item.get("id").and_then(Value::as_str)
+}
+
+/// 按 id 建索引，同一 id 出现多次时以第一次为准；没有 id 的元素无法匹配，直接忽略
+fn index_by_id<'a>(items: &[&'a Value]) -> (Vec<&'a str>, HashMap<&'a str, &'a Value>) {
+    let mut order = Vec::new();
+    let mut index = HashMap::new();
+    for item in items {
+        if let Some(id) = item_id(item) {
+            if !index.contains_key(id) {
+                order.push(id);
+                index.insert(id, *item);
+            }
+        }
+    }
+    (order, index)
+}
+
+/// 逐项比较节点属性：顶层字段与 data 内的字段分别比较，data 中的字段以 `data.<key>` 表示
+fn diff_attributes(before: &Value, after: &Value) -> Vec<AttributeChange> {
+    let empty = Map::new();
+    let before_object = before.as_object().unwrap_or(&empty);
+    let after_object = after.as_object().unwrap_or(&empty);
+    let mut changes = Vec::new();
+    let mut keys: Vec<&String> = before_object.keys().chain(after_object.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        if key == "id" {
+            continue;
+        }
+        let (old, new) = (before_object.get(key), after_object.get(key));
+        if old == new {
+            continue;
+        }
+        match (old, new) {
+            (Some(old_data @ Value::Object(_)), Some(new_data @ Value::Object(_))) if key == "data" => {
+                for mut change in diff_attributes(old_data, new_data) {
+                    change.path = format!("data.{}", change.path);
+                    changes.push(change);
+                }
+            }
+            _ => changes.push(AttributeChange {
+                path: key.clone(),
+                before: old.cloned(),
+                after: new.cloned(),
+            }),
+        }
+    }
+    changes
+}
+
+fn edge_summary(id: &str, edge: &Value) -> EdgeSummary {
+    let endpoint = |key: &str| edge.get(key).and_then(Value::as_str).map(String::from).unwrap_or_default();
+    EdgeSummary {
+        id: String::from(id),
+        source: endpoint("source"),
+        target: endpoint("target"),
+    }
+}
+
+pub(crate) fn compute_graph_diff(a: &Value, b: &Value) -> GraphDiff {
+    let mut diff = GraphDiff::default();
+
+    let (a_node_order, a_nodes) = index_by_id(&items(a, "nodes"));
+    let (b_node_order, b_nodes) = index_by_id(&items(b, "nodes"));
+    for id in &a_node_order {
+        match b_nodes.get(id) {
+            None => diff.removed_nodes.push(String::from(*id)),
+            Some(after) => {
+                let changes = diff_attributes(a_nodes[id], after);
+                if !changes.is_empty() {
+                    diff.modified_nodes.push(NodeChange {
+                        id: String::from(*id),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    let a_node_ids: HashSet<&str> = a_node_order.iter().copied().collect();
+    diff.added_nodes = b_node_order
+        .iter()
+        .filter(|id| !a_node_ids.contains(*id))
+        .map(|id| String::from(*id))
+        .collect();
+
+    let (a_edge_order, a_edges) = index_by_id(&items(a, "edges"));
+    let (b_edge_order, b_edges) = index_by_id(&items(b, "edges"));
+    for id in &a_edge_order {
+        match b_edges.get(id) {
+            None => diff.removed_edges.push(edge_summary(id, a_edges[id])),
+            Some(after) if a_edges[id] != *after => diff.modified_edges.push(String::from(*id)),
+            Some(_) => {}
+        }
+    }
+    diff.added_edges = b_edge_order
+        .iter()
+        .filter(|id| !a_edges.contains_key(*id))
+        .map(|id| edge_summary(id, b_edges[id]))
+        .collect();
+
+    diff
+}
+
+/// a 为旧版本、b 为新版本
+#[tauri::command]
+pub(crate) async fn diff_graphs(a: String, b: String) -> Result<GraphDiff, AppError> {
+    run_blocking(move || {
+        let before = parse_document(&a, "旧版本图数据")?;
+        let after = parse_document(&b, "新版本图数据")?;
+        Ok(compute_graph_diff(&before, &after))
+    })
+    .await
+}
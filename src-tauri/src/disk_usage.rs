@@ -0,0 +1,172 @@
+// 数据目录占用统计：遍历 GraphAndTable 目录，按文档汇总主文件、备份与附属文件（tmp/校验/锁/SQLite/历史记录）的大小
+// 读取失败的文件或目录跳过并计入 skipped，不中断统计；符号链接不跟随，避免统计到数据目录之外
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::backups::{self, BACKUP_DIR_NAME};
+use crate::error::AppError;
+use crate::history::HISTORY_DIR_NAME;
+use crate::{list_graph_names, resolve_app_data_dir, run_blocking};
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DocumentUsage {
+    name: String,
+    main_bytes: u64,
+    backup_bytes: u64,
+    backup_count: usize,
+    sidecar_bytes: u64,
+    total_bytes: u64,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StorageUsage {
+    total_bytes: u64,
+    backup_count: usize,
+    documents: Vec<DocumentUsage>,
+    // 不属于任何文档的文件（设置、日志、最近打开列表等）
+    other_bytes: u64,
+    skipped: usize,
+}
+
+enum FileKind {
+    Main,
+    Backup,
+    Sidecar,
+}
+
+struct Walker {
+    root: PathBuf,
+    names: HashSet<String>,
+    documents: BTreeMap<String, DocumentUsage>,
+    usage: StorageUsage,
+}
+
+impl Walker {
+    fn record(&mut self, name: Option<(String, FileKind)>, size: u64) {
+        self.usage.total_bytes += size;
+        let Some((name, kind)) = name else {
+            self.usage.other_bytes += size;
+            return;
+        };
+        let document = self.documents.entry(name).or_default();
+        document.total_bytes += size;
+        match kind {
+            FileKind::Main => document.main_bytes += size,
+            FileKind::Backup => {
+                document.backup_bytes += size;
+                document.backup_count += 1;
+                self.usage.backup_count += 1;
+            }
+            FileKind::Sidecar => document.sidecar_bytes += size,
+        }
+    }
+
+    /// 数据目录顶层的文件：`<name>.json[.gz]` 为主文件，`<name>.json.*`、`<name>.lock` 与 `<name>.recovery` 为附属文件
+    fn classify_top_level(&self, file_name: &str) -> Option<(String, FileKind)> {
+        let known = |name: &str| self.names.contains(name).then(|| String::from(name));
+        if let Some(name) = file_name
+            .strip_suffix(".json.gz")
+            .and_then(known)
+            .or_else(|| file_name.strip_suffix(".json").and_then(known))
+        {
+            return Some((name, FileKind::Main));
+        }
+        if let Some(name) = file_name
+            .strip_suffix(".lock")
+            .or_else(|| file_name.strip_suffix(".recovery"))
+            .and_then(known)
+        {
+            return Some((name, FileKind::Sidecar));
+        }
+        // 文档名本身可能含 ".json."，逐个候选前缀匹配已知文档
+        file_name
+            .match_indices(".json.")
+            .find_map(|(index, _)| known(&file_name[..index]))
+            .map(|name| (name, FileKind::Sidecar))
+    }
+
+    fn classify(&self, path: &Path) -> Option<(String, FileKind)> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let mut components = relative.iter().map(|item| item.to_string_lossy());
+        let first = components.next()?;
+        match components.next() {
+            None => self.classify_top_level(&first),
+            Some(second) if first == BACKUP_DIR_NAME && components.next().is_none() => {
+                backups::parse_backup_file_name(&second).map(|(name, _, _)| (name, FileKind::Backup))
+            }
+            Some(second) if first == HISTORY_DIR_NAME => Some((second.into_owned(), FileKind::Sidecar)),
+            Some(_) => None,
+        }
+    }
+
+    fn walk(&mut self) {
+        let mut pending = vec![self.root.clone()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                self.usage.skipped += 1;
+                continue;
+            };
+            for entry in entries {
+                let Ok(entry) = entry else {
+                    self.usage.skipped += 1;
+                    continue;
+                };
+                // file_type / metadata 均不跟随符号链接
+                let Ok(file_type) = entry.file_type() else {
+                    self.usage.skipped += 1;
+                    continue;
+                };
+                if file_type.is_symlink() {
+                    continue;
+                }
+                let path = entry.path();
+                if file_type.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                match entry.metadata() {
+                    Ok(metadata) => {
+                        let name = self.classify(&path);
+                        self.record(name, metadata.len());
+                    }
+                    Err(_) => self.usage.skipped += 1,
+                }
+            }
+        }
+    }
+}
+
+fn collect_storage_usage() -> Result<StorageUsage, AppError> {
+    let root = resolve_app_data_dir()?;
+    let mut walker = Walker {
+        names: list_graph_names()?.into_iter().collect(),
+        root,
+        documents: BTreeMap::new(),
+        usage: StorageUsage::default(),
+    };
+    walker.walk();
+    let mut usage = walker.usage;
+    usage.documents = walker
+        .documents
+        .into_iter()
+        .map(|(name, document)| DocumentUsage { name, ..document })
+        .collect();
+    // 占用大的文档排在前面
+    usage.documents.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then_with(|| a.name.cmp(&b.name)));
+    Ok(usage)
+}
+
+#[tauri::command]
+pub(crate) async fn storage_usage() -> Result<StorageUsage, AppError> {
+    run_blocking(collect_storage_usage).await
+}
+
+/// 把每个文档的备份裁剪到最近 keep 份，返回删除的文件数
+#[tauri::command]
+pub(crate) async fn prune_all_backups(keep: usize) -> Result<usize, AppError> {
+    run_blocking(move || backups::prune_all_backups(backups::BackupRetention::keep_latest(keep))).await
+}
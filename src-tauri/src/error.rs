@@ -0,0 +1,358 @@
+// 命令层统一错误类型：序列化为 { code, message }（合并冲突额外带 conflicts，悬空连线额外带 edgeIds，
+// 存在环额外带 nodes，文档过大额外带 size 与 limit，列类型不符额外带 violations），前端按稳定的 code 分支处理，message 仅用于展示
+// message 在序列化时按当前语言本地化：非中文界面使用 i18n 消息表中的说明，原始的中文详情放在 detail 字段
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+use crate::i18n;
+use crate::table::TypeViolation;
+
+/// 与 AppError 一一对应的稳定错误码，i18n 的消息表以此为键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorCode {
+    DataDirUnavailable,
+    Io,
+    PermissionDenied,
+    InvalidName,
+    NotFound,
+    AlreadyExists,
+    Serialization,
+    PasswordRequired,
+    DecryptionFailed,
+    InvalidInput,
+    Cancelled,
+    ChecksumMismatch,
+    UpdateNetwork,
+    UpdateSignatureInvalid,
+    UpdateFailed,
+    RevisionConflict,
+    DocumentLocked,
+    MergeConflict,
+    DanglingEdges,
+    TooLarge,
+    Cycle,
+    TypeMismatch,
+}
+
+impl ErrorCode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ErrorCode::DataDirUnavailable => "DATA_DIR_UNAVAILABLE",
+            ErrorCode::Io => "IO_ERROR",
+            ErrorCode::PermissionDenied => "PERMISSION_DENIED",
+            ErrorCode::InvalidName => "INVALID_NAME",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::AlreadyExists => "ALREADY_EXISTS",
+            ErrorCode::Serialization => "SERIALIZATION_ERROR",
+            ErrorCode::PasswordRequired => "PASSWORD_REQUIRED",
+            ErrorCode::DecryptionFailed => "DECRYPTION_FAILED",
+            ErrorCode::InvalidInput => "INVALID_INPUT",
+            ErrorCode::Cancelled => "CANCELLED",
+            ErrorCode::ChecksumMismatch => "CHECKSUM_MISMATCH",
+            ErrorCode::UpdateNetwork => "UPDATE_NETWORK_ERROR",
+            ErrorCode::UpdateSignatureInvalid => "UPDATE_SIGNATURE_INVALID",
+            ErrorCode::UpdateFailed => "UPDATE_FAILED",
+            ErrorCode::RevisionConflict => "REVISION_CONFLICT",
+            ErrorCode::DocumentLocked => "DOCUMENT_LOCKED",
+            ErrorCode::MergeConflict => "MERGE_CONFLICT",
+            ErrorCode::DanglingEdges => "DANGLING_EDGES",
+            ErrorCode::TooLarge => "TOO_LARGE",
+            ErrorCode::Cycle => "CYCLE",
+            ErrorCode::TypeMismatch => "TYPE_MISMATCH",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum AppError {
+    DataDirUnavailable(String),
+    Io(String),
+    PermissionDenied(String),
+    InvalidName(String),
+    NotFound(String),
+    AlreadyExists(String),
+    Serialization(String),
+    PasswordRequired(String),
+    DecryptionFailed(String),
+    InvalidInput(String),
+    Cancelled(String),
+    ChecksumMismatch(String),
+    // 更新失败按原因细分：网络不可达可稍后重试，签名不符说明更新包不可信
+    UpdateNetwork(String),
+    UpdateSignatureInvalid(String),
+    UpdateFailed(String),
+    RevisionConflict(String),
+    DocumentLocked(String),
+    // ids 为冲突的节点/连线 id，序列化为 conflicts 字段供界面逐项展示
+    MergeConflict { message: String, ids: Vec<String> },
+    // ids 为引用了不存在节点的连线 id，序列化为 edgeIds 字段
+    DanglingEdges { message: String, ids: Vec<String> },
+    // size 为实际的 UTF-8 字节数，limit 为设置的上限
+    TooLarge { message: String, size: u64, limit: u64 },
+    // ids 为构成环的节点 id（按环上的顺序），序列化为 nodes 字段
+    Cycle { message: String, ids: Vec<String> },
+    // violations 为不符合列类型的单元格（最多列出前若干条），序列化为 violations 字段
+    TypeMismatch { message: String, violations: Vec<TypeViolation> },
+}
+
+impl AppError {
+    /// 按 io::ErrorKind 归类底层 IO 错误，context 描述正在进行的操作
+    pub(crate) fn io(context: &str, error: std::io::Error) -> Self {
+        let message = format!("{}: {}", context, error);
+        match error.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(message),
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(message),
+            std::io::ErrorKind::AlreadyExists => AppError::AlreadyExists(message),
+            std::io::ErrorKind::InvalidData => AppError::Serialization(message),
+            _ => AppError::Io(message),
+        }
+    }
+
+    pub(crate) fn error_code(&self) -> ErrorCode {
+        match self {
+            AppError::DataDirUnavailable(_) => ErrorCode::DataDirUnavailable,
+            AppError::Io(_) => ErrorCode::Io,
+            AppError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            AppError::InvalidName(_) => ErrorCode::InvalidName,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::AlreadyExists(_) => ErrorCode::AlreadyExists,
+            AppError::Serialization(_) => ErrorCode::Serialization,
+            AppError::PasswordRequired(_) => ErrorCode::PasswordRequired,
+            AppError::DecryptionFailed(_) => ErrorCode::DecryptionFailed,
+            AppError::InvalidInput(_) => ErrorCode::InvalidInput,
+            AppError::Cancelled(_) => ErrorCode::Cancelled,
+            AppError::ChecksumMismatch(_) => ErrorCode::ChecksumMismatch,
+            AppError::UpdateNetwork(_) => ErrorCode::UpdateNetwork,
+            AppError::UpdateSignatureInvalid(_) => ErrorCode::UpdateSignatureInvalid,
+            AppError::UpdateFailed(_) => ErrorCode::UpdateFailed,
+            AppError::RevisionConflict(_) => ErrorCode::RevisionConflict,
+            AppError::DocumentLocked(_) => ErrorCode::DocumentLocked,
+            AppError::MergeConflict { .. } => ErrorCode::MergeConflict,
+            AppError::DanglingEdges { .. } => ErrorCode::DanglingEdges,
+            AppError::TooLarge { .. } => ErrorCode::TooLarge,
+            AppError::Cycle { .. } => ErrorCode::Cycle,
+            AppError::TypeMismatch { .. } => ErrorCode::TypeMismatch,
+        }
+    }
+
+    pub(crate) fn code(&self) -> &'static str {
+        self.error_code().as_str()
+    }
+
+    pub(crate) fn message(&self) -> &str {
+        match self {
+            AppError::DataDirUnavailable(message)
+            | AppError::Io(message)
+            | AppError::PermissionDenied(message)
+            | AppError::InvalidName(message)
+            | AppError::NotFound(message)
+            | AppError::AlreadyExists(message)
+            | AppError::Serialization(message)
+            | AppError::PasswordRequired(message)
+            | AppError::DecryptionFailed(message)
+            | AppError::InvalidInput(message)
+            | AppError::Cancelled(message)
+            | AppError::ChecksumMismatch(message)
+            | AppError::UpdateNetwork(message)
+            | AppError::UpdateSignatureInvalid(message)
+            | AppError::UpdateFailed(message)
+            | AppError::RevisionConflict(message)
+            | AppError::DocumentLocked(message)
+            | AppError::MergeConflict { message, .. }
+            | AppError::DanglingEdges { message, .. }
+            | AppError::TooLarge { message, .. }
+            | AppError::Cycle { message, .. }
+            | AppError::TypeMismatch { message, .. } => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_in(&i18n::current_locale(), serializer)
+    }
+}
+
+impl AppError {
+    /// 按指定语言序列化，Serialize 使用当前生效的语言
+    fn serialize_in<S: Serializer>(&self, locale: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        let ids = match self {
+            AppError::MergeConflict { ids, .. } => Some(("conflicts", ids)),
+            AppError::DanglingEdges { ids, .. } => Some(("edgeIds", ids)),
+            AppError::Cycle { ids, .. } => Some(("nodes", ids)),
+            _ => None,
+        };
+        let sizes = match self {
+            AppError::TooLarge { size, limit, .. } => Some((size, limit)),
+            _ => None,
+        };
+        let violations = match self {
+            AppError::TypeMismatch { violations, .. } => Some(violations),
+            _ => None,
+        };
+        let localized = i18n::localize_error(locale, self.error_code(), self.message());
+        let detail = (localized != self.message()).then(|| self.message());
+        let field_count = 2
+            + usize::from(detail.is_some())
+            + usize::from(ids.is_some())
+            + 2 * usize::from(sizes.is_some())
+            + usize::from(violations.is_some());
+        let mut state = serializer.serialize_struct("AppError", field_count)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", localized)?;
+        if let Some(detail) = detail {
+            state.serialize_field("detail", detail)?;
+        }
+        if let Some((key, ids)) = ids {
+            state.serialize_field(key, ids)?;
+        }
+        if let Some((size, limit)) = sizes {
+            state.serialize_field("size", size)?;
+            state.serialize_field("limit", limit)?;
+        }
+        if let Some(violations) = violations {
+            state.serialize_field("violations", violations)?;
+        }
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn to_json(error: &AppError, locale: &str) -> Value {
+        error.serialize_in(locale, serde_json::value::Serializer).unwrap()
+    }
+
+    #[test]
+    fn message_variants_serialize_code_and_message() {
+        let cases = [
+            (AppError::DataDirUnavailable(String::from("d")), "DATA_DIR_UNAVAILABLE"),
+            (AppError::Io(String::from("d")), "IO_ERROR"),
+            (AppError::PermissionDenied(String::from("d")), "PERMISSION_DENIED"),
+            (AppError::InvalidName(String::from("d")), "INVALID_NAME"),
+            (AppError::NotFound(String::from("d")), "NOT_FOUND"),
+            (AppError::AlreadyExists(String::from("d")), "ALREADY_EXISTS"),
+            (AppError::Serialization(String::from("d")), "SERIALIZATION_ERROR"),
+            (AppError::PasswordRequired(String::from("d")), "PASSWORD_REQUIRED"),
+            (AppError::DecryptionFailed(String::from("d")), "DECRYPTION_FAILED"),
+            (AppError::InvalidInput(String::from("d")), "INVALID_INPUT"),
+            (AppError::Cancelled(String::from("d")), "CANCELLED"),
+            (AppError::ChecksumMismatch(String::from("d")), "CHECKSUM_MISMATCH"),
+            (AppError::UpdateNetwork(String::from("d")), "UPDATE_NETWORK_ERROR"),
+            (AppError::UpdateSignatureInvalid(String::from("d")), "UPDATE_SIGNATURE_INVALID"),
+            (AppError::UpdateFailed(String::from("d")), "UPDATE_FAILED"),
+            (AppError::RevisionConflict(String::from("d")), "REVISION_CONFLICT"),
+            (AppError::DocumentLocked(String::from("d")), "DOCUMENT_LOCKED"),
+        ];
+        for (error, code) in cases {
+            assert_eq!(to_json(&error, "zh-CN"), json!({ "code": code, "message": "d" }));
+        }
+    }
+
+    #[test]
+    fn same_error_differs_between_english_and_chinese() {
+        let error = AppError::NotFound(String::from("文档不存在: a"));
+        let chinese = to_json(&error, "zh");
+        let english = to_json(&error, "en");
+        assert_eq!(chinese["code"], english["code"]);
+        assert_eq!(chinese["message"], "文档不存在: a");
+        assert_eq!(english["message"], "The requested item was not found");
+        assert_ne!(chinese["message"], english["message"]);
+    }
+
+    #[test]
+    fn checksum_mismatch_keeps_chinese_detail_in_english() {
+        let error = AppError::ChecksumMismatch(String::from("文件校验失败: a.json"));
+        assert_eq!(
+            to_json(&error, "zh-CN"),
+            json!({ "code": "CHECKSUM_MISMATCH", "message": "文件校验失败: a.json" })
+        );
+        assert_eq!(
+            to_json(&error, "en-US"),
+            json!({
+                "code": "CHECKSUM_MISMATCH",
+                "message": "Checksum mismatch: the file may be corrupted",
+                "detail": "文件校验失败: a.json",
+            })
+        );
+    }
+
+    #[test]
+    fn too_large_carries_size_and_limit() {
+        let error = AppError::TooLarge {
+            message: String::from("文档过大"),
+            size: 2048,
+            limit: 1024,
+        };
+        assert_eq!(
+            to_json(&error, "zh-CN"),
+            json!({ "code": "TOO_LARGE", "message": "文档过大", "size": 2048, "limit": 1024 })
+        );
+    }
+
+    #[test]
+    fn dangling_edges_carries_edge_ids() {
+        let error = AppError::DanglingEdges {
+            message: String::from("悬空连线"),
+            ids: vec![String::from("e1"), String::from("e2")],
+        };
+        assert_eq!(
+            to_json(&error, "zh-CN"),
+            json!({ "code": "DANGLING_EDGES", "message": "悬空连线", "edgeIds": ["e1", "e2"] })
+        );
+    }
+
+    #[test]
+    fn merge_conflict_carries_conflicts() {
+        let error = AppError::MergeConflict {
+            message: String::from("冲突"),
+            ids: vec![String::from("n1")],
+        };
+        assert_eq!(
+            to_json(&error, "zh-CN"),
+            json!({ "code": "MERGE_CONFLICT", "message": "冲突", "conflicts": ["n1"] })
+        );
+    }
+
+    #[test]
+    fn cycle_carries_nodes() {
+        let error = AppError::Cycle {
+            message: String::from("存在环"),
+            ids: vec![String::from("a"), String::from("b")],
+        };
+        assert_eq!(
+            to_json(&error, "zh-CN"),
+            json!({ "code": "CYCLE", "message": "存在环", "nodes": ["a", "b"] })
+        );
+    }
+
+    #[test]
+    fn type_mismatch_carries_violations() {
+        let error = AppError::TypeMismatch {
+            message: String::from("类型不符"),
+            violations: vec![TypeViolation {
+                row: 3,
+                column: String::from("age"),
+                value: json!("abc"),
+            }],
+        };
+        let value = to_json(&error, "zh-CN");
+        assert_eq!(value["code"], "TYPE_MISMATCH");
+        assert_eq!(value["message"], "类型不符");
+        assert_eq!(value["violations"], json!([{ "row": 3, "column": "age", "value": "abc" }]));
+        assert_eq!(value.as_object().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,626 @@
+// 文档导出：表格写成 CSV / Excel / Markdown，图写成 Graphviz DOT，目标路径来自前端的保存对话框
+
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use tauri::State;
+
+use crate::error::AppError;
+use crate::operations::{CancelToken, OperationRegistry};
+use crate::table::{cell_datetime, cell_number, cell_text, parse_table, ColumnType, TableData};
+use crate::{resolve_external_path, run_blocking, sanitize_graph_name, write_file_atomic};
+
+/// 按 RFC 4180 生成 CSV：含逗号、双引号或换行的字段由 csv crate 加引号并转义；空表只输出表头
+pub(crate) fn render_csv(table: &TableData) -> Result<Vec<u8>, AppError> {
+    render_delimited(table, b',')
+}
+
+/// 按分隔符生成带表头的文本表格；含分隔符、双引号或换行的字段加引号，与电子表格的约定一致
+pub(crate) fn render_delimited(table: &TableData, delimiter: u8) -> Result<Vec<u8>, AppError> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(Vec::new());
+    writer
+        .write_record(table.columns.iter().map(|column| column.header()))
+        .map_err(|e| AppError::Serialization(format!("写入 CSV 表头失败: {}", e)))?;
+    for row_index in 0..table.rows.len() {
+        let record = table
+            .columns
+            .iter()
+            .map(|column| cell_text(table.cell(row_index, column), column.column_type));
+        writer
+            .write_record(record)
+            .map_err(|e| AppError::Serialization(format!("写入 CSV 行失败: {}", e)))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Serialization(format!("生成 CSV 失败: {}", e)))
+}
+
+#[tauri::command]
+pub(crate) async fn export_csv(data: String, dest_path: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let path = resolve_external_path(&dest_path)?;
+        let table = parse_table(&data)?;
+        let contents = render_csv(&table)?;
+        write_file_atomic(&path, &contents)
+    })
+    .await
+}
+
+/// GFM 表格单元格：`\` 与 `|` 转义为 `\\`、`\|`，换行转为 `<br>`，保证每条记录仍占一行；长单元格不截断
+/// 反斜杠同样需要转义，否则原文中的 `\|` 会变成转义的反斜杠加一个分隔符
+fn markdown_cell(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('\n', "<br>")
+}
+
+fn markdown_row(cells: impl Iterator<Item = String>) -> String {
+    let mut line = String::from("|");
+    for cell in cells {
+        let _ = write!(line, " {} |", cell);
+    }
+    line.push('\n');
+    line
+}
+
+/// 对齐分隔行由列声明的 align 决定，未声明或无法识别时使用默认对齐
+fn markdown_separator(align: Option<&str>) -> String {
+    match align.map(str::trim) {
+        Some(align) if align.eq_ignore_ascii_case("left") => String::from(":---"),
+        Some(align) if align.eq_ignore_ascii_case("center") => String::from(":---:"),
+        Some(align) if align.eq_ignore_ascii_case("right") => String::from("---:"),
+        _ => String::from("---"),
+    }
+}
+
+/// 生成 GitHub 风格的管道表格：表头、对齐分隔行与数据行
+pub(crate) fn render_markdown(table: &TableData) -> Result<String, AppError> {
+    if table.columns.is_empty() {
+        return Err(AppError::InvalidInput(String::from("表格没有列，无法导出 Markdown")));
+    }
+    let mut contents = markdown_row(table.columns.iter().map(|column| markdown_cell(column.header())));
+    contents.push_str(&markdown_row(
+        table.columns.iter().map(|column| markdown_separator(column.align.as_deref())),
+    ));
+    for row_index in 0..table.rows.len() {
+        contents.push_str(&markdown_row(table.columns.iter().map(|column| {
+            markdown_cell(&cell_text(table.cell(row_index, column), column.column_type))
+        })));
+    }
+    Ok(contents)
+}
+
+#[tauri::command]
+pub(crate) async fn export_markdown(data: String, dest_path: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let path = resolve_external_path(&dest_path)?;
+        let table = parse_table(&data)?;
+        let contents = render_markdown(&table)?;
+        write_file_atomic(&path, contents.as_bytes())
+    })
+    .await
+}
+
+const XLSX_DATE_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+const XLSX_MAX_COLUMN_WIDTH: f64 = 80.0;
+// 每处理这么多行检查一次取消标记
+const CANCEL_CHECK_INTERVAL: usize = 1000;
+
+/// Excel 日期序列号：以 1899-12-30 为 0，小数部分表示一天内的时间
+fn excel_serial(datetime: NaiveDateTime) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .unwrap_or_default();
+    (datetime - epoch).num_milliseconds() as f64 / 86_400_000.0
+}
+
+/// 列宽按字符估算：非 ASCII（如中文）按两个字符宽计
+fn display_width(text: &str) -> usize {
+    text.chars().map(|ch| if ch.is_ascii() { 1 } else { 2 }).sum()
+}
+
+fn xlsx_error(error: XlsxError) -> AppError {
+    AppError::Serialization(format!("生成 xlsx 失败: {}", error))
+}
+
+/// 单元格写法由列类型决定而不是逐格猜测：文本列中形如数字的值（如带前导零的编号）仍写为文本
+pub(crate) fn render_xlsx(table: &TableData, cancel: &CancelToken) -> Result<Vec<u8>, AppError> {
+    let header_format = Format::new().set_bold();
+    let date_format = Format::new().set_num_format(XLSX_DATE_FORMAT);
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+
+    for (col_index, column) in table.columns.iter().enumerate() {
+        let col = u16::try_from(col_index).map_err(|_| AppError::InvalidInput(String::from("表格列数超出 xlsx 上限")))?;
+        let mut width = display_width(column.header());
+        worksheet
+            .write_string_with_format(0, col, column.header(), &header_format)
+            .map_err(xlsx_error)?;
+
+        for row_index in 0..table.rows.len() {
+            let row = u32::try_from(row_index + 1).map_err(|_| AppError::InvalidInput(String::from("表格行数超出 xlsx 上限")))?;
+            if row_index % CANCEL_CHECK_INTERVAL == 0 {
+                cancel.check()?;
+            }
+            let value = table.cell(row_index, column);
+            if value.is_null() {
+                continue;
+            }
+            let number = (column.column_type == ColumnType::Number).then(|| cell_number(value)).flatten();
+            let datetime = (column.column_type == ColumnType::Date).then(|| cell_datetime(value)).flatten();
+            if let Some(number) = number {
+                width = width.max(display_width(&number.to_string()));
+                worksheet.write_number(row, col, number).map_err(xlsx_error)?;
+            } else if let Some(datetime) = datetime {
+                width = width.max(XLSX_DATE_FORMAT.len());
+                worksheet
+                    .write_number_with_format(row, col, excel_serial(datetime), &date_format)
+                    .map_err(xlsx_error)?;
+            } else {
+                let text = cell_text(value, column.column_type);
+                width = width.max(display_width(&text));
+                worksheet.write_string(row, col, text).map_err(xlsx_error)?;
+            }
+        }
+        worksheet
+            .set_column_width(col, (width as f64 + 2.0).min(XLSX_MAX_COLUMN_WIDTH))
+            .map_err(xlsx_error)?;
+    }
+
+    cancel.check()?;
+    workbook.save_to_buffer().map_err(xlsx_error)
+}
+
+/// 提供 operation_id 时可通过 cancel_operation 取消；取消发生在写盘之前，不会留下目标文件
+#[tauri::command]
+pub(crate) async fn export_xlsx(
+    operations: State<'_, OperationRegistry>,
+    data: String,
+    dest_path: String,
+    operation_id: Option<String>,
+) -> Result<(), AppError> {
+    let (cancel, _guard) = operations.register(operation_id)?;
+    run_blocking(move || {
+        let path = resolve_external_path(&dest_path)?;
+        let table = parse_table(&data)?;
+        let contents = render_xlsx(&table, &cancel)?;
+        cancel.check()?;
+        write_file_atomic(&path, &contents)
+    })
+    .await
+}
+
+const DOT_KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+/// DOT 的裸标识符：字母/下划线/非 ASCII 开头的单词，或数字字面量；关键字不区分大小写，必须加引号
+fn is_plain_dot_id(id: &str) -> bool {
+    let mut chars = id.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if first.is_ascii_alphabetic() || first == '_' || !first.is_ascii() {
+        return chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || !ch.is_ascii())
+            && !DOT_KEYWORDS.iter().any(|keyword| keyword.eq_ignore_ascii_case(id));
+    }
+    let digits = id.strip_prefix('-').unwrap_or(id);
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    (!integer.is_empty() || !fraction.is_empty())
+        && integer.chars().all(|ch| ch.is_ascii_digit())
+        && fraction.chars().all(|ch| ch.is_ascii_digit())
+}
+
+/// 引号字符串中反斜杠与双引号需转义，换行写成 DOT 的 \n
+fn quote_dot(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn dot_id(id: &str) -> String {
+    if is_plain_dot_id(id) {
+        String::from(id)
+    } else {
+        quote_dot(id)
+    }
+}
+
+fn edge_is_directed(edge: &Value) -> bool {
+    edge.get("directed")
+        .or_else(|| edge.get("data").and_then(|data| data.get("directed")))
+        .and_then(Value::as_bool)
+        .unwrap_or(true)
+}
+
+/// 顶层 directed 决定图类型；未提供时只要有一条有向连线就输出 digraph，其中的无向连线加 dir=none
+/// 引用不存在节点的连线被跳过，避免 Graphviz 自动补出无标签的节点
+pub(crate) fn render_dot(data: &str) -> Result<String, AppError> {
+    let document: Value =
+        serde_json::from_str(data).map_err(|e| AppError::Serialization(format!("解析图数据失败: {}", e)))?;
+    let Some(nodes) = document.get("nodes").and_then(Value::as_array) else {
+        return Err(AppError::Serialization(String::from("图数据缺少 nodes 数组")));
+    };
+    let edges = document.get("edges").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+    let directed = document
+        .get("directed")
+        .and_then(Value::as_bool)
+        .unwrap_or_else(|| edges.iter().any(edge_is_directed));
+
+    let mut output = String::from(if directed { "digraph G {\n" } else { "graph G {\n" });
+    let mut node_ids = HashSet::new();
+    for node in nodes {
+        let Some(id) = node.get("id").and_then(Value::as_str) else {
+            continue;
+        };
+        if !node_ids.insert(id) {
+            continue;
+        }
+        let label = node
+            .get("data")
+            .and_then(|data| data.get("label"))
+            .and_then(Value::as_str)
+            .unwrap_or(id);
+        let _ = writeln!(output, "  {} [label={}];", dot_id(id), quote_dot(label));
+    }
+    for edge in edges {
+        let (Some(source), Some(target)) = (
+            edge.get("source").and_then(Value::as_str),
+            edge.get("target").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        if !node_ids.contains(source) || !node_ids.contains(target) {
+            continue;
+        }
+        let mut attributes = Vec::new();
+        let label = edge
+            .get("label")
+            .or_else(|| edge.get("data").and_then(|data| data.get("label")))
+            .and_then(Value::as_str);
+        if let Some(label) = label.filter(|label| !label.is_empty()) {
+            attributes.push(format!("label={}", quote_dot(label)));
+        }
+        let weight = edge
+            .get("weight")
+            .or_else(|| edge.get("data").and_then(|data| data.get("weight")))
+            .and_then(Value::as_f64);
+        if let Some(weight) = weight {
+            attributes.push(format!("weight={}", weight));
+        }
+        if directed && !edge_is_directed(edge) {
+            attributes.push(String::from("dir=none"));
+        }
+        let _ = write!(
+            output,
+            "  {} {} {}",
+            dot_id(source),
+            if directed { "->" } else { "--" },
+            dot_id(target)
+        );
+        if !attributes.is_empty() {
+            let _ = write!(output, " [{}]", attributes.join(", "));
+        }
+        output.push_str(";\n");
+    }
+    output.push_str("}\n");
+    Ok(output)
+}
+
+#[tauri::command]
+pub(crate) async fn export_dot(data: String, dest_path: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let path = resolve_external_path(&dest_path)?;
+        let contents = render_dot(&data)?;
+        write_file_atomic(&path, contents.as_bytes())
+    })
+    .await
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ExportFormat {
+    Csv,
+    Xlsx,
+    Markdown,
+    Dot,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Dot => "dot",
+        }
+    }
+
+    /// 与单独导出命令使用同一套生成函数，输出保持一致
+    fn render(self, data: &str, cancel: &CancelToken) -> Result<Vec<u8>, AppError> {
+        match self {
+            ExportFormat::Csv => render_csv(&parse_table(data)?),
+            ExportFormat::Xlsx => render_xlsx(&parse_table(data)?, cancel),
+            ExportFormat::Markdown => render_markdown(&parse_table(data)?).map(String::into_bytes),
+            ExportFormat::Dot => render_dot(data).map(String::into_bytes),
+        }
+    }
+}
+
+/// 每种格式一项：成功时给出写入的路径，失败时给出该格式的错误
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BundleEntry {
+    format: ExportFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<AppError>,
+}
+
+fn export_bundle_files(
+    data: &str,
+    dest_dir: &str,
+    name: Option<&str>,
+    formats: &[ExportFormat],
+    cancel: &CancelToken,
+) -> Result<Vec<BundleEntry>, AppError> {
+    let dir = resolve_external_path(dest_dir)?;
+    if !dir.is_dir() {
+        return Err(AppError::NotFound(format!("目标目录不存在: {}", dir.display())));
+    }
+    let stem = sanitize_graph_name(name)?;
+    let mut seen = Vec::new();
+    let mut entries = Vec::new();
+    for &format in formats {
+        if seen.contains(&format) {
+            continue;
+        }
+        seen.push(format);
+        cancel.check()?;
+        let path = dir.join(format!("{}.{}", stem, format.extension()));
+        let result = format.render(data, cancel).and_then(|contents| {
+            // 取消发生在写盘之前时不留下这一格式的文件
+            cancel.check()?;
+            write_file_atomic(&path, &contents)
+        });
+        match result {
+            Ok(()) => entries.push(BundleEntry {
+                format,
+                path: Some(path.to_string_lossy().to_string()),
+                error: None,
+            }),
+            Err(error @ AppError::Cancelled(_)) => return Err(error),
+            Err(error) => entries.push(BundleEntry {
+                format,
+                path: None,
+                error: Some(error),
+            }),
+        }
+    }
+    Ok(entries)
+}
+
+/// 一次导出多种格式到 dest_dir，文件名为 `<文档名>.<扩展名>`；某种格式失败不影响其余格式，
+/// 错误记录在对应项中。取消时中止整批，已写出的文件保留
+#[tauri::command]
+pub(crate) async fn export_bundle(
+    operations: State<'_, OperationRegistry>,
+    data: String,
+    dest_dir: String,
+    formats: Vec<ExportFormat>,
+    name: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Vec<BundleEntry>, AppError> {
+    let (cancel, _guard) = operations.register(operation_id)?;
+    run_blocking(move || export_bundle_files(&data, &dest_dir, name.as_deref(), &formats, &cancel)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(json: &str) -> TableData {
+        parse_table(json).unwrap()
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_quotes_and_commas() {
+        let table = table(
+            r#"{"table": {"columns": [{"id": "name"}, {"id": "note", "title": "备注, 说明"}],
+                "rows": [{"name": "He said \"hi\", then left", "note": "line1\nline2"}, {"name": "plain"}]}}"#,
+        );
+        let csv = String::from_utf8(render_csv(&table).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "name,\"备注, 说明\"\r\n\"He said \"\"hi\"\", then left\",\"line1\nline2\"\r\nplain,\r\n"
+        );
+    }
+
+    #[test]
+    fn csv_of_empty_table_has_only_header() {
+        let table = table(r#"{"table": {"columns": [{"id": "a"}, {"id": "b"}], "rows": []}}"#);
+        assert_eq!(render_csv(&table).unwrap(), b"a,b\r\n");
+    }
+
+    /// 按 DOT 的词法切分：裸标识符、数字、引号字符串（只允许 \" 与 \\ 等转义）、运算符与标点，
+    /// 遇到未闭合的字符串、无法识别的字符或不配对的括号时报错
+    fn dot_tokens(source: &str) -> Result<Vec<String>, String> {
+        let mut tokens = Vec::new();
+        let mut depth = Vec::new();
+        let mut chars = source.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                ' ' | '\t' | '\n' => {}
+                '"' => {
+                    let mut token = String::from('"');
+                    loop {
+                        match chars.next() {
+                            Some('\n') | None => return Err(format!("未闭合的字符串: {}", token)),
+                            Some('\\') => {
+                                token.push('\\');
+                                token.push(chars.next().ok_or("字符串以反斜杠结尾")?);
+                            }
+                            Some('"') => break,
+                            Some(other) => token.push(other),
+                        }
+                    }
+                    token.push('"');
+                    tokens.push(token);
+                }
+                '{' | '[' => {
+                    depth.push(ch);
+                    tokens.push(ch.to_string());
+                }
+                '}' | ']' => {
+                    let expected = if ch == '}' { '{' } else { '[' };
+                    if depth.pop() != Some(expected) {
+                        return Err(format!("括号不配对: {}", ch));
+                    }
+                    tokens.push(ch.to_string());
+                }
+                ';' | ',' | '=' => tokens.push(ch.to_string()),
+                '-' if matches!(chars.peek(), Some('>') | Some('-')) => {
+                    tokens.push(format!("-{}", chars.next().unwrap_or_default()));
+                }
+                _ if ch.is_alphanumeric() || ch == '_' || ch == '.' || ch == '-' => {
+                    let mut token = ch.to_string();
+                    while let Some(&next) = chars.peek() {
+                        if !(next.is_alphanumeric() || next == '_' || next == '.') {
+                            break;
+                        }
+                        token.push(next);
+                        chars.next();
+                    }
+                    tokens.push(token);
+                }
+                _ => return Err(format!("无法识别的字符: {:?}", ch)),
+            }
+        }
+        if !depth.is_empty() {
+            return Err(String::from("括号未闭合"));
+        }
+        Ok(tokens)
+    }
+
+    #[test]
+    fn dot_quotes_ids_and_escapes_labels() {
+        let data = r#"{
+            "nodes": [
+                {"id": "plain_id", "data": {"label": "He said \"hi\""}},
+                {"id": "has space", "data": {"label": "C:\\temp\nline2"}},
+                {"id": "node", "data": {}},
+                {"id": "12.5"}
+            ],
+            "edges": [
+                {"source": "plain_id", "target": "has space", "label": "a \"b\""},
+                {"source": "node", "target": "12.5", "directed": false, "weight": 2},
+                {"source": "plain_id", "target": "missing"}
+            ]
+        }"#;
+        let dot = render_dot(data).unwrap();
+        assert_eq!(
+            dot,
+            concat!(
+                "digraph G {\n",
+                "  plain_id [label=\"He said \\\"hi\\\"\"];\n",
+                "  \"has space\" [label=\"C:\\\\temp\\nline2\"];\n",
+                "  \"node\" [label=\"node\"];\n",
+                "  12.5 [label=\"12.5\"];\n",
+                "  plain_id -> \"has space\" [label=\"a \\\"b\\\"\"];\n",
+                "  \"node\" -> 12.5 [weight=2, dir=none];\n",
+                "}\n",
+            )
+        );
+        let tokens = dot_tokens(&dot).unwrap();
+        assert_eq!(tokens.first().map(String::as_str), Some("digraph"));
+        assert_eq!(tokens.iter().filter(|token| *token == ";").count(), 6);
+    }
+
+    #[test]
+    fn dot_uses_graph_for_undirected_documents() {
+        let data = r#"{"directed": false, "nodes": [{"id": "a"}, {"id": "b"}], "edges": [{"source": "a", "target": "b"}]}"#;
+        let dot = render_dot(data).unwrap();
+        assert_eq!(dot, "graph G {\n  a [label=\"a\"];\n  b [label=\"b\"];\n  a -- b;\n}\n");
+        assert!(dot_tokens(&dot).is_ok());
+    }
+
+    #[test]
+    fn dot_tokenizer_rejects_broken_output() {
+        assert!(dot_tokens("digraph G { a [label=\"open]; }").is_err());
+        assert!(dot_tokens("digraph G { a [label=\"x\"; }").is_err());
+    }
+
+    /// 按 GFM 的规则切分一行管道表格：`\` 转义下一个字符，未转义的 `|` 为分隔符；返回首尾管道之间的各单元格
+    fn markdown_cells(line: &str) -> Vec<String> {
+        let mut cells = vec![String::new()];
+        let mut chars = line.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    let cell = cells.last_mut().unwrap();
+                    cell.push(ch);
+                    cell.extend(chars.next());
+                }
+                '|' => cells.push(String::new()),
+                _ => cells.last_mut().unwrap().push(ch),
+            }
+        }
+        assert!(cells.len() >= 2 && cells[0].is_empty() && cells[cells.len() - 1].is_empty(), "{}", line);
+        cells[1..cells.len() - 1].iter().map(|cell| String::from(cell.trim())).collect()
+    }
+
+    /// 每条记录占一行，且每行的单元格数与表头一致
+    fn assert_markdown_table(markdown: &str, rows: usize, columns: usize) -> Vec<Vec<String>> {
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines.len(), rows + 2, "{}", markdown);
+        lines
+            .iter()
+            .map(|line| {
+                let cells = markdown_cells(line);
+                assert_eq!(cells.len(), columns, "{}", line);
+                cells
+            })
+            .collect()
+    }
+
+    #[test]
+    fn markdown_escapes_pipes_in_cells() {
+        let table = table(
+            r#"{"table": {"columns": [{"id": "a", "title": "A|B"}, {"id": "b"}],
+                "rows": [{"a": "x | y", "b": "C:\\dir\\|pipe"}]}}"#,
+        );
+        let markdown = render_markdown(&table).unwrap();
+        assert_eq!(markdown, "| A\\|B | b |\n| --- | --- |\n| x \\| y | C:\\\\dir\\\\\\|pipe |\n");
+        let rows = assert_markdown_table(&markdown, 1, 2);
+        assert_eq!(rows[2], ["x \\| y", "C:\\\\dir\\\\\\|pipe"]);
+    }
+
+    #[test]
+    fn markdown_keeps_multiline_cells_on_one_row() {
+        let table = table(
+            r#"{"table": {"columns": [{"id": "a"}, {"id": "b", "align": "right"}],
+                "rows": [{"a": "line1\nline2\r\nline3", "b": "end\r"}]}}"#,
+        );
+        let markdown = render_markdown(&table).unwrap();
+        assert_eq!(markdown, "| a | b |\n| --- | ---: |\n| line1<br>line2<br>line3 | end<br> |\n");
+        assert_markdown_table(&markdown, 1, 2);
+    }
+}
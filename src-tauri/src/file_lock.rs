@@ -0,0 +1,220 @@
+// 跨进程文档锁：保存与补丁期间对数据目录下的 `<name>.lock` 加操作系统的排他锁，并写入持有者信息
+// 同步文件夹或不支持文件锁的文件系统上，操作系统锁无法跨机器生效，此时依据持有者信息判断；
+// 持有者信息超过 STALE_LOCK_TIMEOUT 未释放（进程崩溃）即视为过期，可直接接管
+// FileLock 在 drop 时清空持有者信息并解锁，保存出错、提前返回或 panic 都不会遗留锁
+// 退出时 release_all 等待进行中的保存释放锁，超时仍未释放的强制清空持有者信息并解锁
+// 文档删除或重命名后，旧名称的锁文件由 FileLock::remove 删除，新名称的锁文件在下次加锁时创建
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+use crate::{now_ms, resolve_app_data_dir};
+
+const LOCK_FILE_SUFFIX: &str = ".lock";
+// 等待其他进程保存完成的最长时间
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+const STALE_LOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+// 本进程当前持有的文档锁，判断 locked_by_other 时排除自己；保存锁文件句柄的副本供退出时强制释放
+static HELD: LazyLock<Mutex<HashMap<String, File>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct LockHolder {
+    pid: u32,
+    host: String,
+    acquired_at: u64,
+}
+
+impl LockHolder {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            host: host_name(),
+            acquired_at: now_ms(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        if self.host.is_empty() {
+            format!("进程 {}", self.pid)
+        } else {
+            format!("{} 上的进程 {}", self.host, self.pid)
+        }
+    }
+}
+
+pub(crate) struct FileLock {
+    name: String,
+    file: File,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+        if let Ok(mut held) = HELD.lock() {
+            held.remove(&self.name);
+        }
+    }
+}
+
+impl FileLock {
+    /// 释放锁并删除锁文件；Windows 上无法删除仍打开着的文件，因此先关闭句柄再删除
+    pub(crate) fn remove(self) {
+        let path = resolve_lock_path(&self.name);
+        drop(self);
+        if let Ok(path) = path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default()
+}
+
+fn resolve_lock_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}{}", name, LOCK_FILE_SUFFIX)))
+}
+
+fn open_lock_file(name: &str) -> Result<File, AppError> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(resolve_lock_path(name)?)
+        .map_err(|e| AppError::io("打开文档锁失败", e))
+}
+
+fn read_holder(file: &mut File) -> Option<LockHolder> {
+    let mut contents = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut contents).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_holder(file: &mut File, holder: &LockHolder) -> Result<(), AppError> {
+    let contents =
+        serde_json::to_vec(holder).map_err(|e| AppError::Serialization(format!("序列化文档锁失败: {}", e)))?;
+    file.set_len(0)
+        .and_then(|_| file.seek(SeekFrom::Start(0)))
+        .and_then(|_| file.write_all(&contents))
+        .and_then(|_| file.flush())
+        .map_err(|e| AppError::io("写入文档锁失败", e))
+}
+
+/// 锁文件中记录的其他持有者是否仍然有效；已拿到操作系统锁时，同一台机器上的记录必然来自已退出的进程
+fn live_other_holder(file: &mut File, os_locked: bool) -> Option<LockHolder> {
+    let holder = read_holder(file)?;
+    let current = LockHolder::current();
+    if holder.pid == current.pid && holder.host == current.host {
+        return None;
+    }
+    if os_locked && holder.host == current.host {
+        return None;
+    }
+    let age = Duration::from_millis(now_ms().saturating_sub(holder.acquired_at));
+    (age < STALE_LOCK_TIMEOUT).then_some(holder)
+}
+
+/// 不支持文件锁的文件系统上只依据持有者信息判断
+fn try_os_lock(file: &File) -> Result<Option<bool>, AppError> {
+    match file.try_lock() {
+        Ok(()) => Ok(Some(true)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(error)) if error.kind() == std::io::ErrorKind::Unsupported => Ok(Some(false)),
+        Err(TryLockError::Error(error)) => Err(AppError::io("锁定文档失败", error)),
+    }
+}
+
+/// 获取文档锁，其他进程持有时最多等待 ACQUIRE_TIMEOUT，超时返回 DOCUMENT_LOCKED
+pub(crate) fn acquire(name: &str) -> Result<FileLock, AppError> {
+    let mut file = open_lock_file(name)?;
+    let deadline = Instant::now() + ACQUIRE_TIMEOUT;
+    loop {
+        let blocker = match try_os_lock(&file)? {
+            None => None,
+            Some(os_locked) => match live_other_holder(&mut file, os_locked) {
+                None => break,
+                Some(holder) => {
+                    let _ = file.unlock();
+                    Some(holder)
+                }
+            },
+        };
+        if Instant::now() >= deadline {
+            let holder = blocker.map(|holder| holder.describe()).unwrap_or_else(|| String::from("其他进程"));
+            return Err(AppError::DocumentLocked(format!("文档正被{}使用: {}", holder, name)));
+        }
+        std::thread::sleep(RETRY_INTERVAL);
+    }
+
+    let handle = file.try_clone().map_err(|e| AppError::io("打开文档锁失败", e))?;
+    let mut lock = FileLock {
+        name: String::from(name),
+        file,
+    };
+    if let Ok(mut held) = HELD.lock() {
+        held.insert(String::from(name), handle);
+    }
+    // 写入失败时 lock 随 ? 返回被 drop，锁同样会释放
+    write_holder(&mut lock.file, &LockHolder::current())?;
+    Ok(lock)
+}
+
+/// 加载时提示用：文档当前是否被其他进程（或其他机器上的实例）锁定；检测失败时按未锁定处理
+pub(crate) fn locked_by_other(name: &str) -> bool {
+    if HELD.lock().map(|held| held.contains_key(name)).unwrap_or(false) {
+        return false;
+    }
+    let Ok(path) = resolve_lock_path(name) else {
+        return false;
+    };
+    if !path.is_file() {
+        return false;
+    }
+    let Ok(mut file) = OpenOptions::new().read(true).write(true).open(path) else {
+        return false;
+    };
+    match try_os_lock(&file) {
+        Ok(None) => true,
+        Ok(Some(os_locked)) => {
+            let locked = live_other_holder(&mut file, os_locked).is_some();
+            if os_locked {
+                let _ = file.unlock();
+            }
+            locked
+        }
+        Err(_) => false,
+    }
+}
+
+/// 退出前调用：最多等待 wait 让进行中的保存自行释放锁，之后仍持有的锁清空持有者信息并解锁，返回被强制释放的文档名
+/// 进程退出时操作系统锁本就会释放，但同步文件夹上的持有者信息会残留到过期为止，其他机器在此期间无法保存
+pub(crate) fn release_all(wait: Duration) -> Vec<String> {
+    let deadline = Instant::now() + wait;
+    while HELD.lock().map(|held| !held.is_empty()).unwrap_or(false) && Instant::now() < deadline {
+        std::thread::sleep(RETRY_INTERVAL);
+    }
+    let Ok(held) = HELD.lock() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = held.keys().cloned().collect();
+    names.sort();
+    for file in held.values() {
+        let _ = file.set_len(0);
+        let _ = file.unlock();
+    }
+    names
+}
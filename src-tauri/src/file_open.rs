@@ -0,0 +1,122 @@
+// 从系统打开文档：拖放到窗口、双击关联的 .gtgraph 文件（Windows / Linux 通过启动参数，macOS 通过 Opened 事件），
+// 以及单实例转发的第二次启动，统一读取后发 open-file 事件，载荷为 { path, content } 或 { path, error }
+// 前端就绪前（启动阶段）产生的结果先暂存，前端调用 take_pending_open_files 取走，之后改为直接发事件
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, Runtime, WebviewWindow, WindowEvent};
+
+use crate::error::AppError;
+use crate::load_external_graph;
+
+pub(crate) const OPEN_FILE_EVENT: &str = "open-file";
+pub(crate) const GRAPH_FILE_EXTENSION: &str = "gtgraph";
+
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+static PENDING: LazyLock<Mutex<Vec<OpenFilePayload>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OpenFilePayload {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<AppError>,
+}
+
+pub(crate) fn has_graph_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(GRAPH_FILE_EXTENSION))
+}
+
+fn read_dropped_file(path: &Path) -> Result<String, AppError> {
+    if path.is_dir() {
+        return Err(AppError::InvalidInput(format!("不能打开文件夹: {}", path.to_string_lossy())));
+    }
+    if !has_graph_extension(path) {
+        return Err(AppError::InvalidInput(format!(
+            "不支持的文件类型，只能打开 .{} 文件: {}",
+            GRAPH_FILE_EXTENSION,
+            path.to_string_lossy()
+        )));
+    }
+    load_external_graph(&path.to_string_lossy())
+}
+
+fn open_payload(path: &Path) -> OpenFilePayload {
+    let (content, error) = match read_dropped_file(path) {
+        Ok(content) => (Some(content), None),
+        Err(error) => {
+            log::warn!("打开文件失败: path={} code={} error={}", path.to_string_lossy(), error.code(), error);
+            (None, Some(error))
+        }
+    };
+    OpenFilePayload {
+        path: path.to_string_lossy().to_string(),
+        content,
+        error,
+    }
+}
+
+/// target 为空时发给所有窗口（启动参数、单实例转发），拖放则只发给接收拖放的窗口
+fn deliver<R: Runtime>(app: &AppHandle<R>, target: Option<&str>, payload: OpenFilePayload) {
+    if !FRONTEND_READY.load(Ordering::Acquire) {
+        if let Ok(mut pending) = PENDING.lock() {
+            // 加锁后再确认一次，避免与 take_pending_open_files 交错时丢失
+            if !FRONTEND_READY.load(Ordering::Acquire) {
+                pending.push(payload);
+                return;
+            }
+        }
+    }
+    let _ = match target {
+        Some(label) => app.emit_to(label, OPEN_FILE_EVENT, payload),
+        None => app.emit(OPEN_FILE_EVENT, payload),
+    };
+}
+
+/// 在后台线程读取文件，避免大文件阻塞事件循环
+pub(crate) fn open_paths<R: Runtime>(app: &AppHandle<R>, target: Option<String>, paths: Vec<PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        for path in paths {
+            deliver(&app, target.as_deref(), open_payload(&path));
+        }
+    });
+}
+
+/// 启动参数中只处理带 .gtgraph 扩展名的项，其余（命令行开关等）忽略
+pub(crate) fn graph_paths_from_args<I: IntoIterator<Item = String>>(args: I) -> Vec<PathBuf> {
+    args.into_iter()
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute() && has_graph_extension(path))
+        .collect()
+}
+
+/// 在 setup 中为每个窗口调用：把拖放到窗口上的文件交给 open-file 流程
+pub(crate) fn install<R: Runtime>(window: &WebviewWindow<R>) {
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    window.on_window_event(move |event| {
+        if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+            open_paths(&app, Some(label.clone()), paths.clone());
+        }
+    });
+}
+
+/// 前端注册好 open-file 监听后调用：取走启动阶段暂存的结果，此后的结果直接以事件送达
+#[tauri::command]
+pub(crate) fn take_pending_open_files() -> Vec<OpenFilePayload> {
+    let Ok(mut pending) = PENDING.lock() else {
+        return Vec::new();
+    };
+    FRONTEND_READY.store(true, Ordering::Release);
+    std::mem::take(&mut *pending)
+}
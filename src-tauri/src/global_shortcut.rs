@@ -0,0 +1,115 @@
+// 全局快捷键：按下后切换主窗口的显示/隐藏，缺省为 CommandOrControl+Shift+G
+// 快捷键保存在设置项 globalShortcut 中，启动时重新注册；空字符串表示用户已关闭快捷键，未设置时使用缺省值
+
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::error::AppError;
+use crate::{run_blocking, settings, tray};
+
+const GLOBAL_SHORTCUT_SETTING: &str = "globalShortcut";
+const DEFAULT_GLOBAL_SHORTCUT: &str = "CommandOrControl+Shift+G";
+
+// 当前已注册的快捷键，更换或关闭时据此注销
+static CURRENT_SHORTCUT: Mutex<Option<Shortcut>> = Mutex::new(None);
+
+fn parse_accelerator(accel: &str) -> Result<Shortcut, AppError> {
+    Shortcut::from_str(accel.trim()).map_err(|e| AppError::InvalidInput(format!("快捷键格式无效: {}（{}）", accel, e)))
+}
+
+fn stored_accelerator() -> Option<String> {
+    let accel = settings::read_setting::<String>(GLOBAL_SHORTCUT_SETTING)
+        .unwrap_or_else(|| String::from(DEFAULT_GLOBAL_SHORTCUT));
+    (!accel.trim().is_empty()).then_some(accel)
+}
+
+fn lock_current() -> Result<std::sync::MutexGuard<'static, Option<Shortcut>>, AppError> {
+    CURRENT_SHORTCUT
+        .lock()
+        .map_err(|_| AppError::Io(String::from("快捷键状态不可用")))
+}
+
+fn register(app: &AppHandle, shortcut: Shortcut, accel: &str) -> Result<(), AppError> {
+    app.global_shortcut().register(shortcut).map_err(|e| {
+        AppError::InvalidInput(format!("注册快捷键 {} 失败，可能已被其他程序占用: {}", accel, e))
+    })
+}
+
+/// 先注销旧快捷键再注册新的；新快捷键注册失败时恢复旧快捷键，设置保持不变
+fn replace_shortcut(app: &AppHandle, next: Option<(Shortcut, &str)>) -> Result<(), AppError> {
+    let mut current = lock_current()?;
+    let previous = *current;
+    if previous.is_some() && next.map(|(shortcut, _)| shortcut) == previous {
+        return Ok(());
+    }
+    if let Some(previous) = previous {
+        app.global_shortcut()
+            .unregister(previous)
+            .map_err(|e| AppError::Io(format!("注销快捷键失败: {}", e)))?;
+        *current = None;
+    }
+    let Some((shortcut, accel)) = next else {
+        return Ok(());
+    };
+    if let Err(error) = register(app, shortcut, accel) {
+        if let Some(previous) = previous {
+            if app.global_shortcut().register(previous).is_ok() {
+                *current = Some(previous);
+            }
+        }
+        return Err(error);
+    }
+    *current = Some(shortcut);
+    Ok(())
+}
+
+pub(crate) fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                tray::toggle_main_window(app);
+            }
+        })
+        .build()
+}
+
+/// 启动时注册已保存的快捷键；失败（如被其他程序占用）只记录日志，不影响启动
+pub(crate) fn install(app: &AppHandle) {
+    let Some(accel) = stored_accelerator() else {
+        return;
+    };
+    let result = parse_accelerator(&accel).and_then(|shortcut| replace_shortcut(app, Some((shortcut, &accel))));
+    if let Err(error) = result {
+        log::warn!("注册全局快捷键失败: {}", error);
+    }
+}
+
+/// 返回已保存的快捷键，已关闭时为 None
+#[tauri::command]
+pub(crate) fn get_global_shortcut() -> Option<String> {
+    stored_accelerator()
+}
+
+/// 注册成功后才写入设置；格式无效或已被占用时返回错误，原快捷键继续生效
+#[tauri::command]
+pub(crate) async fn set_global_shortcut(app: AppHandle, accel: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let accel = String::from(accel.trim());
+        let shortcut = parse_accelerator(&accel)?;
+        replace_shortcut(&app, Some((shortcut, &accel)))?;
+        settings::write_setting(GLOBAL_SHORTCUT_SETTING, &accel)
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn clear_global_shortcut(app: AppHandle) -> Result<(), AppError> {
+    run_blocking(move || {
+        replace_shortcut(&app, None)?;
+        settings::write_setting(GLOBAL_SHORTCUT_SETTING, "")
+    })
+    .await
+}
@@ -0,0 +1,144 @@
+// 图模型：从文档 JSON 解析出节点 ID 与连线的紧凑表示，供布局、路径与结构分析等命令共用
+// 节点按文档中的顺序编号，算法输出据此保持确定性；引用不存在节点的连线被跳过
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::error::AppError;
+
+pub(crate) struct GraphEdge {
+    pub(crate) source: usize,
+    pub(crate) target: usize,
+    pub(crate) weight: f64,
+    pub(crate) directed: bool,
+}
+
+pub(crate) struct GraphModel {
+    pub(crate) node_ids: Vec<String>,
+    pub(crate) index: HashMap<String, usize>,
+    pub(crate) edges: Vec<GraphEdge>,
+}
+
+/// 连线属性可写在连线对象上，也可写在 data 中；连线对象上的优先
+fn edge_field<'a>(edge: &'a Value, key: &str) -> Option<&'a Value> {
+    edge.get(key).or_else(|| edge.get("data").and_then(|data| data.get(key)))
+}
+
+impl GraphModel {
+    pub(crate) fn node_count(&self) -> usize {
+        self.node_ids.len()
+    }
+
+    pub(crate) fn node_index(&self, id: &str) -> Result<usize, AppError> {
+        self.index
+            .get(id)
+            .copied()
+            .ok_or_else(|| AppError::NotFound(format!("节点不存在: {}", id)))
+    }
+
+    /// 邻接表 (邻居, 连线序号)：有向连线只登记 source → target，无向连线两个方向都登记
+    pub(crate) fn adjacency(&self) -> Vec<Vec<(usize, usize)>> {
+        let mut adjacency = vec![Vec::new(); self.node_count()];
+        for (edge_index, edge) in self.edges.iter().enumerate() {
+            adjacency[edge.source].push((edge.target, edge_index));
+            if !edge.directed && edge.source != edge.target {
+                adjacency[edge.target].push((edge.source, edge_index));
+            }
+        }
+        adjacency
+    }
+
+    /// 按连线方向的后继节点（去重，保持连线顺序），忽略 directed 标记，供只关心 source → target 的算法使用
+    pub(crate) fn successors(&self) -> Vec<Vec<usize>> {
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); self.node_count()];
+        for edge in &self.edges {
+            if !successors[edge.source].contains(&edge.target) {
+                successors[edge.source].push(edge.target);
+            }
+        }
+        successors
+    }
+
+    /// 忽略方向的连通分量；分量内与分量之间都按节点在文档中的顺序排列
+    pub(crate) fn undirected_components(&self) -> Vec<Vec<usize>> {
+        let mut parent: Vec<usize> = (0..self.node_count()).collect();
+        fn find(parent: &mut [usize], node: usize) -> usize {
+            let mut root = node;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            let mut current = node;
+            while parent[current] != root {
+                let next = parent[current];
+                parent[current] = root;
+                current = next;
+            }
+            root
+        }
+        for edge in &self.edges {
+            let a = find(&mut parent, edge.source);
+            let b = find(&mut parent, edge.target);
+            if a != b {
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+        for node in 0..self.node_count() {
+            let root = find(&mut parent, node);
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(node);
+        }
+        groups
+    }
+}
+
+pub(crate) fn parse_graph(data: &str) -> Result<GraphModel, AppError> {
+    let document: Value =
+        serde_json::from_str(data).map_err(|e| AppError::Serialization(format!("解析图数据失败: {}", e)))?;
+    parse_graph_value(&document)
+}
+
+/// weight 缺省为 1；directed 缺省为 true，与应用中 source → target 的方向语义一致
+pub(crate) fn parse_graph_value(document: &Value) -> Result<GraphModel, AppError> {
+    let Some(nodes) = document.get("nodes").and_then(Value::as_array) else {
+        return Err(AppError::Serialization(String::from("图数据缺少 nodes 数组")));
+    };
+    let mut node_ids = Vec::with_capacity(nodes.len());
+    let mut index = HashMap::with_capacity(nodes.len());
+    for node in nodes {
+        let Some(id) = node.get("id").and_then(Value::as_str) else {
+            continue;
+        };
+        if index.contains_key(id) {
+            continue;
+        }
+        index.insert(String::from(id), node_ids.len());
+        node_ids.push(String::from(id));
+    }
+
+    let edges = document
+        .get("edges")
+        .and_then(Value::as_array)
+        .map(|edges| {
+            edges
+                .iter()
+                .filter_map(|edge| {
+                    let source = *index.get(edge.get("source")?.as_str()?)?;
+                    let target = *index.get(edge.get("target")?.as_str()?)?;
+                    Some(GraphEdge {
+                        source,
+                        target,
+                        weight: edge_field(edge, "weight").and_then(Value::as_f64).unwrap_or(1.0),
+                        directed: edge_field(edge, "directed").and_then(Value::as_bool).unwrap_or(true),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(GraphModel { node_ids, index, edges })
+}
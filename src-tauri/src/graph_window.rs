@@ -0,0 +1,83 @@
+// 多文档窗口：每个文档最多对应一个副窗口，重复打开同一文档时聚焦已有窗口
+// 文档名通过初始化脚本注入 window.__GRAPH_AND_TABLE_INITIAL_GRAPH__，前端启动时据此加载；窗口销毁后从登记表移除
+// 副窗口关闭不影响其余窗口，只有全部窗口关闭后应用才退出
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use crate::error::AppError;
+use crate::{file_open, sanitize_required_graph_name, storage, webview_zoom};
+
+// 与 capabilities/default.json 中的 "graph-*" 对应，副窗口才能调用后端命令
+const GRAPH_WINDOW_LABEL_PREFIX: &str = "graph-";
+const INITIAL_GRAPH_GLOBAL: &str = "window.__GRAPH_AND_TABLE_INITIAL_GRAPH__";
+const DEFAULT_WINDOW_WIDTH: f64 = 1200.0;
+const DEFAULT_WINDOW_HEIGHT: f64 = 800.0;
+
+/// 文档名 -> 窗口 label
+#[derive(Default)]
+pub(crate) struct GraphWindows {
+    labels: Mutex<HashMap<String, String>>,
+    next_id: AtomicU64,
+}
+
+fn focus_window(window: &WebviewWindow) {
+    let _ = window.show();
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+}
+
+/// 创建窗口在 Windows 上必须离开主线程的同步命令，否则会死锁，所以这里是 async 命令
+#[tauri::command]
+pub(crate) async fn open_graph_window(
+    app: AppHandle,
+    state: State<'_, GraphWindows>,
+    name: String,
+) -> Result<(), AppError> {
+    let name = sanitize_required_graph_name(&name)?;
+    if storage::resolve_document_file(&name)?.is_none() {
+        return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+    }
+
+    // 创建期间持有锁，避免并发请求为同一文档建出两个窗口
+    let mut labels = state.labels.lock().map_err(|_| AppError::Io(String::from("窗口登记表锁已损坏")))?;
+    if let Some(window) = labels.get(&name).and_then(|label| app.get_webview_window(label)) {
+        focus_window(&window);
+        return Ok(());
+    }
+
+    let label = format!(
+        "{}{}",
+        GRAPH_WINDOW_LABEL_PREFIX,
+        state.next_id.fetch_add(1, Ordering::Relaxed) + 1
+    );
+    let name_literal =
+        serde_json::to_string(&name).map_err(|e| AppError::Serialization(format!("序列化文档名称失败: {}", e)))?;
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::default())
+        .title(format!("{} - {}", name, app.package_info().name))
+        .inner_size(DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT)
+        .resizable(true)
+        .zoom_hotkeys_enabled(false)
+        .initialization_script(format!("{} = {};", INITIAL_GRAPH_GLOBAL, name_literal))
+        .build()
+        .map_err(|e| AppError::Io(format!("创建窗口失败: {}", e)))?;
+
+    // 与主窗口相同的缩放设置与拖放打开
+    webview_zoom::apply_pinch_zoom(&window, webview_zoom::pinch_zoom_enabled());
+    webview_zoom::apply_zoom_level(&window, webview_zoom::stored_zoom_level());
+    file_open::install(&window);
+
+    let handle = app.clone();
+    let closed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            if let Ok(mut labels) = handle.state::<GraphWindows>().labels.lock() {
+                labels.retain(|_, label| *label != closed_label);
+            }
+        }
+    });
+    labels.insert(name, label);
+    Ok(())
+}
@@ -0,0 +1,488 @@
+// GraphML 导入：用 quick-xml 流式读取，按 <key> 声明把 <data> 映射到节点/连线字段，输出应用的文档结构
+// 兼容 yEd 的 nodegraphics/edgegraphics：从 *Label 元素取标签、从 Geometry 取坐标；嵌套子图展平为同一张图
+
+use quick_xml::escape::{escape, resolve_predefined_entity};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+
+use crate::canonical_ids::{self, DuplicateIdPolicy, ImportedGraph};
+use crate::error::AppError;
+use crate::migrations::{CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY};
+use crate::{now_ms, resolve_external_path, run_blocking};
+
+// 没有坐标的节点按网格排列，与格式说明中推荐的列宽/行高一致
+const GRID_COLUMNS: usize = 10;
+const GRID_COLUMN_WIDTH: f64 = 260.0;
+const GRID_ROW_HEIGHT: f64 = 140.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum KeyValueType {
+    Text,
+    Number,
+    Boolean,
+}
+
+struct GraphmlKey {
+    // for 属性：node / edge / graph / all
+    domain: String,
+    name: String,
+    value_type: KeyValueType,
+    default: Option<String>,
+}
+
+#[derive(Default)]
+struct PendingElement {
+    id: Option<String>,
+    source: Option<String>,
+    target: Option<String>,
+    directed: Option<bool>,
+    values: Vec<(String, String)>,
+    graphics_label: Option<String>,
+    position: Option<(f64, f64)>,
+}
+
+enum Owner {
+    Graph,
+    Node(PendingElement),
+    Edge(PendingElement),
+}
+
+#[derive(Default)]
+struct DataCapture {
+    key: String,
+    text: String,
+    // 嵌套在 <data> 内的元素层数；大于 0 说明是 yEd 这类结构化数据
+    depth: usize,
+    structured: bool,
+    label: Option<String>,
+    label_text: Option<String>,
+    position: Option<(f64, f64)>,
+}
+
+#[derive(Default)]
+struct GraphmlParser {
+    keys: HashMap<String, GraphmlKey>,
+    key_default: Option<(String, String)>,
+    owners: Vec<Owner>,
+    edge_defaults: Vec<bool>,
+    data: Option<DataCapture>,
+    nodes: Vec<PendingElement>,
+    edges: Vec<PendingElement>,
+    saw_graph: bool,
+    open_elements: usize,
+}
+
+fn xml_error(reader_position: u64, error: impl std::fmt::Display) -> AppError {
+    AppError::InvalidInput(format!("GraphML 格式错误（字节偏移 {}）: {}", reader_position, error))
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Result<Option<String>, String> {
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        if attribute.key.local_name().as_ref() == name.as_bytes() {
+            return attribute
+                .decode_and_unescape_value(element.decoder())
+                .map(|value| Some(value.into_owned()))
+                .map_err(|e| e.to_string());
+        }
+    }
+    Ok(None)
+}
+
+fn local_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+fn parse_key_type(value: Option<&str>) -> KeyValueType {
+    match value.map(str::to_ascii_lowercase).as_deref() {
+        Some("int" | "long" | "float" | "double") => KeyValueType::Number,
+        Some("boolean") => KeyValueType::Boolean,
+        _ => KeyValueType::Text,
+    }
+}
+
+fn typed_value(text: &str, value_type: KeyValueType) -> Value {
+    let trimmed = text.trim();
+    match value_type {
+        KeyValueType::Number => trimmed
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or_else(|| Value::String(String::from(text))),
+        KeyValueType::Boolean => match trimmed.to_ascii_lowercase().as_str() {
+            "true" | "1" => Value::Bool(true),
+            "false" | "0" => Value::Bool(false),
+            _ => Value::String(String::from(text)),
+        },
+        KeyValueType::Text => Value::String(String::from(text)),
+    }
+}
+
+impl GraphmlParser {
+    fn handle_start(&mut self, element: &BytesStart, is_empty: bool) -> Result<(), String> {
+        let name = local_name(element);
+        if let Some(data) = self.data.as_mut() {
+            data.structured = true;
+            if name == "Geometry" {
+                let x = attribute(element, "x")?.and_then(|value| value.parse::<f64>().ok());
+                let y = attribute(element, "y")?.and_then(|value| value.parse::<f64>().ok());
+                if let (Some(x), Some(y)) = (x, y) {
+                    data.position = Some((x, y));
+                }
+            }
+            if !is_empty {
+                data.depth += 1;
+                if name.ends_with("Label") && data.label.is_none() && data.label_text.is_none() {
+                    data.label_text = Some(String::new());
+                }
+            }
+            return Ok(());
+        }
+        match name.as_str() {
+            "key" => {
+                let id = attribute(element, "id")?.ok_or("key 元素缺少 id 属性")?;
+                let domain = attribute(element, "for")?.unwrap_or_else(|| String::from("all"));
+                let key = GraphmlKey {
+                    name: attribute(element, "attr.name")?.unwrap_or_else(|| id.clone()),
+                    value_type: parse_key_type(attribute(element, "attr.type")?.as_deref()),
+                    domain,
+                    default: None,
+                };
+                self.keys.insert(id.clone(), key);
+                if !is_empty {
+                    self.key_default = Some((id, String::new()));
+                }
+            }
+            "graph" => {
+                self.saw_graph = true;
+                let inherited = self.edge_defaults.last().copied().unwrap_or(true);
+                let directed = match attribute(element, "edgedefault")?.as_deref() {
+                    Some("undirected") => false,
+                    Some("directed") => true,
+                    _ => inherited,
+                };
+                if !is_empty {
+                    self.edge_defaults.push(directed);
+                    self.owners.push(Owner::Graph);
+                } else if self.edge_defaults.is_empty() {
+                    self.edge_defaults.push(directed);
+                }
+            }
+            "node" => {
+                let id = attribute(element, "id")?.ok_or("node 元素缺少 id 属性")?;
+                let node = PendingElement {
+                    id: Some(id),
+                    ..PendingElement::default()
+                };
+                if is_empty {
+                    self.nodes.push(node);
+                } else {
+                    self.owners.push(Owner::Node(node));
+                }
+            }
+            "edge" => {
+                let edge = PendingElement {
+                    id: attribute(element, "id")?,
+                    source: Some(attribute(element, "source")?.ok_or("edge 元素缺少 source 属性")?),
+                    target: Some(attribute(element, "target")?.ok_or("edge 元素缺少 target 属性")?),
+                    directed: match attribute(element, "directed")?.as_deref() {
+                        Some("true") => Some(true),
+                        Some("false") => Some(false),
+                        _ => self.edge_defaults.last().copied(),
+                    },
+                    ..PendingElement::default()
+                };
+                if is_empty {
+                    self.edges.push(edge);
+                } else {
+                    self.owners.push(Owner::Edge(edge));
+                }
+            }
+            "data" if !is_empty => {
+                self.data = Some(DataCapture {
+                    key: attribute(element, "key")?.ok_or("data 元素缺少 key 属性")?,
+                    ..DataCapture::default()
+                });
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_end(&mut self, name: &str) {
+        if let Some(data) = self.data.as_mut() {
+            if data.depth > 0 {
+                data.depth -= 1;
+                if name.ends_with("Label") {
+                    if let Some(text) = data.label_text.take() {
+                        data.label = Some(String::from(text.trim()));
+                    }
+                }
+                return;
+            }
+            let data = self.data.take().unwrap_or_default();
+            let owner = match self.owners.last_mut() {
+                Some(Owner::Node(element) | Owner::Edge(element)) => element,
+                _ => return,
+            };
+            if data.structured {
+                if owner.graphics_label.is_none() {
+                    owner.graphics_label = data.label.filter(|label| !label.is_empty());
+                }
+                owner.position = owner.position.or(data.position);
+            } else {
+                owner.values.push((data.key, data.text));
+            }
+            return;
+        }
+        match name {
+            "key" => {
+                if let Some((id, text)) = self.key_default.take() {
+                    if let Some(key) = self.keys.get_mut(&id) {
+                        key.default = Some(text);
+                    }
+                }
+            }
+            "graph" => {
+                if matches!(self.owners.last(), Some(Owner::Graph)) {
+                    self.owners.pop();
+                    // 保留最外层图的 edgedefault 作为整份文档的方向
+                    if self.edge_defaults.len() > 1 {
+                        self.edge_defaults.pop();
+                    }
+                }
+            }
+            "node" => {
+                if matches!(self.owners.last(), Some(Owner::Node(_))) {
+                    if let Some(Owner::Node(node)) = self.owners.pop() {
+                        self.nodes.push(node);
+                    }
+                }
+            }
+            "edge" => {
+                if matches!(self.owners.last(), Some(Owner::Edge(_))) {
+                    if let Some(Owner::Edge(edge)) = self.owners.pop() {
+                        self.edges.push(edge);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_text(&mut self, text: &str) {
+        if let Some(data) = self.data.as_mut() {
+            if data.depth == 0 {
+                data.text.push_str(text);
+            } else if let Some(label_text) = data.label_text.as_mut() {
+                label_text.push_str(text);
+            }
+        } else if let Some((_, default_text)) = self.key_default.as_mut() {
+            default_text.push_str(text);
+        }
+    }
+
+    /// 显式的 <data> 覆盖 <key> 的 <default>；未声明的 key 以 key id 为字段名按文本保留
+    fn resolve_values(&self, element: &PendingElement, domain: &str) -> Vec<(String, Value)> {
+        let mut resolved: Vec<(String, Value)> = Vec::new();
+        let explicit: HashSet<&str> = element.values.iter().map(|(key, _)| key.as_str()).collect();
+        let mut key_ids: Vec<&String> = self.keys.keys().collect();
+        key_ids.sort();
+        for key_id in key_ids {
+            let key = &self.keys[key_id];
+            if (key.domain == domain || key.domain == "all") && !explicit.contains(key_id.as_str()) {
+                if let Some(default) = &key.default {
+                    resolved.push((key.name.clone(), typed_value(default, key.value_type)));
+                }
+            }
+        }
+        for (key_id, text) in &element.values {
+            match self.keys.get(key_id) {
+                Some(key) => resolved.push((key.name.clone(), typed_value(text, key.value_type))),
+                None => resolved.push((key_id.clone(), Value::String(text.clone()))),
+            }
+        }
+        resolved
+    }
+
+    fn build_node(&self, index: usize, node: &PendingElement, created_at: u64) -> Value {
+        let id = node.id.clone().unwrap_or_default();
+        let mut data = Map::new();
+        let mut label = node.graphics_label.clone();
+        let mut content = String::new();
+        let mut tags: Vec<Value> = Vec::new();
+        let (mut x, mut y) = (None, None);
+        for (name, value) in self.resolve_values(node, "node") {
+            let text = value.as_str().map(String::from).unwrap_or_else(|| value.to_string());
+            match name.to_ascii_lowercase().as_str() {
+                "label" | "name" | "title" => label = Some(text),
+                "description" | "content" | "note" => {
+                    content = format!("<p>{}</p>", escape(text.as_str()));
+                }
+                "tags" => {
+                    tags = text
+                        .split([',', ';'])
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(|tag| Value::String(String::from(tag)))
+                        .collect();
+                }
+                "color" => {
+                    data.insert(String::from("color"), Value::String(text));
+                }
+                "x" => x = value.as_f64(),
+                "y" => y = value.as_f64(),
+                _ => {
+                    data.insert(name, value);
+                }
+            }
+        }
+        let (x, y) = match (x, y, node.position) {
+            (Some(x), Some(y), _) => (x, y),
+            (_, _, Some(position)) => position,
+            _ => (
+                (index % GRID_COLUMNS) as f64 * GRID_COLUMN_WIDTH,
+                (index / GRID_COLUMNS) as f64 * GRID_ROW_HEIGHT,
+            ),
+        };
+        data.insert(String::from("label"), Value::String(label.unwrap_or_else(|| id.clone())));
+        data.insert(String::from("content"), Value::String(content));
+        data.insert(String::from("tags"), Value::Array(tags));
+        data.insert(String::from("createdAt"), json!(created_at));
+        data.insert(String::from("updatedAt"), json!(created_at));
+        json!({
+            "id": id,
+            "type": "knowledgeNode",
+            "position": { "x": x, "y": y },
+            "data": data,
+        })
+    }
+
+    fn build_edge(&self, id: String, edge: &PendingElement, graph_directed: bool) -> Value {
+        let mut object = Map::new();
+        let mut data = Map::new();
+        object.insert(String::from("id"), Value::String(id));
+        object.insert(String::from("source"), Value::String(edge.source.clone().unwrap_or_default()));
+        object.insert(String::from("target"), Value::String(edge.target.clone().unwrap_or_default()));
+        object.insert(String::from("directed"), Value::Bool(edge.directed.unwrap_or(graph_directed)));
+        if let Some(label) = &edge.graphics_label {
+            object.insert(String::from("label"), Value::String(label.clone()));
+        }
+        for (name, value) in self.resolve_values(edge, "edge") {
+            match name.to_ascii_lowercase().as_str() {
+                "label" | "name" | "title" => {
+                    let text = value.as_str().map(String::from).unwrap_or_else(|| value.to_string());
+                    object.insert(String::from("label"), Value::String(text));
+                }
+                "weight" if value.is_number() => {
+                    object.insert(String::from("weight"), value);
+                }
+                _ => {
+                    data.insert(name, value);
+                }
+            }
+        }
+        if !data.is_empty() {
+            object.insert(String::from("data"), Value::Object(data));
+        }
+        Value::Object(object)
+    }
+
+    /// 节点 ID 原样保留，重复 ID 与连线端点由 canonical_ids 统一处理；缺少 id 的连线补 edge_graphml_<序号>
+    fn into_document(self) -> Result<Value, AppError> {
+        let created_at = now_ms();
+        let nodes: Vec<Value> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| self.build_node(index, node, created_at))
+            .collect();
+        let graph_directed = self.edge_defaults.first().copied().unwrap_or(true);
+        let mut edge_ids = HashSet::new();
+        let mut edges = Vec::with_capacity(self.edges.len());
+        for (index, edge) in self.edges.iter().enumerate() {
+            let id = edge
+                .id
+                .clone()
+                .filter(|id| !id.is_empty() && !edge_ids.contains(id))
+                .unwrap_or_else(|| format!("edge_graphml_{}", index + 1));
+            edge_ids.insert(id.clone());
+            edges.push(self.build_edge(id, edge, graph_directed));
+        }
+        Ok(json!({
+            "nodes": nodes,
+            "edges": edges,
+            "directed": graph_directed,
+            SCHEMA_VERSION_KEY: CURRENT_SCHEMA_VERSION,
+        }))
+    }
+}
+
+pub(crate) fn parse_graphml(source: impl BufRead) -> Result<Value, AppError> {
+    let mut reader = Reader::from_reader(source);
+    let mut parser = GraphmlParser::default();
+    let mut buffer = Vec::new();
+    loop {
+        let event = reader
+            .read_event_into(&mut buffer)
+            .map_err(|e| xml_error(reader.error_position(), e))?;
+        let position = reader.buffer_position();
+        let result = match event {
+            Event::Start(element) => {
+                parser.open_elements += 1;
+                parser.handle_start(&element, false)
+            }
+            Event::Empty(element) => parser.handle_start(&element, true),
+            Event::End(element) => {
+                parser.open_elements = parser.open_elements.saturating_sub(1);
+                parser.handle_end(&String::from_utf8_lossy(element.local_name().as_ref()));
+                Ok(())
+            }
+            Event::Text(text) => text.xml_content().map(|text| parser.handle_text(&text)).map_err(|e| e.to_string()),
+            Event::CData(data) => data.decode().map(|text| parser.handle_text(&text)).map_err(|e| e.to_string()),
+            Event::GeneralRef(reference) => match reference.resolve_char_ref() {
+                Ok(Some(ch)) => {
+                    parser.handle_text(ch.encode_utf8(&mut [0u8; 4]));
+                    Ok(())
+                }
+                Ok(None) => reference.decode().map_err(|e| e.to_string()).and_then(|name| {
+                    resolve_predefined_entity(&name)
+                        .map(|text| parser.handle_text(text))
+                        .ok_or_else(|| format!("无法识别的实体引用: &{};", name))
+                }),
+                Err(e) => Err(e.to_string()),
+            },
+            Event::Eof => break,
+            _ => Ok(()),
+        };
+        result.map_err(|message| xml_error(position, message))?;
+        buffer.clear();
+    }
+    if parser.open_elements > 0 {
+        return Err(AppError::InvalidInput(String::from("GraphML 文件不完整：存在未闭合的元素")));
+    }
+    if !parser.saw_graph {
+        return Err(AppError::InvalidInput(String::from("不是有效的 GraphML 文件：缺少 graph 元素")));
+    }
+    parser.into_document()
+}
+
+/// 返回应用文档结构的 JSON（已是当前 schemaVersion），由前端决定合并还是另存为新文档；
+/// on_duplicate 决定重复节点 ID 的处理方式，缺省报错
+#[tauri::command]
+pub(crate) async fn import_graphml(
+    src_path: String,
+    on_duplicate: Option<DuplicateIdPolicy>,
+) -> Result<ImportedGraph, AppError> {
+    run_blocking(move || {
+        let path = resolve_external_path(&src_path)?;
+        let file = std::fs::File::open(&path).map_err(|e| AppError::io("读取 GraphML 文件失败", e))?;
+        let document = parse_graphml(std::io::BufReader::new(file))?;
+        canonical_ids::finish_import(document, on_duplicate.unwrap_or_default())
+    })
+    .await
+}
@@ -0,0 +1,160 @@
+// 撤销/重做历史：每个文档在 history/<name>/ 下保存最多 MAX_HISTORY_STATES 份快照，重启后仍可撤销
+// index.json 记录快照序号与当前位置，快照本身 gzip 压缩后单独成文件，push 时只写新增的一份
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+use crate::error::AppError;
+use crate::{
+    compress_graph_bytes, decompress_graph_bytes, graph_document_is_encrypted, resolve_app_data_dir, run_blocking,
+    sanitize_required_graph_name, write_file_atomic,
+};
+
+pub(crate) const MAX_HISTORY_STATES: usize = 50;
+pub(crate) const HISTORY_DIR_NAME: &str = "history";
+const HISTORY_INDEX_FILE_NAME: &str = "index.json";
+
+// 历史操作是“读索引 - 改 - 写回”，串行执行避免并发的 push/undo 互相覆盖
+static HISTORY_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryIndex {
+    // 快照序号，从旧到新
+    entries: Vec<u64>,
+    // 当前状态在 entries 中的位置；为 None 表示尚无历史
+    cursor: Option<usize>,
+    next_seq: u64,
+}
+
+fn resolve_history_dir(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(HISTORY_DIR_NAME).join(name))
+}
+
+fn resolve_state_path(name: &str, seq: u64) -> Result<PathBuf, AppError> {
+    Ok(resolve_history_dir(name)?.join(format!("{}.json.gz", seq)))
+}
+
+fn read_index(name: &str) -> Result<HistoryIndex, AppError> {
+    let path = resolve_history_dir(name)?.join(HISTORY_INDEX_FILE_NAME);
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(serde_json::from_str(&contents).unwrap_or_default()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HistoryIndex::default()),
+        Err(e) => Err(AppError::io("读取历史索引失败", e)),
+    }
+}
+
+fn write_index(name: &str, index: &HistoryIndex) -> Result<(), AppError> {
+    let contents =
+        serde_json::to_vec(index).map_err(|e| AppError::Serialization(format!("序列化历史索引失败: {}", e)))?;
+    write_file_atomic(&resolve_history_dir(name)?.join(HISTORY_INDEX_FILE_NAME), &contents)
+}
+
+fn read_state(name: &str, seq: u64) -> Result<String, AppError> {
+    let bytes = std::fs::read(resolve_state_path(name, seq)?).map_err(|e| AppError::io("读取历史快照失败", e))?;
+    String::from_utf8(decompress_graph_bytes(&bytes)?)
+        .map_err(|e| AppError::Serialization(format!("历史快照不是有效的 UTF-8: {}", e)))
+}
+
+fn remove_states(name: &str, seqs: &[u64]) -> Result<(), AppError> {
+    for seq in seqs {
+        let _ = std::fs::remove_file(resolve_state_path(name, *seq)?);
+    }
+    Ok(())
+}
+
+/// 在当前位置之后追加新状态：撤销后再 push 会丢弃重做分支，超出上限时丢弃最旧的快照
+fn push_history_state(name: &str, data: &str) -> Result<(), AppError> {
+    let mut index = read_index(name)?;
+    std::fs::create_dir_all(resolve_history_dir(name)?).map_err(|e| AppError::io("创建历史目录失败", e))?;
+
+    let keep = index.cursor.map(|cursor| cursor + 1).unwrap_or(0).min(index.entries.len());
+    let discarded = index.entries.split_off(keep);
+    remove_states(name, &discarded)?;
+
+    let seq = index.next_seq;
+    write_file_atomic(&resolve_state_path(name, seq)?, &compress_graph_bytes(data.as_bytes())?)?;
+    index.entries.push(seq);
+    index.next_seq += 1;
+
+    let overflow = index.entries.len().saturating_sub(MAX_HISTORY_STATES);
+    let dropped: Vec<u64> = index.entries.drain(..overflow).collect();
+    remove_states(name, &dropped)?;
+    index.cursor = Some(index.entries.len() - 1);
+    write_index(name, &index)
+}
+
+/// step 为 -1 表示撤销、+1 表示重做；越界时返回 None 且不改变位置
+fn move_history_cursor(name: &str, step: isize) -> Result<Option<String>, AppError> {
+    let mut index = read_index(name)?;
+    let Some(cursor) = index.cursor else {
+        return Ok(None);
+    };
+    let Some(next) = cursor.checked_add_signed(step).filter(|next| *next < index.entries.len()) else {
+        return Ok(None);
+    };
+    let contents = read_state(name, index.entries[next])?;
+    index.cursor = Some(next);
+    write_index(name, &index)?;
+    Ok(Some(contents))
+}
+
+fn with_history_lock<T>(task: impl FnOnce() -> Result<T, AppError>) -> Result<T, AppError> {
+    let _guard = HISTORY_LOCK
+        .lock()
+        .map_err(|_| AppError::Io(String::from("历史记录状态不可用")))?;
+    task()
+}
+
+pub(crate) fn delete_history(name: &str) -> Result<(), AppError> {
+    with_history_lock(|| {
+        let dir = resolve_history_dir(name)?;
+        if dir.is_dir() {
+            std::fs::remove_dir_all(dir).map_err(|e| AppError::io("删除历史记录失败", e))?;
+        }
+        Ok(())
+    })
+}
+
+pub(crate) fn rename_history(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    with_history_lock(|| {
+        let old_dir = resolve_history_dir(old_name)?;
+        let new_dir = resolve_history_dir(new_name)?;
+        if old_dir.is_dir() && !new_dir.exists() {
+            std::fs::rename(old_dir, new_dir).map_err(|e| AppError::io("重命名历史记录失败", e))?;
+        }
+        Ok(())
+    })
+}
+
+/// 历史快照以明文（gzip）保存，加密文档不记录历史，避免内容绕过加密落盘
+#[tauri::command]
+pub(crate) async fn push_state(name: String, data: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        if graph_document_is_encrypted(&graph_name)? {
+            return Err(AppError::InvalidInput(String::from("加密文档不记录撤销历史")));
+        }
+        with_history_lock(|| push_history_state(&graph_name, &data))
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn undo(name: String) -> Result<Option<String>, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        with_history_lock(|| move_history_cursor(&graph_name, -1))
+    })
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn redo(name: String) -> Result<Option<String>, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        with_history_lock(|| move_history_cursor(&graph_name, 1))
+    })
+    .await
+}
@@ -0,0 +1,166 @@
+// 界面语言与错误消息表：语言取设置项 locale（如 "en-US"、"zh-CN"），未设置时跟随系统语言
+// 现有错误详情均为中文，中文界面直接展示详情；其他语言使用消息表中按错误码给出的说明，目前提供中文与英文
+
+use crate::error::ErrorCode;
+use crate::settings;
+
+const LOCALE_SETTING: &str = "locale";
+const DEFAULT_LOCALE: &str = "zh-CN";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Language {
+    Chinese,
+    English,
+}
+
+/// 按语言前缀匹配，不认识的语言退回英文
+pub(crate) fn language_of(locale: &str) -> Language {
+    let primary = locale.split(['-', '_', '.']).next().unwrap_or_default();
+    if primary.eq_ignore_ascii_case("zh") {
+        Language::Chinese
+    } else {
+        Language::English
+    }
+}
+
+pub(crate) fn current_locale() -> String {
+    settings::read_setting::<String>(LOCALE_SETTING)
+        .filter(|locale| !locale.trim().is_empty())
+        .or_else(sys_locale::get_locale)
+        .unwrap_or_else(|| String::from(DEFAULT_LOCALE))
+}
+
+fn chinese_message(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::DataDirUnavailable => "数据目录不可用",
+        ErrorCode::Io => "文件读写失败",
+        ErrorCode::PermissionDenied => "没有访问权限",
+        ErrorCode::InvalidName => "名称无效",
+        ErrorCode::NotFound => "找不到指定的内容",
+        ErrorCode::AlreadyExists => "目标已存在",
+        ErrorCode::Serialization => "数据格式无效",
+        ErrorCode::PasswordRequired => "文档已加密，请提供密码",
+        ErrorCode::DecryptionFailed => "解密失败，密码错误或文件已损坏",
+        ErrorCode::InvalidInput => "输入无效",
+        ErrorCode::Cancelled => "操作已取消",
+        ErrorCode::ChecksumMismatch => "文件校验失败，内容可能已损坏",
+        ErrorCode::UpdateNetwork => "无法连接更新服务器",
+        ErrorCode::UpdateSignatureInvalid => "更新包签名无效",
+        ErrorCode::UpdateFailed => "更新失败",
+        ErrorCode::RevisionConflict => "文档已被修改，请重新加载后再试",
+        ErrorCode::DocumentLocked => "文档正被其他程序使用",
+        ErrorCode::MergeConflict => "合并时存在冲突",
+        ErrorCode::DanglingEdges => "连线引用了不存在的节点",
+        ErrorCode::TooLarge => "文档超过大小上限",
+        ErrorCode::Cycle => "图中存在环",
+        ErrorCode::TypeMismatch => "表格中有单元格不符合列类型",
+    }
+}
+
+fn english_message(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::DataDirUnavailable => "The data directory is unavailable",
+        ErrorCode::Io => "A file operation failed",
+        ErrorCode::PermissionDenied => "Permission denied",
+        ErrorCode::InvalidName => "The name is invalid",
+        ErrorCode::NotFound => "The requested item was not found",
+        ErrorCode::AlreadyExists => "The target already exists",
+        ErrorCode::Serialization => "The data format is invalid",
+        ErrorCode::PasswordRequired => "The document is encrypted; please enter the password",
+        ErrorCode::DecryptionFailed => "Decryption failed: wrong password or corrupted file",
+        ErrorCode::InvalidInput => "The input is invalid",
+        ErrorCode::Cancelled => "The operation was cancelled",
+        ErrorCode::ChecksumMismatch => "Checksum mismatch: the file may be corrupted",
+        ErrorCode::UpdateNetwork => "Could not reach the update server",
+        ErrorCode::UpdateSignatureInvalid => "The update signature is invalid",
+        ErrorCode::UpdateFailed => "The update failed",
+        ErrorCode::RevisionConflict => "The document was modified elsewhere; reload and try again",
+        ErrorCode::DocumentLocked => "The document is in use by another program",
+        ErrorCode::MergeConflict => "The merge has conflicts",
+        ErrorCode::DanglingEdges => "Some edges reference nodes that do not exist",
+        ErrorCode::TooLarge => "The document exceeds the maximum size",
+        ErrorCode::Cycle => "The graph contains a cycle",
+        ErrorCode::TypeMismatch => "Some table cells do not match their column types",
+    }
+}
+
+/// 指定语言下错误码的说明
+pub(crate) fn error_message(locale: &str, code: ErrorCode) -> &'static str {
+    match language_of(locale) {
+        Language::Chinese => chinese_message(code),
+        Language::English => english_message(code),
+    }
+}
+
+/// 序列化错误时使用：中文界面返回原始详情，其他语言返回消息表中的说明
+pub(crate) fn localize_error<'a>(locale: &str, code: ErrorCode, detail: &'a str) -> &'a str {
+    match language_of(locale) {
+        Language::Chinese => detail,
+        Language::English => error_message(locale, code),
+    }
+}
+
+/// 当前生效的语言，供前端与后端保持一致
+#[tauri::command]
+pub(crate) fn get_locale() -> String {
+    current_locale()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_CODES: [ErrorCode; 22] = [
+        ErrorCode::DataDirUnavailable,
+        ErrorCode::Io,
+        ErrorCode::PermissionDenied,
+        ErrorCode::InvalidName,
+        ErrorCode::NotFound,
+        ErrorCode::AlreadyExists,
+        ErrorCode::Serialization,
+        ErrorCode::PasswordRequired,
+        ErrorCode::DecryptionFailed,
+        ErrorCode::InvalidInput,
+        ErrorCode::Cancelled,
+        ErrorCode::ChecksumMismatch,
+        ErrorCode::UpdateNetwork,
+        ErrorCode::UpdateSignatureInvalid,
+        ErrorCode::UpdateFailed,
+        ErrorCode::RevisionConflict,
+        ErrorCode::DocumentLocked,
+        ErrorCode::MergeConflict,
+        ErrorCode::DanglingEdges,
+        ErrorCode::TooLarge,
+        ErrorCode::Cycle,
+        ErrorCode::TypeMismatch,
+    ];
+
+    #[test]
+    fn matches_language_by_prefix() {
+        for locale in ["zh", "zh-CN", "zh_TW", "ZH-hans", "zh_CN.UTF-8"] {
+            assert!(language_of(locale) == Language::Chinese, "{}", locale);
+        }
+        for locale in ["en", "en-US", "fr-FR", "", "zhx"] {
+            assert!(language_of(locale) == Language::English, "{}", locale);
+        }
+    }
+
+    #[test]
+    fn every_code_has_distinct_english_and_chinese_messages() {
+        for code in ALL_CODES {
+            let english = error_message("en", code);
+            let chinese = error_message("zh", code);
+            assert!(!english.is_empty() && !chinese.is_empty(), "{}", code.as_str());
+            assert_ne!(english, chinese, "{}", code.as_str());
+            assert!(english.is_ascii(), "{}", code.as_str());
+            assert!(!chinese.is_ascii(), "{}", code.as_str());
+        }
+    }
+
+    #[test]
+    fn localize_keeps_chinese_detail_only_for_chinese() {
+        let detail = "读取文件失败: a.json";
+        assert_eq!(localize_error("zh-CN", ErrorCode::Io, detail), detail);
+        assert_eq!(localize_error("en-US", ErrorCode::Io, detail), "A file operation failed");
+    }
+}
@@ -0,0 +1,80 @@
+// 图片导出：画布在 webview 中渲染，前端生成 PNG 的 base64 data URL，这里解码校验后原子写盘
+// 解码失败或内容不是 PNG 时直接报错，不写出损坏的文件
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::{resolve_external_path, run_blocking, sanitize_graph_name, write_file_atomic};
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const PNG_EXTENSION: &str = "png";
+
+/// 接受 `data:image/png;base64,...` 形式的 data URL，也接受不带前缀的 base64
+fn decode_png(base64_png: &str) -> Result<Vec<u8>, AppError> {
+    let trimmed = base64_png.trim();
+    let encoded = match trimmed.strip_prefix("data:") {
+        Some(rest) => {
+            let (header, payload) = rest
+                .split_once(',')
+                .ok_or_else(|| AppError::InvalidInput(String::from("data URL 缺少数据部分")))?;
+            if !header.ends_with(";base64") {
+                return Err(AppError::InvalidInput(format!("data URL 不是 base64 编码: {}", header)));
+            }
+            payload
+        }
+        None => trimmed,
+    };
+    let compact: String = encoded.chars().filter(|ch| !ch.is_ascii_whitespace()).collect();
+    let bytes = STANDARD
+        .decode(compact.as_bytes())
+        .map_err(|e| AppError::InvalidInput(format!("base64 数据无效: {}", e)))?;
+    if !bytes.starts_with(&PNG_SIGNATURE) {
+        return Err(AppError::InvalidInput(String::from("数据不是 PNG 图片")));
+    }
+    Ok(bytes)
+}
+
+/// 目录中已有同名图片时依次尝试 `<name>-1.png`、`<name>-2.png`…，不覆盖已有文件
+fn unique_png_path(dir: &Path, stem: &str) -> PathBuf {
+    let candidate = dir.join(format!("{}.{}", stem, PNG_EXTENSION));
+    if !candidate.exists() {
+        return candidate;
+    }
+    (1..)
+        .map(|index| dir.join(format!("{}-{}.{}", stem, index, PNG_EXTENSION)))
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
+
+#[tauri::command]
+pub(crate) async fn save_png(base64_png: String, dest_path: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let file_path = resolve_external_path(&dest_path)?;
+        let bytes = decode_png(&base64_png)?;
+        write_file_atomic(&file_path, &bytes)
+    })
+    .await
+}
+
+/// 保存到对话框选择的目录，文件名取当前文档名；返回实际写入的路径
+#[tauri::command]
+pub(crate) async fn save_png_to_dir(
+    base64_png: String,
+    dir_path: String,
+    name: Option<String>,
+) -> Result<String, AppError> {
+    run_blocking(move || {
+        let dir = resolve_external_path(&dir_path)?;
+        if !dir.is_dir() {
+            return Err(AppError::NotFound(format!("目录不存在: {}", dir.to_string_lossy())));
+        }
+        let stem = sanitize_graph_name(name.as_deref())?;
+        let bytes = decode_png(&base64_png)?;
+        let file_path = unique_png_path(&dir, &stem);
+        write_file_atomic(&file_path, &bytes)?;
+        Ok(file_path.to_string_lossy().to_string())
+    })
+    .await
+}
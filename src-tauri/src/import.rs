@@ -0,0 +1,240 @@
+// 表格导入：把外部文件（CSV / Excel）解析为表格模型 { columns, rows }，源路径来自前端的打开对话框
+
+use calamine::{open_workbook_auto, Data, DataType, Reader};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+use tauri::State;
+
+use crate::error::AppError;
+use crate::operations::{CancelToken, OperationRegistry};
+use crate::{resolve_external_path, run_blocking};
+use crate::table::{ColumnType, TableColumn, TableData};
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+// 每读取这么多行检查一次取消标记
+const CANCEL_CHECK_INTERVAL: usize = 1000;
+
+/// 表头转列 ID：空表头或重复表头使用 col_<序号> 兜底，保证列 ID 唯一
+pub(crate) fn build_columns(headers: &[String]) -> Vec<TableColumn> {
+    let mut used = HashSet::new();
+    headers
+        .iter()
+        .enumerate()
+        .map(|(index, header)| {
+            let title = header.trim();
+            let id = if title.is_empty() || used.contains(title) {
+                format!("col_{}", index + 1)
+            } else {
+                String::from(title)
+            };
+            used.insert(id.clone());
+            TableColumn::new(&id, title, ColumnType::Text)
+        })
+        .collect()
+}
+
+/// 整列非空单元格都能解析为数字时视为数字列，单元格同时转为 JSON 数字
+pub(crate) fn build_table(columns: Vec<TableColumn>, records: Vec<Vec<String>>) -> TableData {
+    let mut columns = columns;
+    for (index, column) in columns.iter_mut().enumerate() {
+        let mut cells = records.iter().map(|record| record[index].trim()).filter(|cell| !cell.is_empty()).peekable();
+        if cells.peek().is_some() && cells.all(|cell| cell.parse::<f64>().map(f64::is_finite).unwrap_or(false)) {
+            column.column_type = ColumnType::Number;
+        }
+    }
+    let rows = records
+        .into_iter()
+        .map(|record| {
+            let mut row = Map::new();
+            for (column, cell) in columns.iter().zip(record) {
+                let value = match column.column_type {
+                    ColumnType::Number if !cell.trim().is_empty() => cell
+                        .trim()
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(Value::Number)
+                        .unwrap_or(Value::String(cell)),
+                    _ => Value::String(cell),
+                };
+                row.insert(column.id.clone(), value);
+            }
+            row
+        })
+        .collect();
+    TableData { columns, rows }
+}
+
+/// 首行为表头；列数不一致的行直接报错并指出行号，不做补齐
+pub(crate) fn parse_csv(bytes: &[u8], cancel: &CancelToken) -> Result<TableData, AppError> {
+    let bytes = bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new().has_headers(true).flexible(false).from_reader(bytes);
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .map(String::from)
+        .collect();
+    if headers.is_empty() {
+        return Err(AppError::InvalidInput(String::from("CSV 文件为空或缺少表头")));
+    }
+    let mut records = Vec::new();
+    for (record_index, record) in reader.records().enumerate() {
+        if record_index % CANCEL_CHECK_INTERVAL == 0 {
+            cancel.check()?;
+        }
+        records.push(record.map_err(csv_error)?.iter().map(String::from).collect());
+    }
+    Ok(build_table(build_columns(&headers), records))
+}
+
+fn csv_error(error: csv::Error) -> AppError {
+    match error.kind() {
+        csv::ErrorKind::UnequalLengths {
+            pos,
+            expected_len,
+            len,
+        } => {
+            let line = pos.as_ref().map(|pos| pos.line()).unwrap_or(0);
+            AppError::InvalidInput(format!(
+                "CSV 格式错误：第 {} 行有 {} 列，表头为 {} 列",
+                line, len, expected_len
+            ))
+        }
+        csv::ErrorKind::Utf8 { .. } => AppError::InvalidInput(format!("CSV 文件不是有效的 UTF-8: {}", error)),
+        _ => AppError::Serialization(format!("解析 CSV 失败: {}", error)),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn import_csv(
+    operations: State<'_, OperationRegistry>,
+    src_path: String,
+    operation_id: Option<String>,
+) -> Result<String, AppError> {
+    let (cancel, _guard) = operations.register(operation_id)?;
+    run_blocking(move || {
+        let path = resolve_external_path(&src_path)?;
+        let bytes = std::fs::read(&path).map_err(|e| AppError::io("读取 CSV 文件失败", e))?;
+        let table = parse_csv(&bytes, &cancel)?;
+        serde_json::to_string(&table).map_err(|e| AppError::Serialization(format!("序列化表格失败: {}", e)))
+    })
+    .await
+}
+
+const XLSX_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Excel 单元格转表格值：公式单元格读到的是文件中缓存的计算结果；
+/// 日期转为 `YYYY-MM-DD HH:MM:SS` 文本（与日期列的解析格式一致），时长按天数记为数字，错误值保留其文本（如 #DIV/0!）
+fn xlsx_cell_value(cell: &Data) -> Value {
+    match cell {
+        Data::Empty => Value::Null,
+        Data::Int(number) => Value::from(*number),
+        Data::Float(number) => serde_json::Number::from_f64(*number).map(Value::Number).unwrap_or(Value::Null),
+        Data::Bool(flag) => Value::Bool(*flag),
+        Data::String(text) => Value::String(text.clone()),
+        Data::DateTime(datetime) if datetime.is_duration() => {
+            serde_json::Number::from_f64(datetime.as_f64()).map(Value::Number).unwrap_or(Value::Null)
+        }
+        Data::DateTime(_) | Data::DateTimeIso(_) => match cell.as_datetime() {
+            Some(datetime) => Value::String(datetime.format(XLSX_DATETIME_FORMAT).to_string()),
+            None => Value::String(cell.to_string()),
+        },
+        Data::DurationIso(text) => Value::String(text.clone()),
+        Data::Error(error) => Value::String(error.to_string()),
+    }
+}
+
+/// 整列非空单元格都是数字时为数字列，都是日期时为日期列，其余为文本列；单元格保留各自的类型
+fn xlsx_column_type(cells: &[&Data]) -> ColumnType {
+    let mut values = cells.iter().filter(|cell| !cell.is_empty()).peekable();
+    if values.peek().is_none() {
+        return ColumnType::Text;
+    }
+    let values: Vec<&&Data> = values.collect();
+    if values.iter().all(|cell| matches!(cell, Data::Int(_) | Data::Float(_))) {
+        ColumnType::Number
+    } else if values
+        .iter()
+        .all(|cell| matches!(cell, Data::DateTime(datetime) if datetime.is_datetime()) || matches!(cell, Data::DateTimeIso(_)))
+    {
+        ColumnType::Date
+    } else {
+        ColumnType::Text
+    }
+}
+
+/// 读取指定工作表（缺省为第一个），首行为表头
+/// 合并单元格只有左上角带值，其余位置保持为空，不向下或向右填充
+pub(crate) fn parse_xlsx(path: &std::path::Path, sheet: Option<&str>, cancel: &CancelToken) -> Result<TableData, AppError> {
+    let mut workbook =
+        open_workbook_auto(path).map_err(|e| AppError::InvalidInput(format!("打开 Excel 文件失败: {}", e)))?;
+    let sheet_names = workbook.sheet_names();
+    let sheet_name = match sheet.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) if sheet_names.iter().any(|item| item == name) => String::from(name),
+        Some(name) => {
+            return Err(AppError::NotFound(format!(
+                "工作表不存在: {}（可用的工作表: {}）",
+                name,
+                sheet_names.join("、")
+            )))
+        }
+        None => sheet_names
+            .first()
+            .cloned()
+            .ok_or_else(|| AppError::InvalidInput(String::from("Excel 文件中没有工作表")))?,
+    };
+    let range = workbook
+        .worksheet_range(&sheet_name)
+        .map_err(|e| AppError::Serialization(format!("读取工作表 {} 失败: {}", sheet_name, e)))?;
+
+    let mut rows = range.rows();
+    let Some(header_row) = rows.next() else {
+        return Err(AppError::InvalidInput(format!("工作表为空或缺少表头: {}", sheet_name)));
+    };
+    let headers: Vec<String> = header_row.iter().map(|cell| cell.to_string()).collect();
+    let mut records: Vec<&[Data]> = Vec::new();
+    for (record_index, record) in rows.enumerate() {
+        if record_index % CANCEL_CHECK_INTERVAL == 0 {
+            cancel.check()?;
+        }
+        records.push(record);
+    }
+
+    let mut columns = build_columns(&headers);
+    for (index, column) in columns.iter_mut().enumerate() {
+        let cells: Vec<&Data> = records.iter().filter_map(|record| record.get(index)).collect();
+        column.column_type = xlsx_column_type(&cells);
+    }
+    let rows = records
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| {
+                    (column.id.clone(), record.get(index).map(xlsx_cell_value).unwrap_or(Value::Null))
+                })
+                .collect::<Map<String, Value>>()
+        })
+        .collect();
+    Ok(TableData { columns, rows })
+}
+
+/// sheet 为工作表名，缺省读取第一个工作表
+#[tauri::command]
+pub(crate) async fn import_xlsx(
+    operations: State<'_, OperationRegistry>,
+    src_path: String,
+    sheet: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, AppError> {
+    let (cancel, _guard) = operations.register(operation_id)?;
+    run_blocking(move || {
+        let path = resolve_external_path(&src_path)?;
+        let table = parse_xlsx(&path, sheet.as_deref(), &cancel)?;
+        serde_json::to_string(&table).map_err(|e| AppError::Serialization(format!("序列化表格失败: {}", e)))
+    })
+    .await
+}
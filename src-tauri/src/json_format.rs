@@ -0,0 +1,87 @@
+// 文档 JSON 的输出格式：pretty 为两空格缩进，compact 不含空白；两者都按键名排序重新序列化，
+// 同一份文档总是得到相同的字节，便于备份比较与纳入版本管理。preserve 原样写入调用方给出的文本
+// 缺省格式保存在设置项 jsonOutputFormat 中，未设置时为 preserve：默认不做规范化，与旧版本行为一致，
+// 只有把设置改为 pretty/compact，或保存命令显式传入 pretty 时才会规范化输出
+// 键名排序依赖 serde_json 的 Map 为 BTreeMap（未启用 preserve_order 特性），测试中对此做了断言
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::settings;
+
+const JSON_OUTPUT_FORMAT_SETTING: &str = "jsonOutputFormat";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum JsonOutputFormat {
+    #[default]
+    Preserve,
+    Pretty,
+    Compact,
+}
+
+pub(crate) fn default_format() -> JsonOutputFormat {
+    settings::read_setting(JSON_OUTPUT_FORMAT_SETTING).unwrap_or_default()
+}
+
+/// 命令参数的解释：preserve_bytes 为 true 时原样写入；否则按 pretty 选择格式，两者都未提供时使用设置中的缺省格式
+pub(crate) fn resolve_format(pretty: Option<bool>, preserve_bytes: Option<bool>) -> JsonOutputFormat {
+    if preserve_bytes.unwrap_or(false) {
+        return JsonOutputFormat::Preserve;
+    }
+    match pretty {
+        Some(true) => JsonOutputFormat::Pretty,
+        Some(false) => JsonOutputFormat::Compact,
+        None => default_format(),
+    }
+}
+
+/// 没有原文可保留的场景（如补丁写回）中 preserve 按 compact 输出
+pub(crate) fn render_value(value: Value, format: JsonOutputFormat) -> Result<String, AppError> {
+    let rendered = match format {
+        JsonOutputFormat::Pretty => serde_json::to_string_pretty(&value),
+        JsonOutputFormat::Compact | JsonOutputFormat::Preserve => serde_json::to_string(&value),
+    };
+    rendered.map_err(|e| AppError::Serialization(format!("序列化文档失败: {}", e)))
+}
+
+pub(crate) fn format_document(data: String, format: JsonOutputFormat) -> Result<String, AppError> {
+    if format == JsonOutputFormat::Preserve {
+        return Ok(data);
+    }
+    let value: Value =
+        serde_json::from_str(&data).map_err(|e| AppError::Serialization(format!("解析文档失败: {}", e)))?;
+    render_value(value, format)
+}
+
+#[tauri::command]
+pub(crate) fn get_json_output_format() -> JsonOutputFormat {
+    default_format()
+}
+
+#[tauri::command]
+pub(crate) fn set_json_output_format(format: JsonOutputFormat) -> Result<(), AppError> {
+    settings::write_setting(JSON_OUTPUT_FORMAT_SETTING, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendered_keys_are_sorted() {
+        let value: Value = serde_json::from_str(r#"{"b":1,"a":{"z":[{"y":1,"x":2}],"c":null}}"#).unwrap();
+        assert_eq!(
+            render_value(value, JsonOutputFormat::Compact).unwrap(),
+            r#"{"a":{"c":null,"z":[{"x":2,"y":1}]},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn preserve_returns_input_unchanged() {
+        let data = String::from("{ \"b\": 1,\n  \"a\": 2 }");
+        assert_eq!(format_document(data.clone(), JsonOutputFormat::Preserve).unwrap(), data);
+        assert_eq!(format_document(data, JsonOutputFormat::Pretty).unwrap(), "{\n  \"a\": 2,\n  \"b\": 1\n}");
+    }
+}
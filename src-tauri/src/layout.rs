@@ -0,0 +1,229 @@
+// 力导向布局（Fruchterman-Reingold）：每个连通分量单独布局，再按包围盒逐行排布，分量之间互不重叠
+// 初始位置来自固定种子的伪随机数，同一份图数据与迭代次数总是得到相同坐标
+
+use serde::Serialize;
+
+use tauri::State;
+
+use crate::error::AppError;
+use crate::graph::{parse_graph, GraphModel};
+use crate::operations::{CancelToken, OperationRegistry};
+use crate::run_blocking;
+
+const MAX_LAYOUT_ITERATIONS: u32 = 1000;
+const DEFAULT_LAYOUT_ITERATIONS: u32 = 300;
+const LAYOUT_SEED: u64 = 0x5EED_6A7B_1E00_0001;
+// 理想节点间距（像素），与前端网格布局的列宽同一量级
+const NODE_SPACING: f64 = 220.0;
+const COMPONENT_GAP: f64 = NODE_SPACING;
+const MIN_DISTANCE: f64 = 0.01;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NodePosition {
+    id: String,
+    x: f64,
+    y: f64,
+}
+
+/// SplitMix64：足够均匀且无需额外依赖，只用于生成可复现的初始坐标
+struct SeededRng(u64);
+
+impl SeededRng {
+    fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// 对单个连通分量执行布局，返回与 members 顺序一致的坐标
+fn layout_component(
+    graph: &GraphModel,
+    members: &[usize],
+    iterations: u32,
+    rng: &mut SeededRng,
+    cancel: &CancelToken,
+) -> Result<Vec<(f64, f64)>, AppError> {
+    let count = members.len();
+    if count == 1 {
+        return Ok(vec![(0.0, 0.0)]);
+    }
+    let mut local_index = vec![usize::MAX; graph.node_count()];
+    for (local, &node) in members.iter().enumerate() {
+        local_index[node] = local;
+    }
+    let links: Vec<(usize, usize)> = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.source != edge.target && local_index[edge.source] != usize::MAX)
+        .map(|edge| (local_index[edge.source], local_index[edge.target]))
+        .collect();
+
+    let side = NODE_SPACING * (count as f64).sqrt();
+    let mut positions: Vec<(f64, f64)> = (0..count).map(|_| (rng.next_f64() * side, rng.next_f64() * side)).collect();
+    let k = NODE_SPACING;
+    let mut temperature = side / 10.0;
+    let cooling = temperature / (iterations as f64 + 1.0);
+
+    for _ in 0..iterations {
+        cancel.check()?;
+        let mut displacement = vec![(0.0f64, 0.0f64); count];
+        for a in 0..count {
+            for b in (a + 1)..count {
+                let dx = positions[a].0 - positions[b].0;
+                let dy = positions[a].1 - positions[b].1;
+                let distance = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
+                let force = k * k / distance;
+                let (fx, fy) = (dx / distance * force, dy / distance * force);
+                displacement[a].0 += fx;
+                displacement[a].1 += fy;
+                displacement[b].0 -= fx;
+                displacement[b].1 -= fy;
+            }
+        }
+        for &(a, b) in &links {
+            let dx = positions[a].0 - positions[b].0;
+            let dy = positions[a].1 - positions[b].1;
+            let distance = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
+            let force = distance * distance / k;
+            let (fx, fy) = (dx / distance * force, dy / distance * force);
+            displacement[a].0 -= fx;
+            displacement[a].1 -= fy;
+            displacement[b].0 += fx;
+            displacement[b].1 += fy;
+        }
+        for (position, (dx, dy)) in positions.iter_mut().zip(displacement) {
+            let length = (dx * dx + dy * dy).sqrt().max(MIN_DISTANCE);
+            let step = length.min(temperature);
+            position.0 += dx / length * step;
+            position.1 += dy / length * step;
+        }
+        temperature = (temperature - cooling).max(MIN_DISTANCE);
+    }
+    Ok(positions)
+}
+
+/// 分量按节点数从大到小排布，每行宽度接近整体面积的平方根，超出后换行
+pub(crate) fn compute_graph_layout(
+    graph: &GraphModel,
+    iterations: u32,
+    cancel: &CancelToken,
+) -> Result<Vec<NodePosition>, AppError> {
+    let iterations = match iterations {
+        0 => DEFAULT_LAYOUT_ITERATIONS,
+        value => value.min(MAX_LAYOUT_ITERATIONS),
+    };
+    let mut rng = SeededRng(LAYOUT_SEED);
+    let mut components = graph.undirected_components();
+    components.sort_by_key(|members| std::cmp::Reverse(members.len()));
+
+    let row_limit = NODE_SPACING * (graph.node_count() as f64).sqrt() * 1.5;
+    let mut result = vec![(0.0, 0.0); graph.node_count()];
+    let (mut cursor_x, mut cursor_y, mut row_height) = (0.0f64, 0.0f64, 0.0f64);
+    for members in &components {
+        let positions = layout_component(graph, members, iterations, &mut rng, cancel)?;
+        let min_x = positions.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+        let min_y = positions.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+        let width = positions.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max) - min_x;
+        let height = positions.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max) - min_y;
+        if cursor_x > 0.0 && cursor_x + width > row_limit {
+            cursor_x = 0.0;
+            cursor_y += row_height + COMPONENT_GAP;
+            row_height = 0.0;
+        }
+        for (&node, position) in members.iter().zip(&positions) {
+            result[node] = (position.0 - min_x + cursor_x, position.1 - min_y + cursor_y);
+        }
+        cursor_x += width + COMPONENT_GAP;
+        row_height = row_height.max(height);
+    }
+
+    Ok(graph
+        .node_ids
+        .iter()
+        .zip(result)
+        .map(|(id, (x, y))| NodePosition {
+            id: id.clone(),
+            x: x.round(),
+            y: y.round(),
+        })
+        .collect())
+}
+
+/// iterations 为 0 时使用默认值，超过上限按上限计算，保证耗时有界；每轮迭代检查一次取消标记
+#[tauri::command]
+pub(crate) async fn compute_layout(
+    operations: State<'_, OperationRegistry>,
+    data: String,
+    iterations: u32,
+    operation_id: Option<String>,
+) -> Result<Vec<NodePosition>, AppError> {
+    let (cancel, _guard) = operations.register(operation_id)?;
+    run_blocking(move || compute_graph_layout(&parse_graph(&data)?, iterations, &cancel)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 两个三角形与一个孤立节点，共三个连通分量
+    fn sample_graph() -> GraphModel {
+        let nodes: Vec<_> = ["a", "b", "c", "d", "e", "f", "g"].iter().map(|id| json!({ "id": id })).collect();
+        let edges: Vec<_> = [("a", "b"), ("b", "c"), ("c", "a"), ("d", "e"), ("e", "f"), ("f", "d")]
+            .iter()
+            .enumerate()
+            .map(|(index, (source, target))| json!({ "id": format!("e{}", index), "source": source, "target": target }))
+            .collect();
+        parse_graph(&json!({ "nodes": nodes, "edges": edges }).to_string()).unwrap()
+    }
+
+    fn coordinates(positions: &[NodePosition]) -> Vec<(String, f64, f64)> {
+        positions.iter().map(|position| (position.id.clone(), position.x, position.y)).collect()
+    }
+
+    #[test]
+    fn seeded_rng_is_reproducible() {
+        let (mut first, mut second) = (SeededRng(LAYOUT_SEED), SeededRng(LAYOUT_SEED));
+        let sequence: Vec<f64> = (0..16).map(|_| first.next_f64()).collect();
+        assert_eq!(sequence, (0..16).map(|_| second.next_f64()).collect::<Vec<_>>());
+        assert!(sequence.iter().all(|value| (0.0..1.0).contains(value)));
+        let mut other = SeededRng(LAYOUT_SEED + 1);
+        assert_ne!(sequence[0], other.next_f64());
+    }
+
+    #[test]
+    fn same_graph_gives_same_positions() {
+        let graph = sample_graph();
+        let cancel = CancelToken::default();
+        let first = compute_graph_layout(&graph, 200, &cancel).unwrap();
+        let second = compute_graph_layout(&graph, 200, &cancel).unwrap();
+        assert_eq!(coordinates(&first), coordinates(&second));
+        assert_eq!(first.len(), graph.node_count());
+    }
+
+    #[test]
+    fn components_do_not_overlap() {
+        let graph = sample_graph();
+        let positions = compute_graph_layout(&graph, 200, &CancelToken::default()).unwrap();
+        let bounds = |ids: &[&str]| {
+            let points: Vec<_> = positions.iter().filter(|p| ids.contains(&p.id.as_str())).collect();
+            let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+            let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+            let min_y = points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+            let max_y = points.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max);
+            (min_x, max_x, min_y, max_y)
+        };
+        let boxes = [bounds(&["a", "b", "c"]), bounds(&["d", "e", "f"]), bounds(&["g"])];
+        for (index, a) in boxes.iter().enumerate() {
+            for b in &boxes[index + 1..] {
+                let separated = a.1 < b.0 || b.1 < a.0 || a.3 < b.2 || b.3 < a.2;
+                assert!(separated, "{:?} 与 {:?} 重叠", a, b);
+            }
+        }
+    }
+}
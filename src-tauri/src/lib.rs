@@ -3,7 +3,10 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
-use std::collections::{HashMap, HashSet};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
@@ -12,10 +15,74 @@ use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tauri::{Emitter, Manager, State};
 
+mod analysis;
+mod autosave;
+mod backups;
+mod canonical_ids;
+mod checksum;
+mod clipboard;
+mod crash_report;
+mod crypto;
+mod diagnostics;
+mod diff;
+mod disk_usage;
+mod error;
+mod export;
+mod file_lock;
+mod file_open;
+mod global_shortcut;
+mod graph;
+mod graph_window;
+mod graphml;
+mod history;
+mod i18n;
+mod image_export;
+mod import;
+mod json_format;
+mod layout;
+mod logging;
+mod mapped_read;
+mod menu;
+mod merge;
+mod metrics;
+mod migrations;
+mod operations;
+mod print;
+mod progress;
+mod recent;
+mod repair;
+mod revisions;
+mod search;
+mod settings;
+mod shutdown;
+mod single_instance;
+mod snapshot;
+mod stats;
+mod storage;
+mod storage_dir;
+mod stream_load;
+mod subgraph;
+mod table;
+mod tags;
+mod tray;
+mod updater;
+mod validate;
+mod watcher;
+mod webview_zoom;
+mod window_state;
+
+use error::AppError;
+use progress::ProgressFn;
+
 const MAX_SESSION_COUNT: usize = 10;
 const MAX_TASK_COUNT: usize = 12;
 const MAX_APPROVAL_COUNT: usize = 12;
 const MAX_EVENT_COUNT: usize = 60;
+const DEFAULT_GRAPH_NAME: &str = "graph_data";
+const BRIDGE_MANIFEST_NAME: &str = "bridge_manifest";
+// 默认数据目录中与文档同为 .json 的应用文件，不能用作文档名，也不出现在文档列表里
+const RESERVED_FILE_NAMES: [&str; 7] =
+    [BRIDGE_MANIFEST_NAME, "storage", "window_state", "recent", "tray", "settings", "tags"];
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,6 +94,31 @@ struct GraphDataInfo {
     updated_at: Option<u64>,
 }
 
+/// 关于窗口与前端的版本兼容判断使用
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppInfo {
+    app_version: &'static str,
+    schema_version: u64,
+    tauri_version: &'static str,
+    storage_dir: String,
+}
+
+/// 文档内容与文件元数据一次返回，前端据此显示“上次保存”并检测磁盘上的数据是否更新
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphMeta {
+    name: String,
+    exists: bool,
+    content: String,
+    byte_size: u64,
+    modified_at: Option<u64>,
+    // 其他进程正在写入该文档，界面可在编辑前提示
+    locked_by_other: bool,
+    // 传给 patch_graph 的 expected_revision
+    revision: u64,
+}
+
 #[derive(Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct GraphPosition {
@@ -267,7 +359,6 @@ struct BridgeRuntime {
     event_counter: u64,
     revision_counter: u64,
     subscribers: Vec<mpsc::Sender<String>>,
-    app_data_dir: PathBuf,
 }
 
 struct BridgeAppState {
@@ -313,41 +404,451 @@ fn parse_counter(id: &str, prefix: &str) -> Option<u64> {
         .or_else(|| suffix.parse::<u64>().ok())
 }
 
-fn resolve_app_data_dir() -> Result<PathBuf, String> {
+/// 默认数据目录：存放存储目录配置、bridge manifest 等不随文档迁移的文件
+fn resolve_default_data_dir() -> Result<PathBuf, AppError> {
     let app_dir = dirs_next::data_dir()
-        .ok_or("无法获取应用数据目录".to_string())?
+        .ok_or_else(|| AppError::DataDirUnavailable(String::from("无法获取应用数据目录")))?
         .join("GraphAndTable");
-    std::fs::create_dir_all(&app_dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    std::fs::create_dir_all(&app_dir).map_err(|e| AppError::DataDirUnavailable(format!("创建目录失败: {}", e)))?;
     Ok(app_dir)
 }
 
-fn resolve_graph_data_path() -> Result<PathBuf, String> {
-    Ok(resolve_app_data_dir()?.join("graph_data.json"))
+/// 文档存储目录：所有文档相关路径都经由这里解析。
+/// 配置的目录不可用（如同步盘离线）时回退到默认目录，保证应用仍可使用
+fn resolve_app_data_dir() -> Result<PathBuf, AppError> {
+    match storage_dir::configured_storage_dir() {
+        Some(dir) => Ok(dir),
+        None => resolve_default_data_dir(),
+    }
 }
 
-fn resolve_bridge_manifest_path() -> Result<PathBuf, String> {
-    Ok(resolve_app_data_dir()?.join("bridge_manifest.json"))
+/// 校验文档名：空名称回退为默认文档，拒绝路径分隔符与 `..`，防止逃逸出数据目录
+fn sanitize_graph_name(name: Option<&str>) -> Result<String, AppError> {
+    let trimmed = name.map(str::trim).unwrap_or_default();
+    if trimmed.is_empty() {
+        return Ok(String::from(DEFAULT_GRAPH_NAME));
+    }
+    if trimmed.contains("..") || trimmed.chars().any(|ch| std::path::is_separator(ch) || ch == '/' || ch == '\\') {
+        return Err(AppError::InvalidName(format!("文档名称无效: {}", trimmed)));
+    }
+    if RESERVED_FILE_NAMES.contains(&trimmed) {
+        return Err(AppError::InvalidName(format!("文档名称被保留: {}", trimmed)));
+    }
+    Ok(String::from(trimmed))
 }
 
-fn read_graph_data_file() -> GraphDataPayload {
-    let file_path = match resolve_graph_data_path() {
-        Ok(path) => path,
-        Err(_) => return GraphDataPayload::default(),
+/// 删除、重命名等破坏性操作要求显式名称，不回退到默认文档
+fn sanitize_required_graph_name(name: &str) -> Result<String, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidName(String::from("文档名称不能为空")));
+    }
+    sanitize_graph_name(Some(name))
+}
+
+fn resolve_named_graph_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}.json", name)))
+}
+
+fn resolve_compressed_graph_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}.json.gz", name)))
+}
+
+/// 文档实际所在的文件：优先 gzip 压缩格式，其次兼容旧的纯 JSON 文件
+fn resolve_existing_graph_path(name: &str) -> Result<Option<PathBuf>, AppError> {
+    let compressed_path = resolve_compressed_graph_path(name)?;
+    if compressed_path.is_file() {
+        return Ok(Some(compressed_path));
+    }
+    let plain_path = resolve_named_graph_path(name)?;
+    Ok(plain_path.is_file().then_some(plain_path))
+}
+
+fn is_compressed_graph_path(file_path: &Path) -> bool {
+    file_path.extension().map(|ext| ext == "gz").unwrap_or(false)
+}
+
+/// 对话框选择的外部文件路径：必须是绝对路径，避免相对于进程当前目录读写
+fn resolve_external_path(path: &str) -> Result<PathBuf, AppError> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::InvalidInput(String::from("文件路径不能为空")));
+    }
+    let file_path = PathBuf::from(trimmed);
+    if !file_path.is_absolute() {
+        return Err(AppError::InvalidInput(format!("文件路径必须为绝对路径: {}", trimmed)));
+    }
+    Ok(file_path)
+}
+
+fn resolve_bridge_manifest_path() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(format!("{}.json", BRIDGE_MANIFEST_NAME)))
+}
+
+/// 文档的附属文件：`<name>.json.` 前缀的 tmp/校验等文件
+fn list_graph_sidecar_paths(name: &str) -> Result<Vec<PathBuf>, AppError> {
+    let app_dir = resolve_app_data_dir()?;
+    let prefix = format!("{}.json.", name);
+    let entries = std::fs::read_dir(&app_dir).map_err(|e| AppError::io("读取目录失败", e))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .map(|file_name| file_name.to_string_lossy().starts_with(&prefix))
+                    .unwrap_or(false)
+        })
+        .collect())
+}
+
+/// 持有文档锁期间删除，完成后删除锁文件；文档不存在时不加锁，避免为它创建锁文件
+fn delete_graph_files(name: &str) -> Result<(), AppError> {
+    if storage::resolve_document_file(name)?.is_none() {
+        return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+    }
+    storage::with_retiring_document_lock(name, || delete_graph_files_locked(name))
+}
+
+fn delete_graph_files_locked(name: &str) -> Result<(), AppError> {
+    let Some(file_path) = storage::resolve_document_file(name)? else {
+        return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+    };
+    std::fs::remove_file(&file_path).map_err(|e| AppError::io("删除文件失败", e))?;
+    let plain_path = resolve_named_graph_path(name)?;
+    if plain_path.is_file() {
+        let _ = std::fs::remove_file(plain_path);
+    }
+    for sidecar_path in list_graph_sidecar_paths(name)? {
+        let _ = std::fs::remove_file(sidecar_path);
+    }
+    let _ = history::delete_history(name);
+    let _ = revisions::delete_revisions(name);
+    let _ = tags::delete_tags(name);
+    autosave::delete_recovery(name);
+    snapshot::forget(name);
+    let _ = storage::forget_backend(name);
+    backups::delete_backups(name)
+}
+
+/// 持有旧名称的文档锁期间重命名，完成后删除旧名称的锁文件
+fn rename_graph_files(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    if storage::resolve_document_file(old_name)?.is_none() {
+        return Err(AppError::NotFound(format!("文档不存在: {}", old_name)));
+    }
+    storage::with_retiring_document_lock(old_name, || rename_graph_files_locked(old_name, new_name))
+}
+
+fn rename_graph_files_locked(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    let Some(old_path) = storage::resolve_document_file(old_name)? else {
+        return Err(AppError::NotFound(format!("文档不存在: {}", old_name)));
+    };
+    // 目标已存在时直接失败，绝不覆盖已有文档
+    if storage::resolve_document_file(new_name)?.is_some() {
+        return Err(AppError::AlreadyExists(format!("目标文档已存在: {}", new_name)));
+    }
+    let new_path = if storage::backend_for(old_name) == storage::StorageBackend::Sqlite {
+        storage::resolve_sqlite_path(new_name)?
+    } else if is_compressed_graph_path(&old_path) {
+        resolve_compressed_graph_path(new_name)?
+    } else {
+        resolve_named_graph_path(new_name)?
     };
-    let json_str = match std::fs::read_to_string(file_path) {
-        Ok(contents) => contents,
-        Err(_) => return GraphDataPayload::default(),
+    std::fs::rename(&old_path, &new_path).map_err(|e| AppError::io("重命名文件失败", e))?;
+
+    let old_prefix = format!("{}.json.", old_name);
+    for sidecar_path in list_graph_sidecar_paths(old_name)? {
+        let Some(file_name) = sidecar_path.file_name().map(|item| item.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let suffix = file_name.strip_prefix(&old_prefix).unwrap_or_default();
+        let _ = std::fs::rename(&sidecar_path, sidecar_path.with_file_name(format!("{}.json.{}", new_name, suffix)));
+    }
+    let _ = history::rename_history(old_name, new_name);
+    let _ = revisions::rename_revisions(old_name, new_name);
+    let _ = tags::rename_tags(old_name, new_name);
+    let _ = autosave::rename_recovery(old_name, new_name);
+    snapshot::forget(old_name);
+    storage::rename_backend(old_name, new_name)?;
+    backups::rename_backups(old_name, new_name)
+}
+
+/// 逐字节复制文档文件，不重新序列化；新文档不继承备份、修订与撤销历史，标签按需复制
+/// SQLite 文档只复制库文件（见 storage::copy_backend）
+fn duplicate_graph_files(src_name: &str, dest_name: &str, copy_tags: bool) -> Result<(), AppError> {
+    storage::with_document_lock(src_name, || {
+        let Some(src_path) = storage::resolve_document_file(src_name)? else {
+            return Err(AppError::NotFound(format!("文档不存在: {}", src_name)));
+        };
+        if storage::resolve_document_file(dest_name)?.is_some() {
+            return Err(AppError::AlreadyExists(format!("目标文档已存在: {}", dest_name)));
+        }
+        // 清掉同名旧文档可能残留的备份与历史，保证副本从空白的版本记录开始
+        let _ = backups::delete_backups(dest_name);
+        let _ = revisions::delete_revisions(dest_name);
+        let _ = history::delete_history(dest_name);
+        if storage::backend_for(src_name) == storage::StorageBackend::Json {
+            let bytes = std::fs::read(&src_path).map_err(|e| AppError::io("读取文件失败", e))?;
+            let dest_path = if is_compressed_graph_path(&src_path) {
+                resolve_compressed_graph_path(dest_name)?
+            } else {
+                resolve_named_graph_path(dest_name)?
+            };
+            checksum::stage_checksum(dest_name, &bytes)?;
+            if let Err(error) = write_file_atomic(&dest_path, &bytes) {
+                checksum::discard_checksum(dest_name);
+                return Err(error);
+            }
+            checksum::commit_checksum(dest_name)?;
+        }
+        storage::copy_backend(src_name, dest_name)?;
+        if copy_tags {
+            tags::copy_tags(src_name, dest_name)?;
+        }
+        Ok(())
+    })
+}
+
+fn list_graph_names() -> Result<Vec<String>, AppError> {
+    let app_dir = resolve_app_data_dir()?;
+    let entries = std::fs::read_dir(&app_dir).map_err(|e| AppError::io("读取目录失败", e))?;
+    let names: BTreeSet<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let sqlite_name = file_name
+                .strip_suffix(storage::SQLITE_FILE_SUFFIX)
+                .filter(|name| storage::backend_for(name) == storage::StorageBackend::Sqlite);
+            file_name
+                .strip_suffix(".json.gz")
+                .or_else(|| file_name.strip_suffix(".json"))
+                .or(sqlite_name)
+                .map(String::from)
+        })
+        .filter(|name| !RESERVED_FILE_NAMES.contains(&name.as_str()))
+        .collect();
+    Ok(names.into_iter().collect())
+}
+
+fn resolve_temp_path(file_path: &Path) -> PathBuf {
+    // 临时文件与目标文件放在同一目录，保证 rename 不跨文件系统
+    let mut file_name = file_path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    file_name.push(".tmp");
+    file_path.with_file_name(file_name)
+}
+
+fn write_temp_file(temp_path: &Path, contents: &[u8], progress: Option<ProgressFn>) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(temp_path)?;
+    progress::write_all_with_progress(&mut file, contents, progress)?;
+    file.flush()?;
+    file.sync_all()
+}
+
+/// 原子写入：先写同目录临时文件并 fsync，再 rename 覆盖目标文件。
+/// 任一步失败都会删除临时文件，目标文件保持原样，读者不会看到写了一半的文档。
+fn write_file_atomic(file_path: &Path, contents: &[u8]) -> Result<(), AppError> {
+    write_file_atomic_with_progress(file_path, contents, None)
+}
+
+fn write_file_atomic_with_progress(
+    file_path: &Path,
+    contents: &[u8],
+    progress: Option<ProgressFn>,
+) -> Result<(), AppError> {
+    let temp_path = resolve_temp_path(file_path);
+    if let Err(e) = write_temp_file(&temp_path, contents, progress) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(AppError::io("写入临时文件失败", e));
+    }
+    if let Err(e) = std::fs::rename(&temp_path, file_path) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(AppError::io("替换文件失败", e));
+    }
+    Ok(())
+}
+
+fn compress_graph_bytes(contents: &[u8]) -> Result<Vec<u8>, AppError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(contents).map_err(|e| AppError::io("压缩数据失败", e))?;
+    encoder.finish().map_err(|e| AppError::io("压缩数据失败", e))
+}
+
+fn decompress_graph_bytes(bytes: &[u8]) -> Result<Vec<u8>, AppError> {
+    let mut contents = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut contents)
+        .map_err(|e| AppError::Serialization(format!("解压文件失败: {}", e)))?;
+    Ok(contents)
+}
+
+#[derive(Clone, Copy)]
+struct GraphWriteOptions<'a> {
+    compress: bool,
+    password: Option<&'a str>,
+    progress: Option<ProgressFn<'a>>,
+    // None 时每次覆盖都备份；Some 时最新备份距今不足该间隔则不备份（自动保存使用）
+    backup_interval: Option<Duration>,
+}
+
+impl Default for GraphWriteOptions<'_> {
+    fn default() -> Self {
+        Self {
+            compress: true,
+            password: None,
+            progress: None,
+            backup_interval: None,
+        }
+    }
+}
+
+/// 空字符串密码视为未设置
+fn normalize_password(password: Option<&str>) -> Option<&str> {
+    password.filter(|value| !value.is_empty())
+}
+
+/// 文本 → (gzip) → (加密)；先压缩再加密，密文本身不可压缩
+fn encode_graph_bytes(contents: &[u8], options: GraphWriteOptions) -> Result<Vec<u8>, AppError> {
+    let bytes = if options.compress {
+        compress_graph_bytes(contents)?
+    } else {
+        contents.to_vec()
+    };
+    match normalize_password(options.password) {
+        Some(password) => crypto::encrypt(&bytes, password),
+        None => Ok(bytes),
+    }
+}
+
+/// encode_graph_bytes 的逆过程；加密文件在未提供密码时拒绝加载
+fn decode_graph_bytes(bytes: Vec<u8>, compressed: bool, password: Option<&str>) -> Result<String, AppError> {
+    let bytes = if crypto::is_encrypted(&bytes) {
+        let Some(password) = normalize_password(password) else {
+            return Err(AppError::PasswordRequired(String::from("文档已加密，请提供密码")));
+        };
+        crypto::decrypt(&bytes, password)?
+    } else {
+        bytes
+    };
+    let bytes = if compressed { decompress_graph_bytes(&bytes)? } else { bytes };
+    String::from_utf8(bytes).map_err(|e| AppError::Serialization(format!("文件不是有效的 UTF-8: {}", e)))
+}
+
+fn read_graph_file(file_path: &Path, password: Option<&str>) -> Result<String, AppError> {
+    read_graph_file_with_progress(file_path, password, None)
+}
+
+fn read_graph_file_with_progress(
+    file_path: &Path,
+    password: Option<&str>,
+    progress: Option<ProgressFn>,
+) -> Result<String, AppError> {
+    let bytes = progress::read_with_progress(file_path, progress).map_err(|e| AppError::io("读取文件失败", e))?;
+    decode_graph_bytes(bytes, is_compressed_graph_path(file_path), password)
+}
+
+/// 读取命名文档的文本内容（自动解压/解密）；文档不存在时返回 None，与校验文件不符时返回 CHECKSUM_MISMATCH
+fn read_graph_document(name: &str, password: Option<&str>) -> Result<Option<String>, AppError> {
+    read_graph_document_with_progress(name, password, None)
+}
+
+/// 按文档所用的存储后端读取
+fn read_graph_document_with_progress(
+    name: &str,
+    password: Option<&str>,
+    progress: Option<ProgressFn>,
+) -> Result<Option<String>, AppError> {
+    storage::storage_for(name).load(name, password, progress)
+}
+
+fn read_graph_file_document(
+    name: &str,
+    password: Option<&str>,
+    progress: Option<ProgressFn>,
+) -> Result<Option<String>, AppError> {
+    let Some(file_path) = resolve_existing_graph_path(name)? else {
+        return Ok(None);
+    };
+    if let Some(contents) = mapped_read::read_plain_document(name, &file_path, progress)? {
+        return Ok(Some(contents));
+    }
+    let bytes = progress::read_with_progress(&file_path, progress).map_err(|e| AppError::io("读取文件失败", e))?;
+    checksum::ensure_checksum(name, &bytes)?;
+    decode_graph_bytes(bytes, is_compressed_graph_path(&file_path), password).map(Some)
+}
+
+fn graph_document_is_encrypted(name: &str) -> Result<bool, AppError> {
+    let Some(file_path) = resolve_existing_graph_path(name)? else {
+        return Ok(false);
+    };
+    let mut header = Vec::new();
+    std::fs::File::open(&file_path)
+        .and_then(|file| file.take(64).read_to_end(&mut header))
+        .map_err(|e| AppError::io("读取文件失败", e))?;
+    Ok(crypto::is_encrypted(&header))
+}
+
+/// 写入已编码的文档字节：先备份旧文件并写入待生效的摘要，再原子替换文档并让摘要生效；
+/// 写入成功后递增文档的 revision，并删除另一种格式的旧文件，避免过期的 .gz 遮住新保存的纯 JSON
+fn write_graph_document_bytes(
+    name: &str,
+    bytes: &[u8],
+    compressed: bool,
+    progress: Option<ProgressFn>,
+    backup_interval: Option<Duration>,
+) -> Result<PathBuf, AppError> {
+    if let Some(previous_path) = resolve_existing_graph_path(name)? {
+        backups::backup_before_overwrite(name, &previous_path, backup_interval)?;
+    }
+    let (file_path, stale_path) = if compressed {
+        (resolve_compressed_graph_path(name)?, resolve_named_graph_path(name)?)
+    } else {
+        (resolve_named_graph_path(name)?, resolve_compressed_graph_path(name)?)
+    };
+    // 摘要先于文档落盘，替换文档与更新校验文件之间崩溃也不会让新文档校验失败
+    checksum::stage_checksum(name, bytes)?;
+    if let Err(error) = write_file_atomic_with_progress(&file_path, bytes, progress) {
+        checksum::discard_checksum(name);
+        return Err(error);
+    }
+    watcher::note_self_write(name, &file_path);
+    checksum::commit_checksum(name)?;
+    storage::bump_revision(name)?;
+    if stale_path.is_file() {
+        let _ = std::fs::remove_file(stale_path);
+    }
+    Ok(file_path)
+}
+
+/// 写入命名文档的统一入口，按文档所用的存储后端写入
+fn write_graph_document(name: &str, contents: &[u8], options: GraphWriteOptions) -> Result<PathBuf, AppError> {
+    storage::storage_for(name).save(name, contents, options)
+}
+
+fn write_graph_file_document(name: &str, contents: &[u8], options: GraphWriteOptions) -> Result<PathBuf, AppError> {
+    let bytes = encode_graph_bytes(contents, options)?;
+    write_graph_document_bytes(name, &bytes, options.compress, options.progress, options.backup_interval)
+}
+
+fn read_graph_data_file() -> GraphDataPayload {
+    let json_str = match read_graph_document(DEFAULT_GRAPH_NAME, None) {
+        Ok(Some(contents)) => contents,
+        _ => return GraphDataPayload::default(),
     };
     serde_json::from_str::<GraphDataPayload>(&json_str).unwrap_or_default()
 }
 
+fn file_modified_ms(metadata: &std::fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+}
+
 fn build_graph_data_info_for_path(file_path: &Path) -> GraphDataInfo {
     let metadata = std::fs::metadata(file_path).ok();
-    let updated_at = metadata
-        .as_ref()
-        .and_then(|item| item.modified().ok())
-        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
-        .map(|duration| duration.as_millis() as u64);
+    let updated_at = metadata.as_ref().and_then(file_modified_ms);
 
     GraphDataInfo {
         backend: String::from("tauri_file"),
@@ -358,8 +859,11 @@ fn build_graph_data_info_for_path(file_path: &Path) -> GraphDataInfo {
     }
 }
 
-fn build_current_graph_data_info() -> Result<GraphDataInfo, String> {
-    let file_path = resolve_graph_data_path()?;
+fn build_current_graph_data_info() -> Result<GraphDataInfo, AppError> {
+    let file_path = match storage::resolve_document_file(DEFAULT_GRAPH_NAME)? {
+        Some(path) => path,
+        None => resolve_compressed_graph_path(DEFAULT_GRAPH_NAME)?,
+    };
     Ok(build_graph_data_info_for_path(&file_path))
 }
 
@@ -410,7 +914,7 @@ fn create_default_workspace_snapshot(graph: GraphDataPayload) -> BridgeWorkspace
 }
 
 fn write_bridge_manifest(manifest: &BridgeManifest) -> Result<(), String> {
-    let file_path = resolve_bridge_manifest_path()?;
+    let file_path = resolve_bridge_manifest_path().map_err(|e| e.to_string())?;
     let json_str = serde_json::to_string_pretty(manifest).map_err(|e| format!("序列化 bridge manifest 失败: {}", e))?;
     std::fs::write(file_path, json_str).map_err(|e| format!("写入 bridge manifest 失败: {}", e))
 }
@@ -450,6 +954,7 @@ fn ensure_data_object(value: &mut Value) -> &mut Map<String, Value> {
     value.as_object_mut().expect("node data 应始终为 object")
 }
 
+#[allow(clippy::too_many_arguments)]
 fn bridge_event(
     runtime: &mut BridgeRuntime,
     event_type: &str,
@@ -504,6 +1009,7 @@ fn begin_session(runtime: &mut BridgeRuntime, actor: &str, goal: &str, action_na
     session
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_task(
     runtime: &mut BridgeRuntime,
     session_id: &str,
@@ -537,6 +1043,7 @@ fn create_task(
     task
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_approval(
     runtime: &mut BridgeRuntime,
     session_id: &str,
@@ -648,9 +1155,15 @@ fn serialize_workspace_json(graph: &GraphDataPayload) -> Result<String, String>
 }
 
 fn persist_workspace_snapshot(runtime: &mut BridgeRuntime) -> Result<(), String> {
-    let file_path = resolve_graph_data_path()?;
     let json_str = serialize_workspace_json(&runtime.workspace.graph)?;
-    std::fs::write(file_path, json_str).map_err(|e| format!("写入文件失败: {}", e))
+    storage::with_document_lock(DEFAULT_GRAPH_NAME, || {
+        // bridge 无法获得用户密码，不能用明文覆盖已加密的文档
+        if graph_document_is_encrypted(DEFAULT_GRAPH_NAME)? {
+            return Err(AppError::PasswordRequired(String::from("当前文档已加密，请在应用内保存。")));
+        }
+        write_graph_document(DEFAULT_GRAPH_NAME, json_str.as_bytes(), GraphWriteOptions::default()).map(|_| ())
+    })
+    .map_err(|e| e.to_string())
 }
 
 fn selected_subgraph(graph: &GraphDataPayload, selected_ids: &[String]) -> Option<GraphDataPayload> {
@@ -744,7 +1257,7 @@ fn handle_query(shared: &Arc<Mutex<BridgeRuntime>>, name: &str, input: &Value) -
         "describe_active_workspace" => result_ok(json!(runtime.workspace.summary)),
         "describe_persistence_target" => match build_current_graph_data_info() {
             Ok(info) => result_ok(json!(info)),
-            Err(error) => result_error("EXTERNAL_FAILURE", &error, true, None),
+            Err(error) => result_error("EXTERNAL_FAILURE", error.message(), true, None),
         },
         "get_execution_state" => result_ok(json!(runtime.execution_state)),
         "list_pending_approvals" => {
@@ -902,12 +1415,13 @@ fn handle_action(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, nam
                             session_ref.ended_at = Some(now_ms());
                             session_ref.updated_at = now_ms();
                         }
+                        let revision = runtime.workspace.revision.clone();
                         if let Some(task_ref) = find_task_mut(&mut runtime, &task.id) {
                             task_ref.status = String::from("succeeded");
                             task_ref.message = String::from("工作区已保存到持久化后端。");
                             task_ref.progress_percent = 100;
                             task_ref.completed_at = Some(now_ms());
-                            task_ref.output_summary = Some(format!("revision={}", runtime.workspace.revision));
+                            task_ref.output_summary = Some(format!("revision={}", revision));
                             task_ref.updated_at = now_ms();
                         }
                         let completed_event = bridge_event(
@@ -1039,16 +1553,15 @@ fn handle_action(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, nam
                     session_ref.ended_at = Some(now_ms());
                     session_ref.updated_at = now_ms();
                 }
+                let node_count = runtime.workspace.graph.nodes.len();
+                let edge_count = runtime.workspace.graph.edges.len();
+                let revision = runtime.workspace.revision.clone();
                 if let Some(task_ref) = find_task_mut(&mut runtime, &task.id) {
                     task_ref.status = String::from("succeeded");
-                    task_ref.message = format!(
-                        "导入完成：{} 个节点，{} 条连线。",
-                        runtime.workspace.graph.nodes.len(),
-                        runtime.workspace.graph.edges.len()
-                    );
+                    task_ref.message = format!("导入完成：{} 个节点，{} 条连线。", node_count, edge_count);
                     task_ref.progress_percent = 100;
                     task_ref.completed_at = Some(now_ms());
-                    task_ref.output_summary = Some(format!("revision={}", runtime.workspace.revision));
+                    task_ref.output_summary = Some(format!("revision={}", revision));
                     task_ref.updated_at = now_ms();
                 }
                 let completed_event = bridge_event(
@@ -1109,6 +1622,7 @@ fn handle_action(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, nam
                     session_ref.updated_at = now_ms();
                 }
 
+                let revision = runtime.workspace.revision.clone();
                 let approval = create_approval(
                     &mut runtime,
                     &session.id,
@@ -1125,7 +1639,7 @@ fn handle_action(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, nam
                     json!({
                         "scope": scope,
                         "filename": filename,
-                        "expectedRevision": runtime.workspace.revision,
+                        "expectedRevision": revision,
                         "reason": reason,
                     }),
                 );
@@ -1154,7 +1668,7 @@ fn handle_action(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, nam
                     Some(session.id.clone()),
                     Some(task.id.clone()),
                     Some(approval.id.clone()),
-                    Some(json!({ "scope": scope, "revision": runtime.workspace.revision })),
+                    Some(json!({ "scope": scope, "revision": revision })),
                 );
 
                 should_publish = true;
@@ -1640,7 +2154,8 @@ fn handle_approval(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, n
                     .unwrap_or_default()
                     .to_string();
 
-                if runtime.workspace.revision != expected_revision {
+                let current_revision = runtime.workspace.revision.clone();
+                if current_revision != expected_revision {
                     if let Some(approval_ref) = find_approval_mut(&mut runtime, &approval.id) {
                         approval_ref.status = String::from("expired");
                         approval_ref.resolved_by = Some(String::from(actor));
@@ -1659,7 +2174,7 @@ fn handle_approval(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, n
                             retryable: true,
                             details: Some(json!({
                                 "expectedRevision": expected_revision,
-                                "currentRevision": runtime.workspace.revision,
+                                "currentRevision": current_revision,
                             })),
                         });
                     }
@@ -1680,7 +2195,7 @@ fn handle_approval(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, n
                         Some(approval.id.clone()),
                         Some(json!({
                             "expectedRevision": expected_revision,
-                            "currentRevision": runtime.workspace.revision,
+                            "currentRevision": current_revision,
                         })),
                     );
                     should_publish = true;
@@ -1692,7 +2207,7 @@ fn handle_approval(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, n
                             "retryable": true,
                             "details": {
                                 "expectedRevision": expected_revision,
-                                "currentRevision": runtime.workspace.revision,
+                                "currentRevision": current_revision,
                             }
                         },
                         "task": get_task(&runtime, &approval.task_id),
@@ -1761,7 +2276,7 @@ fn handle_approval(shared: &Arc<Mutex<BridgeRuntime>>, app: &tauri::AppHandle, n
                         Some(approval.session_id.clone()),
                         Some(approval.task_id.clone()),
                         Some(approval.id.clone()),
-                        Some(json!({ "scope": scope, "revision": runtime.workspace.revision })),
+                        Some(json!({ "scope": scope, "revision": current_revision })),
                     );
 
                     let result = serialize_workspace_json(&export_graph)
@@ -1933,7 +2448,9 @@ fn handle_bridge_request(
     }
 }
 
-fn read_http_request(stream: &mut TcpStream) -> Result<(String, String, HashMap<String, String>, Vec<u8>), String> {
+type HttpRequestParts = (String, String, HashMap<String, String>, Vec<u8>);
+
+fn read_http_request(stream: &mut TcpStream) -> Result<HttpRequestParts, String> {
     stream
         .set_read_timeout(Some(Duration::from_secs(3)))
         .map_err(|e| format!("设置读取超时失败: {}", e))?;
@@ -2101,7 +2618,7 @@ fn start_bridge_server(app: &tauri::AppHandle, shared: Arc<Mutex<BridgeRuntime>>
         .set_nonblocking(true)
         .map_err(|e| format!("设置本地 bridge 监听器失败: {}", e))?;
 
-    let manifest_path = resolve_bridge_manifest_path()?;
+    let manifest_path = resolve_bridge_manifest_path().map_err(|e| e.to_string())?;
     let base_url = format!("http://127.0.0.1:{}", port);
     let manifest = BridgeManifest {
         version: String::from("0.1.0"),
@@ -2143,27 +2660,327 @@ fn start_bridge_server(app: &tauri::AppHandle, shared: Arc<Mutex<BridgeRuntime>>
     Ok(manifest)
 }
 
+/// 在阻塞线程池中执行磁盘 IO，避免大文件读写占住 Tauri 的命令线程
+async fn run_blocking<T, F>(task: F) -> Result<T, AppError>
+where
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| AppError::Io(format!("后台任务执行失败: {}", e)))?
+}
+
+fn save_named_graph(name: Option<&str>, data: &str, options: GraphWriteOptions) -> Result<PathBuf, AppError> {
+    let graph_name = sanitize_graph_name(name)?;
+    let file_path = storage::with_document_lock(&graph_name, || {
+        let file_path = write_graph_document(&graph_name, data.as_bytes(), options)?;
+        if normalize_password(options.password).is_none() {
+            if let Err(error) = revisions::record_revision(&graph_name, data.as_bytes()) {
+                log::warn!("记录修订失败 {}: {}", graph_name, error);
+            }
+        }
+        Ok(file_path)
+    })?;
+    autosave::clear_recovery(&graph_name);
+    snapshot::forget(&graph_name);
+    let _ = recent::touch(&graph_name);
+    Ok(file_path)
+}
+
+fn load_named_graph(
+    name: Option<&str>,
+    password: Option<&str>,
+    progress: Option<ProgressFn>,
+) -> Result<String, AppError> {
+    let graph_name = sanitize_graph_name(name)?;
+    let Some(contents) = read_graph_document_with_progress(&graph_name, password, progress)? else {
+        return Ok(String::from("{}"));
+    };
+    let _ = recent::touch(&graph_name);
+    Ok(migrations::migrate_document_text(&contents).unwrap_or(contents))
+}
+
+/// 先取元数据再读内容：读取期间若被外部修改，前端拿到的 modifiedAt 偏旧，下次比较时仍会判定为有更新
+fn load_named_graph_meta(name: &str, password: Option<&str>) -> Result<GraphMeta, AppError> {
+    let graph_name = sanitize_required_graph_name(name)?;
+    let metadata = storage::resolve_document_file(&graph_name)?.and_then(|path| std::fs::metadata(path).ok());
+    let Some(metadata) = metadata else {
+        return Ok(GraphMeta {
+            name: graph_name,
+            exists: false,
+            content: String::new(),
+            byte_size: 0,
+            modified_at: None,
+            locked_by_other: false,
+            revision: 0,
+        });
+    };
+    let revision = storage::current_revision(&graph_name)?;
+    let content = load_named_graph(Some(&graph_name), password, None)?;
+    Ok(GraphMeta {
+        locked_by_other: file_lock::locked_by_other(&graph_name),
+        revision,
+        name: graph_name,
+        exists: true,
+        content,
+        byte_size: metadata.len(),
+        modified_at: file_modified_ms(&metadata),
+    })
+}
+
+/// 日志中定位文档用：返回已存在文件的路径，不存在时返回默认格式的目标路径
+fn describe_graph_location(name: Option<&str>) -> String {
+    let Ok(graph_name) = sanitize_graph_name(name) else {
+        return format!("<无效名称 {:?}>", name.unwrap_or_default());
+    };
+    resolve_existing_graph_path(&graph_name)
+        .ok()
+        .flatten()
+        .or_else(|| resolve_compressed_graph_path(&graph_name).ok())
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or(graph_name)
+}
+
+/// 进度事件中的文档名：与保存/加载实际使用的名称一致，未传名称时为默认文档
+fn describe_progress_name(name: Option<&str>) -> String {
+    sanitize_graph_name(name).unwrap_or_else(|_| String::from(name.unwrap_or_default()))
+}
+
+/// compress 缺省为 true，调试时可传 false 写出纯 JSON；提供 password 时加密保存
+/// 写入前校验文档结构，前端有意保存后端尚不认识的新结构时可传 skip_validation 跳过
+/// pretty 指定按缩进或紧凑格式规范化输出，未指定时使用设置中的缺省格式；preserve_bytes 为 true 时原样写入
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn save_graph_data(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    data: String,
+    compress: Option<bool>,
+    password: Option<String>,
+    skip_validation: Option<bool>,
+    pretty: Option<bool>,
+    preserve_bytes: Option<bool>,
+) -> Result<String, AppError> {
+    let byte_size = data.len();
+    if let Err(error) = validate::check_document_size(byte_size) {
+        log::error!("save_graph_data 拒绝: name={:?} code={} error={}", name, error.code(), error);
+        return Err(error);
+    }
+    log::info!(
+        "save_graph_data 开始: name={:?} bytes={} encrypted={}",
+        name,
+        byte_size,
+        password.as_deref().is_some_and(|value| !value.is_empty())
+    );
+    let log_name = name.clone();
+    let report_progress = progress::emitter(app.clone(), "save", describe_progress_name(name.as_deref()));
+    let result = run_blocking(move || {
+        let graph_name = sanitize_graph_name(name.as_deref())?;
+        let format = json_format::resolve_format(pretty, preserve_bytes);
+        let validate::PreparedDocument {
+            data,
+            dropped_edges,
+            type_violations,
+        } = if skip_validation.unwrap_or(false) {
+            validate::PreparedDocument {
+                data: json_format::format_document(data, format)?,
+                dropped_edges: Vec::new(),
+                type_violations: (0, Vec::new()),
+            }
+        } else {
+            validate::prepare_document(data, format)?
+        };
+        // 显式保存的内容最新，丢弃尚未写出的自动保存，免得稍后被旧内容覆盖
+        autosave::discard(&graph_name);
+        let options = GraphWriteOptions {
+            compress: compress.unwrap_or(true),
+            password: password.as_deref(),
+            progress: Some(&report_progress),
+            ..GraphWriteOptions::default()
+        };
+        let file_path = save_named_graph(Some(&graph_name), &data, options)?;
+        Ok((file_path, graph_name, dropped_edges, type_violations))
+    })
+    .await;
+    match result {
+        Ok((file_path, graph_name, dropped_edges, (total, violations))) => {
+            log::info!("save_graph_data 完成: path={} bytes={}", file_path.to_string_lossy(), byte_size);
+            // 返回值仍是保存路径，剔除的悬空连线与列类型不符的单元格经事件告知前端
+            if total > 0 {
+                log::warn!("save_graph_data 列类型不符: name={} cells={}", graph_name, total);
+                let _ = app.emit(
+                    validate::COLUMN_TYPE_VIOLATIONS_EVENT,
+                    validate::ColumnTypeViolations {
+                        name: graph_name.clone(),
+                        total,
+                        violations,
+                    },
+                );
+            }
+            if !dropped_edges.is_empty() {
+                log::warn!("save_graph_data 剔除悬空连线: name={} edges={:?}", graph_name, dropped_edges);
+                let _ = app.emit(
+                    validate::DANGLING_EDGES_DROPPED_EVENT,
+                    validate::DanglingEdgesDropped {
+                        name: graph_name,
+                        edge_ids: dropped_edges,
+                    },
+                );
+            }
+            Ok(file_path.to_string_lossy().to_string())
+        }
+        Err(error) => {
+            log::error!(
+                "save_graph_data 失败: path={} code={} error={}",
+                describe_graph_location(log_name.as_deref()),
+                error.code(),
+                error
+            );
+            Err(error)
+        }
+    }
+}
+
 #[tauri::command]
-fn save_graph_data(data: String) -> Result<String, String> {
-    let file_path = resolve_graph_data_path()?;
-    std::fs::write(&file_path, &data).map_err(|e| format!("写入文件失败: {}", e))?;
-    Ok(file_path.to_string_lossy().to_string())
+async fn load_graph_data(
+    app: tauri::AppHandle,
+    name: Option<String>,
+    password: Option<String>,
+) -> Result<String, AppError> {
+    log::info!("load_graph_data 开始: name={:?}", name);
+    let log_name = name.clone();
+    let report_progress = progress::emitter(app, "load", describe_progress_name(name.as_deref()));
+    let result =
+        run_blocking(move || load_named_graph(name.as_deref(), password.as_deref(), Some(&report_progress))).await;
+    let location = describe_graph_location(log_name.as_deref());
+    match &result {
+        Ok(contents) => log::info!("load_graph_data 完成: path={} bytes={}", location, contents.len()),
+        // 未提供密码是正常的交互流程，不算错误
+        Err(error @ AppError::PasswordRequired(_)) => {
+            log::warn!("load_graph_data 需要密码: path={} code={}", location, error.code())
+        }
+        Err(error) => log::error!("load_graph_data 失败: path={} code={} error={}", location, error.code(), error),
+    }
+    result
 }
 
 #[tauri::command]
-fn load_graph_data() -> Result<String, String> {
-    let file_path = resolve_graph_data_path()?;
-    if !file_path.exists() {
-        return Ok(String::from("{}"));
+async fn load_graph_meta(name: String, password: Option<String>) -> Result<GraphMeta, AppError> {
+    run_blocking(move || load_named_graph_meta(&name, password.as_deref())).await
+}
+
+/// “另存为”：按对话框选择的路径写出纯 JSON，不经过数据目录，也不记入最近文档
+#[tauri::command]
+async fn save_to_path(data: String, path: String) -> Result<(), AppError> {
+    run_blocking(move || {
+        let file_path = resolve_external_path(&path)?;
+        validate::validate_document(&data)?;
+        write_file_atomic(&file_path, data.as_bytes())
+    })
+    .await
+}
+
+/// 文件不存在时返回 NOT_FOUND；.gz 文件自动解压，旧版本结构执行迁移
+fn load_external_graph(path: &str) -> Result<String, AppError> {
+    let file_path = resolve_external_path(path)?;
+    if !file_path.is_file() {
+        return Err(AppError::NotFound(format!("文件不存在: {}", file_path.to_string_lossy())));
     }
-    std::fs::read_to_string(&file_path).map_err(|e| format!("读取文件失败: {}", e))
+    let contents = read_graph_file(&file_path, None)?;
+    Ok(migrations::migrate_document_text(&contents).unwrap_or(contents))
 }
 
+/// “打开…”
 #[tauri::command]
-fn get_graph_data_info() -> Result<GraphDataInfo, String> {
+async fn load_from_path(path: String) -> Result<String, AppError> {
+    run_blocking(move || load_external_graph(&path)).await
+}
+
+#[tauri::command]
+fn list_graphs() -> Result<Vec<String>, AppError> {
+    list_graph_names()
+}
+
+#[tauri::command]
+fn delete_graph(name: String) -> Result<(), AppError> {
+    let graph_name = sanitize_required_graph_name(&name)?;
+    delete_graph_files(&graph_name)
+}
+
+#[tauri::command]
+fn rename_graph(old: String, new: String) -> Result<(), AppError> {
+    let old_name = sanitize_required_graph_name(&old)?;
+    let new_name = sanitize_required_graph_name(&new)?;
+    if old_name == new_name {
+        return Ok(());
+    }
+    rename_graph_files(&old_name, &new_name)
+}
+
+/// copy_tags 缺省为 false
+#[tauri::command]
+async fn duplicate_graph(src: String, dest: String, copy_tags: Option<bool>) -> Result<(), AppError> {
+    run_blocking(move || {
+        let src_name = sanitize_required_graph_name(&src)?;
+        let dest_name = sanitize_required_graph_name(&dest)?;
+        if src_name == dest_name {
+            return Err(AppError::AlreadyExists(format!("目标文档已存在: {}", dest_name)));
+        }
+        duplicate_graph_files(&src_name, &dest_name, copy_tags.unwrap_or(false))
+    })
+    .await
+}
+
+#[tauri::command]
+fn list_backups(name: Option<String>) -> Result<Vec<String>, AppError> {
+    let graph_name = sanitize_graph_name(name.as_deref())?;
+    let mut file_names: Vec<String> = backups::list_backup_entries(&graph_name)?
+        .into_iter()
+        .map(|entry| entry.file_name)
+        .collect();
+    file_names.reverse();
+    Ok(file_names)
+}
+
+#[tauri::command]
+async fn restore_backup(file_name: String, password: Option<String>) -> Result<String, AppError> {
+    run_blocking(move || {
+        let (graph_name, backup_path) = backups::resolve_backup_file(&file_name)?;
+        let graph_name = sanitize_graph_name(Some(&graph_name))?;
+        let bytes = std::fs::read(&backup_path).map_err(|e| AppError::io("读取备份失败", e))?;
+        let compressed = is_compressed_graph_path(&backup_path);
+        let encrypted = crypto::is_encrypted(&bytes);
+        let contents = decode_graph_bytes(bytes, compressed, password.as_deref())?;
+        // 与显式保存走同一路径：按备份原有的压缩/加密格式写回并记录修订，当前内容会先被备份，恢复操作可以撤回；
+        // 丢弃待写的自动保存，免得稍后被旧内容覆盖
+        autosave::discard(&graph_name);
+        let options = GraphWriteOptions {
+            compress: compressed,
+            password: if encrypted { password.as_deref() } else { None },
+            ..GraphWriteOptions::default()
+        };
+        save_named_graph(Some(&graph_name), &contents, options)?;
+        Ok(contents)
+    })
+    .await
+}
+
+#[tauri::command]
+fn get_graph_data_info() -> Result<GraphDataInfo, AppError> {
     build_current_graph_data_info()
 }
 
+#[tauri::command]
+fn app_info() -> Result<AppInfo, AppError> {
+    Ok(AppInfo {
+        app_version: env!("CARGO_PKG_VERSION"),
+        schema_version: migrations::CURRENT_SCHEMA_VERSION,
+        tauri_version: tauri::VERSION,
+        storage_dir: resolve_app_data_dir()?.to_string_lossy().to_string(),
+    })
+}
+
 #[tauri::command]
 fn bridge_status(state: State<BridgeAppState>) -> Result<BridgeStatus, String> {
     let runtime = state.inner.lock().map_err(|_| "bridge 状态已损坏".to_string())?;
@@ -2207,22 +3024,130 @@ fn bridge_approval(app: tauri::AppHandle, state: State<BridgeAppState>, name: St
 }
 
 pub fn run() {
+    crash_report::install();
     tauri::Builder::default()
+        .plugin(single_instance::plugin())
+        .plugin(logging::plugin())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
-        .invoke_handler(tauri::generate_handler![
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(global_shortcut::plugin())
+        .plugin(webview_zoom::plugin())
+        .invoke_handler(crash_report::track_commands(tauri::generate_handler![
             save_graph_data,
             load_graph_data,
+            load_graph_meta,
+            stream_load::load_graph_streamed,
+            save_to_path,
+            load_from_path,
+            list_graphs,
+            delete_graph,
+            rename_graph,
+            duplicate_graph,
+            list_backups,
+            restore_backup,
+            repair::repair_graph,
+            disk_usage::storage_usage,
+            disk_usage::prune_all_backups,
+            backups::prune_backups,
+            backups::get_backup_retention,
+            backups::set_backup_retention,
             get_graph_data_info,
+            app_info,
+            logging::get_log_path,
+            export::export_csv,
+            export::export_markdown,
+            print::print_document,
+            print::print_to_pdf,
+            export::export_xlsx,
+            export::export_dot,
+            subgraph::export_subgraph,
+            export::export_bundle,
+            image_export::save_png,
+            image_export::save_png_to_dir,
+            stats::compute_stats,
+            layout::compute_layout,
+            analysis::shortest_path,
+            analysis::find_cycles,
+            analysis::topo_sort,
+            analysis::connected_components,
+            metrics::graph_metrics,
+            metrics::centrality,
+            diff::diff_graphs,
+            merge::merge_graphs,
+            history::push_state,
+            history::undo,
+            history::redo,
+            revisions::list_revisions,
+            revisions::load_revision,
+            menu::set_undo_redo_enabled,
+            tray::get_minimize_to_tray,
+            tray::set_minimize_to_tray,
+            global_shortcut::get_global_shortcut,
+            global_shortcut::set_global_shortcut,
+            global_shortcut::clear_global_shortcut,
+            webview_zoom::set_pinch_zoom,
+            webview_zoom::save_zoom,
+            webview_zoom::load_zoom,
+            graph_window::open_graph_window,
+            autosave::queue_autosave,
+            autosave::check_recovery,
+            autosave::restore_recovery,
+            autosave::discard_recovery,
+            autosave::flush_autosave,
+            snapshot::mark_dirty,
+            snapshot::get_snapshot_interval,
+            snapshot::set_snapshot_interval,
+            file_open::take_pending_open_files,
+            storage::patch_graph,
+            storage::update_nodes,
+            storage::delete_nodes,
+            storage::get_storage_backend,
+            storage::set_storage_backend,
+            settings::get_setting,
+            settings::set_setting,
+            json_format::get_json_output_format,
+            json_format::set_json_output_format,
+            validate::get_dangling_edge_policy,
+            validate::set_dangling_edge_policy,
+            validate::get_column_type_policy,
+            validate::set_column_type_policy,
+            crash_report::get_pending_crashes,
+            crash_report::clear_crashes,
+            crash_report::get_crash_reports_enabled,
+            crash_report::set_crash_reports_enabled,
+            diagnostics::self_test,
+            i18n::get_locale,
+            search::search_graph,
+            updater::check_for_updates,
+            updater::install_update,
+            checksum::verify_graph,
+            operations::cancel_operation,
+            import::import_csv,
+            import::import_xlsx,
+            graphml::import_graphml,
+            clipboard::copy_table_tsv,
+            clipboard::paste_table_tsv,
+            watcher::watch_graph,
+            watcher::stop_watch,
+            storage_dir::get_storage_dir,
+            storage_dir::set_storage_dir,
+            storage_dir::reveal_in_explorer,
+            recent::get_recent,
+            recent::clear_recent,
+            tags::set_tags,
+            tags::get_tags,
+            tags::list_graphs_by_tag,
             bridge_status,
             bridge_sync_state,
             bridge_query,
             bridge_action,
             bridge_approval
-        ])
+        ]))
         .setup(|app| {
-            let app_data_dir = resolve_app_data_dir()?;
+            resolve_default_data_dir()?;
             let initial_graph = read_graph_data_file();
             let shared = Arc::new(Mutex::new(BridgeRuntime {
                 manifest: BridgeManifest {
@@ -2250,37 +3175,178 @@ pub fn run() {
                 event_counter: 1,
                 revision_counter: 1,
                 subscribers: Vec::new(),
-                app_data_dir,
             }));
 
-            let _manifest = start_bridge_server(&app.handle(), Arc::clone(&shared))?;
+            let _manifest = start_bridge_server(app.handle(), Arc::clone(&shared))?;
             app.manage(BridgeAppState { inner: shared });
+            settings::load();
+            app.manage(watcher::WatchState::default());
+            app.manage(operations::OperationRegistry::default());
+            app.manage(graph_window::GraphWindows::default());
+            app.manage(updater::UpdateState::default());
+            updater::check_on_startup(app.handle());
+            autosave::install(app.handle());
+            snapshot::install();
+            menu::install(app)?;
+            // 部分 Linux 桌面没有托盘支持，创建失败时只记录日志，不影响启动
+            if let Err(error) = tray::install(app) {
+                log::warn!("创建托盘图标失败: {}", error);
+            }
+            global_shortcut::install(app.handle());
 
-            // 在 Windows 上启用 WebView2 的 pinch zoom
-            #[cfg(target_os = "windows")]
-            {
-                use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Settings5;
-                use windows_core::Interface;
-
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.with_webview(|webview| {
-                        unsafe {
-                            let core = webview.controller().CoreWebView2().unwrap();
-                            let settings = core.Settings().unwrap();
-                            if let Ok(settings5) = settings.cast::<ICoreWebView2Settings5>() {
-                                // 启用 WebView2 的 pinch zoom，前端通过 visualViewport 检测缩放变化
-                                let _ = settings5.SetIsPinchZoomEnabled(true);
-                            }
-                        }
-                    });
-                }
+            if let Some(window) = app.get_webview_window("main") {
+                window_state::install(&window);
+                file_open::install(&window);
+            }
+
+            // Windows / Linux 双击关联文件时，文件路径在启动参数中
+            let cwd = std::env::current_dir()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let args = std::env::args().skip(1).map(|arg| single_instance::normalize_arg(&arg, &cwd));
+            file_open::open_paths(app.handle(), None, file_open::graph_paths_from_args(args));
+
+            // 按设置启用或关闭 WebView2 的 pinch zoom，前端通过 visualViewport 检测缩放变化
+            // 页面缩放比例恢复为上次保存的值
+            if let Some(window) = app.get_webview_window("main") {
+                webview_zoom::apply_pinch_zoom(&window, webview_zoom::pinch_zoom_enabled());
+                webview_zoom::apply_zoom_level(&window, webview_zoom::stored_zoom_level());
             }
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("启动 Tauri 应用失败");
+        .build(tauri::generate_context!())
+        .expect("启动 Tauri 应用失败")
+        .run(|_app, event| match event {
+            // 关闭最后一个窗口或托盘退出时先完成写出与释放锁，再真正退出
+            tauri::RunEvent::ExitRequested { code, api, .. } => shutdown::handle_exit_requested(_app, &api, code),
+            // 兜底：正常流程中 ExitRequested 已写完，这里通常无事可做
+            tauri::RunEvent::Exit => {
+                if let Err(error) = autosave::flush_pending() {
+                    log::error!("退出前写出自动保存失败: {}", error);
+                }
+                if let Err(error) = settings::flush() {
+                    log::error!("退出前写出设置失败: {}", error);
+                }
+            }
+            // macOS 双击关联文件（包括启动时）通过 Opened 事件送达
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                let paths = urls.iter().filter_map(|url| url.to_file_path().ok()).collect();
+                file_open::open_paths(_app, None, paths);
+            }
+            _ => {}
+        });
 
     // 应用退出时清理 bridge manifest，避免 MCP Server 连接过期端口
     remove_bridge_manifest();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SCRATCH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// 每个测试独占的临时目录，进程号加计数器保证并行测试互不干扰
+    pub(crate) fn scratch_dir(label: &str) -> PathBuf {
+        let index = SCRATCH_COUNTER.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("gt-test-{}-{}-{}", std::process::id(), label, index));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("创建测试目录失败");
+        dir
+    }
+
+    #[test]
+    fn atomic_write_replaces_target() {
+        let dir = scratch_dir("atomic-replace");
+        let target = dir.join("graph.json");
+        std::fs::write(&target, b"old").unwrap();
+        write_file_atomic(&target, b"new").unwrap();
+        assert_eq!(std::fs::read(&target).unwrap(), b"new");
+        assert!(!resolve_temp_path(&target).exists());
+    }
+
+    #[test]
+    fn failed_temp_write_keeps_original() {
+        let dir = scratch_dir("atomic-fail");
+        let target = dir.join("graph.json");
+        std::fs::write(&target, b"original").unwrap();
+        // 临时文件路径被目录占用，创建临时文件必然失败
+        std::fs::create_dir(resolve_temp_path(&target)).unwrap();
+        assert!(write_file_atomic(&target, b"replacement").is_err());
+        assert_eq!(std::fs::read(&target).unwrap(), b"original");
+    }
+
+    /// 约 size 字节的图文档，节点文本各不相同，压缩后仍有一定体积
+    fn large_document(size: usize) -> String {
+        let mut nodes = Vec::new();
+        let mut total = 0;
+        let mut index = 0;
+        while total < size {
+            let node = json!({
+                "id": format!("node-{}", index),
+                "data": { "label": format!("节点 {} 的说明文本 {}", index, "x".repeat(index % 97)) },
+            });
+            total += node.to_string().len();
+            nodes.push(node);
+            index += 1;
+        }
+        json!({ "schemaVersion": 1, "nodes": nodes, "edges": [] }).to_string()
+    }
+
+    /// 与 save/load 命令一样在阻塞线程池中写入再读回
+    fn round_trip_large_document(compress: bool) {
+        let dir = scratch_dir("large-round-trip");
+        let contents = large_document(50 * 1024 * 1024);
+        let target = dir.join(if compress { "large.json.gz" } else { "large.json" });
+        let expected = contents.clone();
+        let loaded = tauri::async_runtime::block_on(run_blocking(move || {
+            let options = GraphWriteOptions {
+                compress,
+                ..Default::default()
+            };
+            let bytes = encode_graph_bytes(contents.as_bytes(), options)?;
+
+            let written = std::cell::Cell::new(0);
+            let on_write = |processed: u64, _total: u64| written.set(processed);
+            write_file_atomic_with_progress(&target, &bytes, Some(&on_write))?;
+            assert_eq!(written.get(), bytes.len() as u64);
+
+            let read = std::cell::Cell::new((0, 0));
+            let on_read = |processed: u64, total: u64| read.set((processed, total));
+            let loaded = read_graph_file_with_progress(&target, None, Some(&on_read))?;
+            assert_eq!(read.get(), (bytes.len() as u64, bytes.len() as u64));
+            Ok(loaded)
+        }))
+        .unwrap();
+        assert_eq!(loaded, expected);
+    }
+
+    #[test]
+    fn compression_shrinks_repetitive_documents() {
+        let contents = large_document(1024 * 1024);
+        let compressed = compress_graph_bytes(contents.as_bytes()).unwrap();
+        // 重复度高的图文档应至少压缩到原大小的四分之一
+        assert!(compressed.len() * 4 < contents.len(), "{} -> {}", contents.len(), compressed.len());
+        assert_eq!(decompress_graph_bytes(&compressed).unwrap(), contents.as_bytes());
+    }
+
+    #[test]
+    fn compressed_file_decodes_to_original_string() {
+        let contents = large_document(64 * 1024);
+        let bytes = encode_graph_bytes(contents.as_bytes(), GraphWriteOptions::default()).unwrap();
+        assert_eq!(decode_graph_bytes(bytes, true, None).unwrap(), contents);
+    }
+
+    #[test]
+    fn large_plain_document_round_trips_with_progress() {
+        round_trip_large_document(false);
+    }
+
+    #[test]
+    fn large_compressed_document_round_trips_with_progress() {
+        round_trip_large_document(true);
+    }
+}
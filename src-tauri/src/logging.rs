@@ -0,0 +1,52 @@
+// 运行日志：写入默认数据目录下 logs/graphandtable.log，超过大小上限后轮转，只保留最近几份
+// 日志中只记录文档名、路径和字节数，绝不写入文档内容
+
+use log::LevelFilter;
+use std::path::PathBuf;
+use tauri::Runtime;
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+use crate::error::AppError;
+use crate::resolve_default_data_dir;
+
+const LOG_DIR_NAME: &str = "logs";
+const LOG_FILE_STEM: &str = "graphandtable";
+const MAX_LOG_FILE_SIZE: u128 = 2 * 1024 * 1024;
+const KEEP_LOG_FILES: usize = 5;
+
+fn resolve_log_dir() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(LOG_DIR_NAME))
+}
+
+pub(crate) fn plugin<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    let file_target = match resolve_log_dir() {
+        Ok(path) => TargetKind::Folder {
+            path,
+            file_name: Some(String::from(LOG_FILE_STEM)),
+        },
+        // 数据目录不可用时退回系统日志目录，保证仍有日志可查
+        Err(_) => TargetKind::LogDir {
+            file_name: Some(String::from(LOG_FILE_STEM)),
+        },
+    };
+    let mut builder = tauri_plugin_log::Builder::new()
+        .clear_targets()
+        .target(Target::new(file_target))
+        .level(LevelFilter::Info)
+        .max_file_size(MAX_LOG_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(KEEP_LOG_FILES))
+        .timezone_strategy(TimezoneStrategy::UseLocal);
+    if cfg!(debug_assertions) {
+        builder = builder.target(Target::new(TargetKind::Stderr));
+    }
+    builder.build()
+}
+
+/// 当前日志文件路径，用户反馈问题时附上该文件
+#[tauri::command]
+pub(crate) fn get_log_path() -> Result<String, AppError> {
+    Ok(resolve_log_dir()?
+        .join(format!("{}.log", LOG_FILE_STEM))
+        .to_string_lossy()
+        .to_string())
+}
@@ -0,0 +1,58 @@
+// 大文件的内存映射读取：未压缩、未加密且超过阈值的文档直接映射文件，在映射上校验摘要与 UTF-8，
+// 只在最后复制一次成字符串，省去先读入堆缓冲区的那一份拷贝；反复切换大文档时由页缓存直接提供内容
+// 文件过小、映射失败（如部分网络文件系统）或映射前后长度不一致（文件正被截断或改写）时返回 None，
+// 调用方退回普通读取
+
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+
+use crate::error::AppError;
+use crate::progress::ProgressFn;
+use crate::{checksum, crypto, is_compressed_graph_path};
+
+const MMAP_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+fn map_file(file: &File) -> Option<Mmap> {
+    let length = file.metadata().ok()?.len();
+    if length < MMAP_THRESHOLD {
+        return None;
+    }
+    // SAFETY: 映射只读；文件在映射期间被其他进程截断时访问会出错，
+    // 因此映射后立即复核长度，读完后再复核一次，不一致即放弃映射结果
+    let mapping = unsafe { Mmap::map(file) }.ok()?;
+    let current = file.metadata().ok()?.len();
+    (mapping.len() as u64 == length && current == length).then_some(mapping)
+}
+
+/// 返回 Ok(None) 表示不适用映射读取，由调用方走普通读取路径
+pub(crate) fn read_plain_document(
+    name: &str,
+    file_path: &Path,
+    progress: Option<ProgressFn>,
+) -> Result<Option<String>, AppError> {
+    if is_compressed_graph_path(file_path) {
+        return Ok(None);
+    }
+    let Ok(file) = File::open(file_path) else {
+        return Ok(None);
+    };
+    let Some(mapping) = map_file(&file) else {
+        return Ok(None);
+    };
+    if crypto::is_encrypted(&mapping) {
+        return Ok(None);
+    }
+    checksum::ensure_checksum(name, &mapping)?;
+    let text = std::str::from_utf8(&mapping)
+        .map_err(|e| AppError::Serialization(format!("文件不是有效的 UTF-8: {}", e)))?;
+    let contents = String::from(text);
+    if file.metadata().map(|metadata| metadata.len()).ok() != Some(mapping.len() as u64) {
+        log::warn!("读取期间文件长度发生变化，改用普通读取: {}", file_path.display());
+        return Ok(None);
+    }
+    if let Some(progress) = progress {
+        progress(mapping.len() as u64, mapping.len() as u64);
+    }
+    Ok(Some(contents))
+}
@@ -0,0 +1,111 @@
+// 原生菜单栏：文件 / 编辑 / 视图，菜单项只负责发 menu-action 事件（载荷为菜单项 id），具体逻辑由前端处理
+// 撤销/重做的可用状态由前端在历史变化后通过 set_undo_redo_enabled 同步
+
+use tauri::menu::{MenuBuilder, MenuItem, MenuItemBuilder, SubmenuBuilder};
+use tauri::{App, Emitter, Manager, State, Wry};
+
+use crate::error::AppError;
+use crate::tray;
+
+pub(crate) const MENU_ACTION_EVENT: &str = "menu-action";
+
+const MENU_UNDO_ID: &str = "edit.undo";
+const MENU_REDO_ID: &str = "edit.redo";
+
+// 重做的习惯快捷键：macOS 为 Cmd+Shift+Z，Windows / Linux 为 Ctrl+Y
+#[cfg(target_os = "macos")]
+const REDO_ACCELERATOR: &str = "CmdOrCtrl+Shift+Z";
+#[cfg(not(target_os = "macos"))]
+const REDO_ACCELERATOR: &str = "CmdOrCtrl+Y";
+
+pub(crate) struct MenuState {
+    undo: MenuItem<Wry>,
+    redo: MenuItem<Wry>,
+}
+
+fn menu_item(app: &App, id: &str, text: &str, accelerator: Option<&str>) -> tauri::Result<MenuItem<Wry>> {
+    let builder = MenuItemBuilder::with_id(id, text);
+    match accelerator {
+        Some(accelerator) => builder.accelerator(accelerator).build(app),
+        None => builder.build(app),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn build_app_menu(app: &App) -> tauri::Result<tauri::menu::Submenu<Wry>> {
+    SubmenuBuilder::new(app, app.package_info().name.clone())
+        .about(None)
+        .separator()
+        .services()
+        .separator()
+        .hide()
+        .hide_others()
+        .show_all()
+        .separator()
+        .quit()
+        .build()
+}
+
+/// 构建并设置应用菜单；撤销/重做初始为禁用，等前端报告历史状态后再启用
+pub(crate) fn install(app: &mut App) -> tauri::Result<()> {
+    let undo = MenuItemBuilder::with_id(MENU_UNDO_ID, "撤销")
+        .accelerator("CmdOrCtrl+Z")
+        .enabled(false)
+        .build(app)?;
+    let redo = MenuItemBuilder::with_id(MENU_REDO_ID, "重做")
+        .accelerator(REDO_ACCELERATOR)
+        .enabled(false)
+        .build(app)?;
+
+    let file_menu = SubmenuBuilder::new(app, "文件")
+        .item(&menu_item(app, "file.new", "新建", Some("CmdOrCtrl+N"))?)
+        .item(&menu_item(app, "file.open", "打开…", Some("CmdOrCtrl+O"))?)
+        .separator()
+        .item(&menu_item(app, "file.save", "保存", Some("CmdOrCtrl+S"))?)
+        .item(&menu_item(app, "file.saveAs", "另存为…", Some("CmdOrCtrl+Shift+S"))?)
+        .separator()
+        .item(&menu_item(app, "file.exportCsv", "导出 CSV…", None)?)
+        .item(&menu_item(app, "file.exportXlsx", "导出 Excel…", None)?)
+        .build()?;
+    // 剪切/复制/粘贴用系统预置项，保证 macOS 上输入框的剪贴板快捷键可用
+    let edit_menu = SubmenuBuilder::new(app, "编辑")
+        .item(&undo)
+        .item(&redo)
+        .separator()
+        .cut_with_text("剪切")
+        .copy_with_text("复制")
+        .paste_with_text("粘贴")
+        .select_all_with_text("全选")
+        .build()?;
+    let view_menu = SubmenuBuilder::new(app, "视图")
+        .item(&menu_item(app, "view.zoomIn", "放大", Some("CmdOrCtrl+="))?)
+        .item(&menu_item(app, "view.zoomOut", "缩小", Some("CmdOrCtrl+-"))?)
+        .item(&menu_item(app, "view.zoomReset", "实际大小", Some("CmdOrCtrl+0"))?)
+        .build()?;
+
+    let menu = MenuBuilder::new(app);
+    // macOS 的第一个菜单固定为应用菜单，承载“关于”与“退出”
+    #[cfg(target_os = "macos")]
+    let menu = menu.item(&build_app_menu(app)?);
+    let menu = menu.items(&[&file_menu, &edit_menu, &view_menu]).build()?;
+    app.set_menu(menu)?;
+
+    // 托盘菜单的事件同样会到达这里，由 tray 模块自行处理
+    app.on_menu_event(|app, event| {
+        if event.id().as_ref().starts_with(tray::TRAY_MENU_ID_PREFIX) {
+            return;
+        }
+        let _ = app.emit(MENU_ACTION_EVENT, event.id().as_ref());
+    });
+    app.manage(MenuState { undo, redo });
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn set_undo_redo_enabled(state: State<MenuState>, can_undo: bool, can_redo: bool) -> Result<(), AppError> {
+    state
+        .undo
+        .set_enabled(can_undo)
+        .and_then(|_| state.redo.set_enabled(can_redo))
+        .map_err(|e| AppError::Io(format!("更新菜单状态失败: {}", e)))
+}
@@ -0,0 +1,225 @@
+// 图数据合并：按 id 合并节点，按 (source, target, directed) 合并连线，strategy 决定同一元素属性不一致时取哪一边
+// 输出顺序固定为“base 的元素在前，incoming 新增的元素按其文档顺序追加”，重复合并结果可复现
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+use crate::error::AppError;
+use crate::{migrations, run_blocking};
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum MergeStrategy {
+    PreferBase,
+    PreferIncoming,
+    Fail,
+}
+
+// 只影响画布摆放的字段不算冲突：两个分支各自拖动过节点很常见
+const LAYOUT_ONLY_KEYS: [&str; 1] = ["position"];
+
+fn parse_document(data: &str, label: &str) -> Result<Value, AppError> {
+    let document: Value =
+        serde_json::from_str(data).map_err(|e| AppError::Serialization(format!("解析{}失败: {}", label, e)))?;
+    if !document.get("nodes").map(Value::is_array).unwrap_or(false) {
+        return Err(AppError::Serialization(format!("{}缺少 nodes 数组", label)));
+    }
+    // 先迁移到当前结构，避免新旧结构的同一元素被误判为冲突
+    Ok(migrations::migrate(document))
+}
+
+fn items(document: &Value, key: &str) -> Vec<Value> {
+    document.get(key).and_then(Value::as_array).cloned().unwrap_or_default()
+}
+
+fn item_id(item: &Value) -> Option<&str> {
+    item.get("id").and_then(Value::as_str)
+}
+
+fn without_layout(item: &Value) -> Value {
+    match item {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .filter(|(key, _)| !LAYOUT_ONLY_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// 无向连线 a-b 与 b-a 视为同一条
+fn edge_key(edge: &Value) -> Option<(String, String, bool)> {
+    let source = edge.get("source")?.as_str()?;
+    let target = edge.get("target")?.as_str()?;
+    let directed = edge
+        .get("directed")
+        .or_else(|| edge.get("data").and_then(|data| data.get("directed")))
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    if !directed && source > target {
+        return Some((String::from(target), String::from(source), false));
+    }
+    Some((String::from(source), String::from(target), directed))
+}
+
+/// 连线比较忽略 id：两边各自创建的同一条关系 id 往往不同
+fn edge_attributes(edge: &Value) -> Value {
+    match edge {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .filter(|(key, _)| key.as_str() != "id")
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+struct MergeOutcome {
+    nodes: Vec<Value>,
+    edges: Vec<Value>,
+    conflicts: Vec<String>,
+}
+
+fn merge_nodes(base: Vec<Value>, incoming: Vec<Value>, strategy: MergeStrategy, outcome: &mut MergeOutcome) {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for node in base {
+        if let Some(id) = item_id(&node) {
+            if positions.contains_key(id) {
+                continue;
+            }
+            positions.insert(String::from(id), outcome.nodes.len());
+        }
+        outcome.nodes.push(node);
+    }
+    for node in incoming {
+        let Some(id) = item_id(&node).map(String::from) else {
+            outcome.nodes.push(node);
+            continue;
+        };
+        match positions.get(&id) {
+            None => {
+                positions.insert(id, outcome.nodes.len());
+                outcome.nodes.push(node);
+            }
+            Some(&index) => {
+                if without_layout(&outcome.nodes[index]) == without_layout(&node) {
+                    continue;
+                }
+                match strategy {
+                    MergeStrategy::PreferBase => {}
+                    MergeStrategy::PreferIncoming => outcome.nodes[index] = node,
+                    MergeStrategy::Fail => outcome.conflicts.push(id),
+                }
+            }
+        }
+    }
+}
+
+/// incoming 新增连线的 id 与 base 中另一条连线重复时改名为 `<id>_merged_<序号>`，保证结果中 id 唯一
+fn merge_edges(base: Vec<Value>, incoming: Vec<Value>, strategy: MergeStrategy, outcome: &mut MergeOutcome) {
+    let mut by_key: HashMap<(String, String, bool), usize> = HashMap::new();
+    let mut used_ids: HashSet<String> = HashSet::new();
+    for edge in base {
+        if let Some(key) = edge_key(&edge) {
+            if by_key.contains_key(&key) {
+                continue;
+            }
+            by_key.insert(key, outcome.edges.len());
+        }
+        if let Some(id) = item_id(&edge) {
+            used_ids.insert(String::from(id));
+        }
+        outcome.edges.push(edge);
+    }
+    let mut renamed = 0usize;
+    for mut edge in incoming {
+        let Some(key) = edge_key(&edge) else {
+            continue;
+        };
+        if let Some(&index) = by_key.get(&key) {
+            if edge_attributes(&outcome.edges[index]) == edge_attributes(&edge) {
+                continue;
+            }
+            match strategy {
+                MergeStrategy::PreferBase => {}
+                MergeStrategy::PreferIncoming => {
+                    // 保留 base 的 id，引用该连线的其他数据不会失效
+                    if let (Some(id), Some(object)) = (outcome.edges[index].get("id").cloned(), edge.as_object_mut()) {
+                        object.insert(String::from("id"), id);
+                    }
+                    outcome.edges[index] = edge;
+                }
+                MergeStrategy::Fail => {
+                    let id = item_id(&outcome.edges[index]).map(String::from);
+                    outcome
+                        .conflicts
+                        .push(id.unwrap_or_else(|| format!("{} -> {}", key.0, key.1)));
+                }
+            }
+            continue;
+        }
+        if let Some(id) = item_id(&edge).map(String::from) {
+            if used_ids.contains(&id) {
+                let new_id = loop {
+                    renamed += 1;
+                    let candidate = format!("{}_merged_{}", id, renamed);
+                    if !used_ids.contains(&candidate) {
+                        break candidate;
+                    }
+                };
+                if let Some(object) = edge.as_object_mut() {
+                    object.insert(String::from("id"), Value::String(new_id.clone()));
+                }
+                used_ids.insert(new_id);
+            } else {
+                used_ids.insert(id);
+            }
+        }
+        by_key.insert(key, outcome.edges.len());
+        outcome.edges.push(edge);
+    }
+}
+
+/// 其余顶层字段（如 table）以 base 为准，base 中没有的字段取自 incoming
+pub(crate) fn merge_documents(base: Value, incoming: Value, strategy: MergeStrategy) -> Result<Value, AppError> {
+    let mut outcome = MergeOutcome {
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    merge_nodes(items(&base, "nodes"), items(&incoming, "nodes"), strategy, &mut outcome);
+    merge_edges(items(&base, "edges"), items(&incoming, "edges"), strategy, &mut outcome);
+    if !outcome.conflicts.is_empty() {
+        return Err(AppError::MergeConflict {
+            message: format!("合并冲突：{} 个节点或连线在两份文档中不一致", outcome.conflicts.len()),
+            ids: outcome.conflicts,
+        });
+    }
+
+    let mut merged: Map<String, Value> = base.as_object().cloned().unwrap_or_default();
+    if let Some(incoming) = incoming.as_object() {
+        for (key, value) in incoming {
+            merged.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    merged.insert(String::from("nodes"), Value::Array(outcome.nodes));
+    merged.insert(String::from("edges"), Value::Array(outcome.edges));
+    Ok(Value::Object(merged))
+}
+
+/// strategy 为 fail 时，冲突的节点/连线 id 放在错误的 conflicts 字段中
+#[tauri::command]
+pub(crate) async fn merge_graphs(base: String, incoming: String, strategy: MergeStrategy) -> Result<String, AppError> {
+    run_blocking(move || {
+        let base = parse_document(&base, "基准文档")?;
+        let incoming = parse_document(&incoming, "待合并文档")?;
+        let merged = merge_documents(base, incoming, strategy)?;
+        serde_json::to_string(&merged).map_err(|e| AppError::Serialization(format!("序列化合并结果失败: {}", e)))
+    })
+    .await
+}
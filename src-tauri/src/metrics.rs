@@ -0,0 +1,243 @@
+// 图结构指标：节点数、连线数、密度、连通分量数与度分布，一次遍历连线累加各节点的度；以及度中心性与介数中心性
+// 计数规则：重复连线逐条计入度；自环为节点的度贡献 2，为出度、入度各贡献 1；
+// 密度只按不同的节点对计算，忽略自环与重复连线，因此不超过 1
+// 存在有向连线时视为有向图：密度按有序节点对计算，并返回出度/入度统计，无向连线对两个端点同时计入出度与入度
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::error::AppError;
+use crate::graph::{parse_graph, GraphModel};
+use crate::run_blocking;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DegreeStats {
+    min: usize,
+    max: usize,
+    mean: f64,
+    // 度 → 具有该度的节点数
+    distribution: BTreeMap<usize, usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GraphMetrics {
+    node_count: usize,
+    edge_count: usize,
+    self_loop_count: usize,
+    directed: bool,
+    density: f64,
+    component_count: usize,
+    degree: DegreeStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_degree: Option<DegreeStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    out_degree: Option<DegreeStats>,
+}
+
+fn degree_stats(degrees: &[usize]) -> DegreeStats {
+    let mut distribution = BTreeMap::new();
+    for &degree in degrees {
+        *distribution.entry(degree).or_insert(0) += 1;
+    }
+    let total: usize = degrees.iter().sum();
+    DegreeStats {
+        min: degrees.iter().copied().min().unwrap_or(0),
+        max: degrees.iter().copied().max().unwrap_or(0),
+        mean: if degrees.is_empty() { 0.0 } else { total as f64 / degrees.len() as f64 },
+        distribution,
+    }
+}
+
+pub(crate) fn compute_metrics(graph: &GraphModel) -> GraphMetrics {
+    let node_count = graph.node_count();
+    let directed = graph.edges.iter().any(|edge| edge.directed);
+    let mut degree = vec![0usize; node_count];
+    let mut in_degree = vec![0usize; node_count];
+    let mut out_degree = vec![0usize; node_count];
+    let mut self_loop_count = 0;
+    // 有向图记录有序节点对，无向图记录 (小, 大) 的无序节点对
+    let mut pairs: HashSet<(usize, usize)> = HashSet::with_capacity(graph.edges.len());
+
+    for edge in &graph.edges {
+        let (source, target) = (edge.source, edge.target);
+        degree[source] += 1;
+        degree[target] += 1;
+        out_degree[source] += 1;
+        in_degree[target] += 1;
+        if !edge.directed && source != target {
+            out_degree[target] += 1;
+            in_degree[source] += 1;
+        }
+        if source == target {
+            self_loop_count += 1;
+            continue;
+        }
+        if !directed {
+            pairs.insert((source.min(target), source.max(target)));
+        } else {
+            pairs.insert((source, target));
+            if !edge.directed {
+                pairs.insert((target, source));
+            }
+        }
+    }
+
+    let possible_pairs = if node_count < 2 {
+        0.0
+    } else if directed {
+        (node_count * (node_count - 1)) as f64
+    } else {
+        (node_count * (node_count - 1) / 2) as f64
+    };
+    GraphMetrics {
+        node_count,
+        edge_count: graph.edges.len(),
+        self_loop_count,
+        directed,
+        density: if possible_pairs > 0.0 { pairs.len() as f64 / possible_pairs } else { 0.0 },
+        component_count: graph.undirected_components().len(),
+        degree: degree_stats(&degree),
+        in_degree: directed.then(|| degree_stats(&in_degree)),
+        out_degree: directed.then(|| degree_stats(&out_degree)),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn graph_metrics(data: String) -> Result<GraphMetrics, AppError> {
+    run_blocking(move || Ok(compute_metrics(&parse_graph(&data)?))).await
+}
+
+// 节点数超过该值时介数中心性改为从均匀抽取的源点近似计算，避免 O(V·E) 的精确计算让界面长时间无响应
+const DEFAULT_MAX_EXACT_BETWEENNESS_NODES: usize = 2000;
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CentralityKind {
+    Degree,
+    Betweenness,
+}
+
+/// 去重后的邻居列表（不含自身）：有向连线只登记 source → target，无向连线两个方向都登记
+fn simple_neighbors(graph: &GraphModel, respect_direction: bool) -> Vec<Vec<usize>> {
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); graph.node_count()];
+    let mut seen: HashSet<(usize, usize)> = HashSet::with_capacity(graph.edges.len() * 2);
+    for edge in &graph.edges {
+        if edge.source == edge.target {
+            continue;
+        }
+        let mut link = |from: usize, to: usize| {
+            if seen.insert((from, to)) {
+                neighbors[from].push(to);
+            }
+        };
+        link(edge.source, edge.target);
+        if !respect_direction || !edge.directed {
+            link(edge.target, edge.source);
+        }
+    }
+    neighbors
+}
+
+/// 度中心性：忽略方向后的不同邻居数除以 n - 1，重复连线与自环不计
+fn degree_centrality(graph: &GraphModel) -> Vec<f64> {
+    let node_count = graph.node_count();
+    let neighbors = simple_neighbors(graph, false);
+    if node_count < 2 {
+        return vec![0.0; node_count];
+    }
+    neighbors
+        .iter()
+        .map(|list| list.len() as f64 / (node_count - 1) as f64)
+        .collect()
+}
+
+/// Brandes 算法（不计权重，按跳数计最短路径）：从每个源点 BFS 后逆序累加依赖值；
+/// sources 少于节点数时按比例放大作为近似值
+fn betweenness_centrality(graph: &GraphModel, max_exact_nodes: usize) -> Vec<f64> {
+    let node_count = graph.node_count();
+    let neighbors = simple_neighbors(graph, true);
+    let sources: Vec<usize> = if node_count > max_exact_nodes {
+        let sample_count = max_exact_nodes.max(1);
+        (0..sample_count).map(|index| index * node_count / sample_count).collect()
+    } else {
+        (0..node_count).collect()
+    };
+
+    let mut scores = vec![0.0f64; node_count];
+    let mut sigma = vec![0.0f64; node_count];
+    let mut distance = vec![usize::MAX; node_count];
+    let mut delta = vec![0.0f64; node_count];
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    let mut order = Vec::with_capacity(node_count);
+    let mut queue = VecDeque::new();
+    for &source in &sources {
+        for node in 0..node_count {
+            sigma[node] = 0.0;
+            distance[node] = usize::MAX;
+            delta[node] = 0.0;
+            predecessors[node].clear();
+        }
+        order.clear();
+        sigma[source] = 1.0;
+        distance[source] = 0;
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in &neighbors[node] {
+                if distance[next] == usize::MAX {
+                    distance[next] = distance[node] + 1;
+                    queue.push_back(next);
+                }
+                if distance[next] == distance[node] + 1 {
+                    sigma[next] += sigma[node];
+                    predecessors[next].push(node);
+                }
+            }
+        }
+        for &node in order.iter().rev() {
+            for &previous in &predecessors[node] {
+                delta[previous] += sigma[previous] / sigma[node] * (1.0 + delta[node]);
+            }
+            if node != source {
+                scores[node] += delta[node];
+            }
+        }
+    }
+
+    if node_count < 3 {
+        return vec![0.0; node_count];
+    }
+    // 归一化到 0–1：有向图除以 (n-1)(n-2)；无向图每对节点从两端各计一次，除以同一分母即等于按无序节点对归一化
+    let scale = node_count as f64 / sources.len().max(1) as f64;
+    let pairs = ((node_count - 1) * (node_count - 2)) as f64;
+    scores.iter().map(|score| (score * scale / pairs).min(1.0)).collect()
+}
+
+pub(crate) fn compute_centrality(
+    graph: &GraphModel,
+    kind: CentralityKind,
+    max_exact_nodes: usize,
+) -> Vec<(String, f64)> {
+    let scores = match kind {
+        CentralityKind::Degree => degree_centrality(graph),
+        CentralityKind::Betweenness => betweenness_centrality(graph, max_exact_nodes),
+    };
+    let mut ranked: Vec<(String, f64)> = graph.node_ids.iter().cloned().zip(scores).collect();
+    // 分数相同按节点在文档中的顺序，结果稳定
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked
+}
+
+/// 返回 (节点 ID, 分数) 列表，分数归一化到 0–1 并从高到低排列；
+/// max_exact_nodes 缺省为 2000，节点数超过它时介数中心性改为抽样近似
+#[tauri::command]
+pub(crate) async fn centrality(
+    data: String,
+    kind: CentralityKind,
+    max_exact_nodes: Option<usize>,
+) -> Result<Vec<(String, f64)>, AppError> {
+    let max_exact_nodes = max_exact_nodes.unwrap_or(DEFAULT_MAX_EXACT_BETWEENNESS_NODES);
+    run_blocking(move || Ok(compute_centrality(&parse_graph(&data)?, kind, max_exact_nodes))).await
+}
@@ -0,0 +1,177 @@
+// 文档结构迁移：顶层 schemaVersion 记录结构版本，缺失视为 0
+// 加载时按顺序执行迁移步骤升级到当前版本；新增结构变更时在 MIGRATIONS 末尾追加一步并递增版本号
+
+use serde_json::{json, Map, Value};
+
+use crate::now_ms;
+
+pub(crate) const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// MIGRATIONS[i] 把版本 i 升级到 i + 1
+const MIGRATIONS: &[fn(Value) -> Value] = &[migrate_v0_to_v1];
+
+pub(crate) const CURRENT_SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+// 与前端 normalizeEdgeColor 的历史取值映射保持一致
+const LEGACY_EDGE_COLORS: [(&str, &str); 4] = [("core", "p0"), ("important", "p3"), ("normal", "p5"), ("minor", "p6")];
+
+pub(crate) fn schema_version(document: &Value) -> u64 {
+    document.get(SCHEMA_VERSION_KEY).and_then(Value::as_u64).unwrap_or(0)
+}
+
+fn ensure_array<'a>(object: &'a mut Map<String, Value>, key: &str) -> &'a mut Vec<Value> {
+    if !object.get(key).map(Value::is_array).unwrap_or(false) {
+        object.insert(String::from(key), Value::Array(Vec::new()));
+    }
+    object.get_mut(key).and_then(Value::as_array_mut).expect("刚写入的数组字段")
+}
+
+fn migrate_node_data(data: &mut Map<String, Value>, fallback_time: u64) {
+    for key in ["label", "content"] {
+        if !data.get(key).map(Value::is_string).unwrap_or(false) {
+            data.insert(String::from(key), json!(""));
+        }
+    }
+    if !data.get("tags").map(Value::is_array).unwrap_or(false) {
+        data.insert(String::from("tags"), json!([]));
+    }
+    let created_at = data.get("createdAt").and_then(Value::as_u64);
+    let updated_at = data.get("updatedAt").and_then(Value::as_u64);
+    data.insert(
+        String::from("createdAt"),
+        json!(created_at.or(updated_at).unwrap_or(fallback_time)),
+    );
+    data.insert(
+        String::from("updatedAt"),
+        json!(updated_at.or(created_at).unwrap_or(fallback_time)),
+    );
+    if let Some(edge_color) = data.get("edgeColor").and_then(Value::as_str) {
+        if let Some((_, mapped)) = LEGACY_EDGE_COLORS.iter().find(|(legacy, _)| *legacy == edge_color) {
+            data.insert(String::from("edgeColor"), json!(mapped));
+        }
+    }
+}
+
+/// v0 → v1：补齐 nodes/edges 数组、节点 type 与 data 必填字段，映射旧版 edgeColor，为缺少 id 的连线补 id
+fn migrate_v0_to_v1(document: Value) -> Value {
+    let Value::Object(mut object) = document else {
+        return document;
+    };
+    let fallback_time = now_ms();
+    for node in ensure_array(&mut object, "nodes").iter_mut() {
+        let Some(node) = node.as_object_mut() else {
+            continue;
+        };
+        node.insert(String::from("type"), json!("knowledgeNode"));
+        if !node.get("data").map(Value::is_object).unwrap_or(false) {
+            node.insert(String::from("data"), json!({}));
+        }
+        if let Some(data) = node.get_mut("data").and_then(Value::as_object_mut) {
+            migrate_node_data(data, fallback_time);
+        }
+    }
+    for (index, edge) in ensure_array(&mut object, "edges").iter_mut().enumerate() {
+        let Some(edge) = edge.as_object_mut() else {
+            continue;
+        };
+        if !edge.get("id").map(Value::is_string).unwrap_or(false) {
+            edge.insert(String::from("id"), json!(format!("edge_migrated_{}", index + 1)));
+        }
+    }
+    Value::Object(object)
+}
+
+/// 依次执行迁移直到当前版本；比当前版本更新的文档（由新版应用写入）原样返回
+pub(crate) fn migrate(document: Value) -> Value {
+    let version = schema_version(&document);
+    if version >= CURRENT_SCHEMA_VERSION || !document.is_object() {
+        return document;
+    }
+    let mut migrated = MIGRATIONS[version as usize..]
+        .iter()
+        .fold(document, |document, step| step(document));
+    if let Some(object) = migrated.as_object_mut() {
+        object.insert(String::from(SCHEMA_VERSION_KEY), json!(CURRENT_SCHEMA_VERSION));
+    }
+    migrated
+}
+
+/// 对文档文本执行迁移；无需迁移或无法解析时返回 None，调用方沿用原文
+pub(crate) fn migrate_document_text(contents: &str) -> Option<String> {
+    let document: Value = serde_json::from_str(contents).ok()?;
+    if schema_version(&document) >= CURRENT_SCHEMA_VERSION || !document.is_object() {
+        return None;
+    }
+    serde_json::to_string(&migrate(document)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 早期版本写出的文档：没有 schemaVersion，节点缺少 type 与部分 data 字段，连线缺少 id，edgeColor 为旧取值
+    const V0_FIXTURE: &str = r#"{
+        "nodes": [
+            {"id": "a", "position": {"x": 1, "y": 2}, "data": {"label": "A", "createdAt": 100, "edgeColor": "core"}},
+            {"id": "b", "position": {"x": 3, "y": 4}}
+        ],
+        "edges": [
+            {"source": "a", "target": "b"},
+            {"id": "kept", "source": "b", "target": "a"}
+        ]
+    }"#;
+
+    #[test]
+    fn migrates_v0_fixture_to_current_shape() {
+        let migrated: Value = serde_json::from_str(&migrate_document_text(V0_FIXTURE).unwrap()).unwrap();
+        assert_eq!(migrated[SCHEMA_VERSION_KEY], json!(CURRENT_SCHEMA_VERSION));
+        assert_eq!(CURRENT_SCHEMA_VERSION, 1);
+
+        assert_eq!(
+            migrated["nodes"][0],
+            json!({
+                "id": "a",
+                "type": "knowledgeNode",
+                "position": {"x": 1, "y": 2},
+                "data": {
+                    "label": "A",
+                    "content": "",
+                    "tags": [],
+                    "createdAt": 100,
+                    "updatedAt": 100,
+                    "edgeColor": "p0",
+                },
+            })
+        );
+
+        let second = &migrated["nodes"][1];
+        assert_eq!(second["type"], "knowledgeNode");
+        assert_eq!(second["data"]["label"], "");
+        assert_eq!(second["data"]["content"], "");
+        assert_eq!(second["data"]["tags"], json!([]));
+        // 没有任何时间戳时两者都取迁移时的时间
+        assert!(second["data"]["createdAt"].is_u64());
+        assert_eq!(second["data"]["createdAt"], second["data"]["updatedAt"]);
+
+        assert_eq!(
+            migrated["edges"],
+            json!([
+                {"id": "edge_migrated_1", "source": "a", "target": "b"},
+                {"id": "kept", "source": "b", "target": "a"},
+            ])
+        );
+    }
+
+    #[test]
+    fn missing_arrays_are_added() {
+        let migrated: Value = serde_json::from_str(&migrate_document_text("{}").unwrap()).unwrap();
+        assert_eq!(migrated, json!({ "nodes": [], "edges": [], "schemaVersion": CURRENT_SCHEMA_VERSION }));
+    }
+
+    #[test]
+    fn current_and_newer_documents_are_left_alone() {
+        assert!(migrate_document_text(r#"{"schemaVersion": 1, "nodes": []}"#).is_none());
+        let newer = json!({ "schemaVersion": 99, "nodes": "future" });
+        assert_eq!(migrate(newer.clone()), newer);
+    }
+}
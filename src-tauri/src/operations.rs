@@ -0,0 +1,88 @@
+// 可取消的长耗时操作：命令携带 operation_id 注册取消标记，cancel_operation 置位后，
+// 操作在下一个检查点返回 CANCELLED。输出文件都经原子写入，取消时不会留下写了一半的文件
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use crate::error::AppError;
+
+#[derive(Clone, Default)]
+pub(crate) struct CancelToken(Option<Arc<AtomicBool>>);
+
+impl CancelToken {
+    /// 检查点：已请求取消时返回 Cancelled 错误
+    pub(crate) fn check(&self) -> Result<(), AppError> {
+        match &self.0 {
+            Some(flag) if flag.load(Ordering::Relaxed) => Err(AppError::Cancelled(String::from("操作已取消"))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct OperationRegistry {
+    flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+/// 操作结束（无论成功、失败还是取消）时从注册表移除取消标记
+pub(crate) struct OperationGuard<'a> {
+    registry: &'a OperationRegistry,
+    operation_id: Option<String>,
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        if let (Some(operation_id), Ok(mut flags)) = (self.operation_id.take(), self.registry.flags.lock()) {
+            flags.remove(&operation_id);
+        }
+    }
+}
+
+impl OperationRegistry {
+    /// 未提供 operation_id 的调用不可取消，返回的令牌永远不会触发
+    pub(crate) fn register(&self, operation_id: Option<String>) -> Result<(CancelToken, OperationGuard<'_>), AppError> {
+        let Some(operation_id) = operation_id.filter(|id| !id.trim().is_empty()) else {
+            return Ok((
+                CancelToken::default(),
+                OperationGuard {
+                    registry: self,
+                    operation_id: None,
+                },
+            ));
+        };
+        let mut flags = self
+            .flags
+            .lock()
+            .map_err(|_| AppError::Io(String::from("操作注册表不可用")))?;
+        if flags.contains_key(&operation_id) {
+            return Err(AppError::AlreadyExists(format!("操作 ID 已在使用中: {}", operation_id)));
+        }
+        let flag = Arc::new(AtomicBool::new(false));
+        flags.insert(operation_id.clone(), Arc::clone(&flag));
+        Ok((
+            CancelToken(Some(flag)),
+            OperationGuard {
+                registry: self,
+                operation_id: Some(operation_id),
+            },
+        ))
+    }
+}
+
+/// 返回是否找到了该操作；操作已结束时返回 false
+#[tauri::command]
+pub(crate) fn cancel_operation(state: State<OperationRegistry>, operation_id: String) -> Result<bool, AppError> {
+    let flags = state
+        .flags
+        .lock()
+        .map_err(|_| AppError::Io(String::from("操作注册表不可用")))?;
+    Ok(match flags.get(&operation_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    })
+}
@@ -0,0 +1,166 @@
+// 打印：print_document 打开 webview 的打印对话框；print_to_pdf 不经对话框把当前视图直接渲染为 PDF 文件，
+// 无需用户操作，可用于自动生成报告
+// Windows 使用 WebView2 的 ShowPrintUI / PrintToPdf，Linux 使用 WebKitGTK 的 PrintOperation（输出到文件），
+// macOS 使用 WKWebView 的 createPDF；渲染比例跟随当前页面缩放（webview_zoom 保存的 zoomLevel）
+
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::WebviewWindow;
+
+use crate::error::AppError;
+use crate::{resolve_external_path, run_blocking, webview_zoom};
+
+// 大文档排版可能较慢，超过该时间仍未完成视为失败
+const PDF_TIMEOUT: Duration = Duration::from_secs(120);
+
+type PdfResult = Result<(), String>;
+
+#[cfg(target_os = "windows")]
+fn show_print_dialog(window: &WebviewWindow) -> tauri::Result<()> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{ICoreWebView2_16, COREWEBVIEW2_PRINT_DIALOG_KIND_BROWSER};
+    use windows_core::Interface;
+
+    let fallback = window.clone();
+    window.with_webview(move |webview| unsafe {
+        let shown = webview
+            .controller()
+            .CoreWebView2()
+            .and_then(|core| core.cast::<ICoreWebView2_16>())
+            .and_then(|core| core.ShowPrintUI(COREWEBVIEW2_PRINT_DIALOG_KIND_BROWSER));
+        // 旧版 WebView2 运行时没有 ICoreWebView2_16，退回页面的 window.print()
+        if shown.is_err() {
+            let _ = fallback.print();
+        }
+    })
+}
+
+/// WebKitGTK 与 WKWebView 由 wry 调起各自的打印对话框
+#[cfg(not(target_os = "windows"))]
+fn show_print_dialog(window: &WebviewWindow) -> tauri::Result<()> {
+    window.print()
+}
+
+#[cfg(target_os = "windows")]
+fn render_pdf(window: &WebviewWindow, path: PathBuf, zoom: f64, done: mpsc::Sender<PdfResult>) -> tauri::Result<()> {
+    use webview2_com::Microsoft::Web::WebView2::Win32::{ICoreWebView2Environment6, ICoreWebView2_7};
+    use webview2_com::PrintToPdfCompletedHandler;
+    use windows_core::{Interface, HSTRING};
+
+    window.with_webview(move |webview| unsafe {
+        let completed = done.clone();
+        let started = (|| -> windows_core::Result<()> {
+            let core = webview.controller().CoreWebView2()?.cast::<ICoreWebView2_7>()?;
+            let settings = webview
+                .environment()
+                .cast::<ICoreWebView2Environment6>()?
+                .CreatePrintSettings()?;
+            // WebView2 接受的缩放范围为 0.1 ~ 2.0
+            settings.SetScaleFactor(zoom.clamp(0.1, 2.0))?;
+            settings.SetShouldPrintBackgrounds(true)?;
+            let handler = PrintToPdfCompletedHandler::create(Box::new(move |result, success| {
+                let _ = completed.send(match result {
+                    Ok(()) if success => Ok(()),
+                    Ok(()) => Err(String::from("WebView2 未能生成 PDF")),
+                    Err(error) => Err(error.to_string()),
+                });
+                Ok(())
+            }));
+            core.PrintToPdf(&HSTRING::from(path.as_path()), &settings, &handler)
+        })();
+        if let Err(error) = started {
+            let _ = done.send(Err(error.to_string()));
+        }
+    })
+}
+
+/// 选用 GTK 的“打印到文件”打印机并指定输出为 PDF，print() 不显示对话框
+#[cfg(target_os = "linux")]
+fn render_pdf(window: &WebviewWindow, path: PathBuf, zoom: f64, done: mpsc::Sender<PdfResult>) -> tauri::Result<()> {
+    window.with_webview(move |webview| {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use webkit2gtk::PrintOperationExt;
+
+        let uri = match gtk::glib::filename_to_uri(&path, None) {
+            Ok(uri) => uri,
+            Err(error) => {
+                let _ = done.send(Err(error.to_string()));
+                return;
+            }
+        };
+        let settings = gtk::PrintSettings::new();
+        settings.set_printer("Print to File");
+        settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+        settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(uri.as_str()));
+        settings.set_scale(zoom * 100.0);
+
+        let operation = webkit2gtk::PrintOperation::new(&webview.inner());
+        operation.set_print_settings(&settings);
+        // 打印异步进行，finished 之前保留操作对象；出错时 failed 先于 finished 触发，接收方只取第一条结果
+        let keep_alive = Rc::new(RefCell::new(Some(operation.clone())));
+        let failed = done.clone();
+        operation.connect_failed(move |_, error| {
+            let _ = failed.send(Err(error.to_string()));
+        });
+        operation.connect_finished(move |_| {
+            let _ = done.send(Ok(()));
+            keep_alive.borrow_mut().take();
+        });
+        operation.print();
+    })
+}
+
+/// createPDF 按页面当前的显示比例渲染，已包含页面缩放，无需另行设置
+#[cfg(target_os = "macos")]
+fn render_pdf(window: &WebviewWindow, path: PathBuf, _zoom: f64, done: mpsc::Sender<PdfResult>) -> tauri::Result<()> {
+    window.with_webview(move |webview| unsafe {
+        use block2::RcBlock;
+        use objc2_foundation::{NSData, NSError};
+        use objc2_web_kit::WKWebView;
+
+        let view = &*(webview.inner() as *const WKWebView);
+        let handler = RcBlock::new(move |data: *mut NSData, error: *mut NSError| {
+            let result = match data.as_ref() {
+                Some(data) => std::fs::write(&path, data.to_vec()).map_err(|e| e.to_string()),
+                None => Err(error
+                    .as_ref()
+                    .map(|error| error.localizedDescription().to_string())
+                    .unwrap_or_else(|| String::from("WebKit 未能生成 PDF"))),
+            };
+            let _ = done.send(result);
+        });
+        view.createPDFWithConfiguration_completionHandler(None, &handler);
+    })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn render_pdf(_window: &WebviewWindow, _path: PathBuf, _zoom: f64, done: mpsc::Sender<PdfResult>) -> tauri::Result<()> {
+    let _ = done.send(Err(String::from("当前平台不支持导出 PDF")));
+    Ok(())
+}
+
+/// 打印调用该命令的窗口
+#[tauri::command]
+pub(crate) fn print_document(webview_window: WebviewWindow) -> Result<(), AppError> {
+    show_print_dialog(&webview_window).map_err(|e| AppError::Io(format!("打开打印对话框失败: {}", e)))
+}
+
+/// 渲染调用该命令的窗口当前显示的内容；dest_path 须为绝对路径，已存在的文件被覆盖。返回写入的路径
+#[tauri::command]
+pub(crate) async fn print_to_pdf(webview_window: WebviewWindow, dest_path: String) -> Result<String, AppError> {
+    let path = resolve_external_path(&dest_path)?;
+    if !path.parent().is_some_and(|parent| parent.is_dir()) {
+        return Err(AppError::NotFound(format!("目标目录不存在: {}", path.to_string_lossy())));
+    }
+    let (done, receiver) = mpsc::channel();
+    render_pdf(&webview_window, path.clone(), webview_zoom::stored_zoom_level(), done)
+        .map_err(|e| AppError::Io(format!("导出 PDF 失败: {}", e)))?;
+    run_blocking(move || match receiver.recv_timeout(PDF_TIMEOUT) {
+        Ok(Ok(())) => Ok(path.to_string_lossy().to_string()),
+        Ok(Err(error)) => Err(AppError::Io(format!("导出 PDF 失败: {}", error))),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(AppError::Io(String::from("导出 PDF 超时"))),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(AppError::Io(String::from("导出 PDF 失败: 渲染未返回结果"))),
+    })
+    .await
+}
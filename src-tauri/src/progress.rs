@@ -0,0 +1,80 @@
+// 大文件读写进度：超过阈值的保存/加载按 1MB 分块读写，每块完成后发送 io-progress 事件
+// 小文件不发事件，避免事件刷屏；进度只是附加信息，不影响命令本身的返回值
+
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+pub(crate) const IO_PROGRESS_EVENT: &str = "io-progress";
+pub(crate) const PROGRESS_CHUNK_SIZE: usize = 1024 * 1024;
+const PROGRESS_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// 进度回调：(已处理字节数, 总字节数)
+pub(crate) type ProgressFn<'a> = &'a dyn Fn(u64, u64);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IoProgressPayload<'a> {
+    operation: &'a str,
+    name: &'a str,
+    processed: u64,
+    total: u64,
+}
+
+/// 生成向前端发送进度事件的回调；总量低于阈值时不发送
+pub(crate) fn emitter(app: AppHandle, operation: &'static str, name: String) -> impl Fn(u64, u64) {
+    move |processed, total| {
+        if total < PROGRESS_THRESHOLD {
+            return;
+        }
+        let _ = app.emit(
+            IO_PROGRESS_EVENT,
+            IoProgressPayload {
+                operation,
+                name: &name,
+                processed,
+                total,
+            },
+        );
+    }
+}
+
+/// 分块写入并报告进度；没有回调时一次写完
+pub(crate) fn write_all_with_progress(
+    writer: &mut impl Write,
+    contents: &[u8],
+    progress: Option<ProgressFn>,
+) -> std::io::Result<()> {
+    let Some(progress) = progress else {
+        return writer.write_all(contents);
+    };
+    let total = contents.len() as u64;
+    let mut written = 0u64;
+    for chunk in contents.chunks(PROGRESS_CHUNK_SIZE) {
+        writer.write_all(chunk)?;
+        written += chunk.len() as u64;
+        progress(written, total);
+    }
+    Ok(())
+}
+
+/// 分块读取整个文件并报告进度；没有回调时等同于 std::fs::read
+pub(crate) fn read_with_progress(path: &Path, progress: Option<ProgressFn>) -> std::io::Result<Vec<u8>> {
+    let Some(progress) = progress else {
+        return std::fs::read(path);
+    };
+    let mut file = std::fs::File::open(path)?;
+    let total = file.metadata()?.len();
+    let mut contents = Vec::with_capacity(total as usize);
+    let mut buffer = vec![0u8; PROGRESS_CHUNK_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        contents.extend_from_slice(&buffer[..read]);
+        progress(contents.len() as u64, total.max(contents.len() as u64));
+    }
+    Ok(contents)
+}
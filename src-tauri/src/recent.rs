@@ -0,0 +1,84 @@
+// 最近打开的文档：保存/加载成功后记录到默认数据目录的 recent.json，最新的排在最前
+// 文件已被删除的条目保留并标记 exists = false，由前端决定是否移除
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::error::AppError;
+use crate::{now_ms, resolve_default_data_dir, storage, write_file_atomic};
+
+const RECENT_FILE_NAME: &str = "recent.json";
+const MAX_RECENT_COUNT: usize = 15;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentRecord {
+    name: String,
+    opened_at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RecentEntry {
+    name: String,
+    opened_at: u64,
+    exists: bool,
+}
+
+fn resolve_recent_path() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(RECENT_FILE_NAME))
+}
+
+fn read_recent_records() -> Vec<RecentRecord> {
+    resolve_recent_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_recent_records(records: &[RecentRecord]) -> Result<(), AppError> {
+    let contents = serde_json::to_vec_pretty(records)
+        .map_err(|e| AppError::Serialization(format!("序列化最近文档失败: {}", e)))?;
+    write_file_atomic(&resolve_recent_path()?, &contents)
+}
+
+/// 把文档移到列表最前，同名旧记录去重，超出上限的最旧记录被丢弃
+pub(crate) fn touch(name: &str) -> Result<(), AppError> {
+    let mut records = read_recent_records();
+    records.retain(|record| record.name != name);
+    records.insert(
+        0,
+        RecentRecord {
+            name: String::from(name),
+            opened_at: now_ms(),
+        },
+    );
+    records.truncate(MAX_RECENT_COUNT);
+    write_recent_records(&records)
+}
+
+/// 最近文档名称，最新的在前；供托盘菜单等只需名称的场景使用
+pub(crate) fn recent_names() -> Vec<String> {
+    read_recent_records().into_iter().map(|record| record.name).collect()
+}
+
+#[tauri::command]
+pub(crate) fn get_recent() -> Result<Vec<RecentEntry>, AppError> {
+    read_recent_records()
+        .into_iter()
+        .map(|record| {
+            let exists = storage::resolve_document_file(&record.name)?.is_some();
+            Ok(RecentEntry {
+                name: record.name,
+                opened_at: record.opened_at,
+                exists,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub(crate) fn clear_recent() -> Result<(), AppError> {
+    write_recent_records(&[])
+}
@@ -0,0 +1,321 @@
+// 损坏文档修复：依次尝试 1) 与校验文件一致的内容（原文件或写入中断留下的 tmp 文件）、2) 最近一份可解析的备份、
+// 3) 把原文件截断到最后一个完整的 JSON 值并补齐括号；报告采用的策略以及是否可能丢失数据
+// 覆盖前总是先把原文件移到 `<name>.corrupt`，修复写入失败时再移回；所有策略都失败时不改动任何文件
+
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::storage::{self, StorageBackend};
+use crate::{
+    backups, checksum, crypto, decode_graph_bytes, is_compressed_graph_path, normalize_password,
+    resolve_app_data_dir, resolve_existing_graph_path, resolve_temp_path, run_blocking, sanitize_required_graph_name,
+    write_graph_document_bytes, write_graph_file_document, GraphWriteOptions,
+};
+
+// 截断恢复时从后往前最多尝试的截断位置数
+const MAX_TRUNCATION_ATTEMPTS: usize = 64;
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum RepairStrategy {
+    // 文档完好，未做任何修改
+    Intact,
+    // 内容可以解析，只是校验文件过期，已重写校验文件
+    Checksum,
+    // 写入中断留下的 tmp 文件与校验文件一致，已用它替换原文件
+    TempFile,
+    Backup,
+    Truncated,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RepairReport {
+    strategy: RepairStrategy,
+    // 为 true 时修复结果缺少部分内容，需要用户确认
+    data_lost: bool,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corrupt_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backup_file: Option<String>,
+    // 截断恢复时丢弃的（解码后）字节数
+    dropped_bytes: usize,
+    node_count: usize,
+    edge_count: usize,
+}
+
+enum Recovery {
+    // 已编码的文档字节，原样写回
+    Encoded { bytes: Vec<u8>, compressed: bool },
+    // 解码后的文档文本，按原文件的格式重新编码
+    Text(String),
+}
+
+fn count_items(document: &Value, key: &str) -> usize {
+    document.get(key).and_then(Value::as_array).map(Vec::len).unwrap_or(0)
+}
+
+fn parse_document(text: &str) -> Option<Value> {
+    serde_json::from_str::<Value>(text).ok().filter(Value::is_object)
+}
+
+/// 已编码字节能否解码并解析为文档对象
+fn decode_document(bytes: &[u8], compressed: bool, password: Option<&str>) -> Option<Value> {
+    let text = decode_graph_bytes(bytes.to_vec(), compressed, password).ok()?;
+    parse_document(&text)
+}
+
+/// 与校验文件一致的 tmp 文件：没有校验文件时无法确认内容完整，不采用
+fn find_valid_temp_file(name: &str, password: Option<&str>) -> Option<(PathBuf, Vec<u8>, Value)> {
+    if !checksum::has_checksum(name) {
+        return None;
+    }
+    let app_dir = resolve_app_data_dir().ok()?;
+    [
+        app_dir.join(format!("{}.json.gz", name)),
+        app_dir.join(format!("{}.json", name)),
+    ]
+    .iter()
+    .map(|path| resolve_temp_path(path))
+    .find_map(|temp_path| {
+        let bytes = std::fs::read(&temp_path).ok()?;
+        if !checksum::verify_checksum(name, &bytes).ok()? {
+            return None;
+        }
+        let document = decode_document(&bytes, is_compressed_graph_path(&temp_path.with_extension("")), password)?;
+        Some((temp_path, bytes, document))
+    })
+}
+
+/// 从新到旧找第一份可以解码并解析的备份
+fn find_valid_backup(name: &str, password: Option<&str>) -> Option<(String, Vec<u8>, bool, Value)> {
+    let backup_dir = backups::resolve_backup_dir().ok()?;
+    let entries = backups::list_backup_entries(name).ok()?;
+    entries.into_iter().rev().find_map(|entry| {
+        let bytes = std::fs::read(backup_dir.join(&entry.file_name)).ok()?;
+        let compressed = entry.extension == ".json.gz";
+        let document = decode_document(&bytes, compressed, password)?;
+        Some((entry.file_name, bytes, compressed, document))
+    })
+}
+
+/// 尽量解码截断的文件：gzip 读到出错为止，UTF-8 只保留有效前缀；加密文件截断后无法解密
+fn decode_partial(bytes: &[u8], compressed: bool, password: Option<&str>) -> Option<String> {
+    let bytes = if crypto::is_encrypted(bytes) {
+        crypto::decrypt(bytes, normalize_password(password)?).ok()?
+    } else {
+        bytes.to_vec()
+    };
+    let bytes = if compressed {
+        let mut decoder = GzDecoder::new(bytes.as_slice());
+        let mut contents = Vec::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            match decoder.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => contents.extend_from_slice(&buffer[..read]),
+            }
+        }
+        contents
+    } else {
+        bytes
+    };
+    Some(match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => {
+            let valid = error.utf8_error().valid_up_to();
+            let mut bytes = error.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).unwrap_or_default()
+        }
+    })
+}
+
+/// 可截断的位置：容器内每个 `,` 之前（前一个元素或成员已完整）以及每个内层容器闭合之后，
+/// 连同当时尚未闭合的容器，用于补齐括号；只保留最后若干个
+fn truncation_points(text: &str) -> VecDeque<(usize, Vec<u8>)> {
+    let mut points = VecDeque::new();
+    let mut stack: Vec<u8> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let record = |points: &mut VecDeque<(usize, Vec<u8>)>, position: usize, stack: &[u8]| {
+        if points.len() == MAX_TRUNCATION_ATTEMPTS {
+            points.pop_front();
+        }
+        points.push_back((position, stack.to_vec()));
+    };
+    for (position, byte) in text.bytes().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => stack.push(byte),
+            b'}' | b']' => {
+                stack.pop();
+                if !stack.is_empty() {
+                    record(&mut points, position + 1, &stack);
+                }
+            }
+            b',' if !stack.is_empty() => record(&mut points, position, &stack),
+            _ => {}
+        }
+    }
+    points
+}
+
+fn truncate_document(text: &str) -> Option<(String, Value, usize)> {
+    truncation_points(text).into_iter().rev().find_map(|(position, open)| {
+        let mut candidate = String::with_capacity(position + open.len());
+        candidate.push_str(&text[..position]);
+        candidate.extend(open.iter().rev().map(|&bracket| if bracket == b'{' { '}' } else { ']' }));
+        let document = parse_document(&candidate)?;
+        Some((candidate, document, text.len() - position))
+    })
+}
+
+/// 移到 `<name>.corrupt`；已存在时依次尝试 `<name>.corrupt.2`、`.3`…，不覆盖之前留下的副本
+fn move_aside(name: &str, file_path: &Path) -> Result<PathBuf, AppError> {
+    let app_dir = resolve_app_data_dir()?;
+    let mut target = app_dir.join(format!("{}.corrupt", name));
+    let mut counter = 2;
+    while target.exists() {
+        target = app_dir.join(format!("{}.corrupt.{}", name, counter));
+        counter += 1;
+    }
+    std::fs::rename(file_path, &target).map_err(|e| AppError::io("移出损坏文件失败", e))?;
+    Ok(target)
+}
+
+fn repair_document(name: &str, password: Option<&str>) -> Result<RepairReport, AppError> {
+    if storage::backend_for(name) != StorageBackend::Json {
+        return Err(AppError::InvalidInput(format!("只能修复 JSON 文件存储的文档: {}", name)));
+    }
+    let Some(file_path) = resolve_existing_graph_path(name)? else {
+        return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+    };
+    let bytes = std::fs::read(&file_path).map_err(|e| AppError::io("读取文件失败", e))?;
+    if crypto::is_encrypted(&bytes) && normalize_password(password).is_none() {
+        return Err(AppError::PasswordRequired(String::from("文档已加密，请提供密码")));
+    }
+    let compressed = is_compressed_graph_path(&file_path);
+
+    let report = |strategy, data_lost, message: String, document: &Value| RepairReport {
+        strategy,
+        data_lost,
+        message,
+        corrupt_path: None,
+        backup_file: None,
+        dropped_bytes: 0,
+        node_count: count_items(document, "nodes"),
+        edge_count: count_items(document, "edges"),
+    };
+
+    if let Some(document) = decode_document(&bytes, compressed, password) {
+        if checksum::verify_checksum(name, &bytes)? {
+            return Ok(report(RepairStrategy::Intact, false, String::from("文档完好，无需修复"), &document));
+        }
+        checksum::write_checksum(name, &bytes)?;
+        return Ok(report(
+            RepairStrategy::Checksum,
+            false,
+            String::from("文档内容可以正常解析，已更新过期的校验文件"),
+            &document,
+        ));
+    }
+
+    let (mut result, recovery, temp_path) = if let Some((temp_path, temp_bytes, document)) =
+        find_valid_temp_file(name, password)
+    {
+        let compressed = is_compressed_graph_path(&temp_path.with_extension(""));
+        (
+            report(
+                RepairStrategy::TempFile,
+                false,
+                String::from("已用与校验文件一致的未完成写入文件恢复，内容完整"),
+                &document,
+            ),
+            Recovery::Encoded {
+                bytes: temp_bytes,
+                compressed,
+            },
+            Some(temp_path),
+        )
+    } else if let Some((backup_file, backup_bytes, compressed, document)) = find_valid_backup(name, password) {
+        let mut result = report(
+            RepairStrategy::Backup,
+            true,
+            format!("已从备份 {} 恢复，该备份之后的修改已丢失", backup_file),
+            &document,
+        );
+        result.backup_file = Some(backup_file);
+        (
+            result,
+            Recovery::Encoded {
+                bytes: backup_bytes,
+                compressed,
+            },
+            None,
+        )
+    } else {
+        let truncated = decode_partial(&bytes, compressed, password).and_then(|text| truncate_document(&text));
+        let Some((text, document, dropped_bytes)) = truncated else {
+            return Err(AppError::Serialization(format!(
+                "无法修复文档：没有可用的校验内容、备份，原文件也无法部分解析: {}",
+                name
+            )));
+        };
+        let mut result = report(
+            RepairStrategy::Truncated,
+            dropped_bytes > 0,
+            format!("已截断到最后一个完整的 JSON 值，丢弃了末尾 {} 字节", dropped_bytes),
+            &document,
+        );
+        result.dropped_bytes = dropped_bytes;
+        (result, Recovery::Text(text), None)
+    };
+
+    let corrupt_path = move_aside(name, &file_path)?;
+    let written = match &recovery {
+        Recovery::Encoded { bytes, compressed } => write_graph_document_bytes(name, bytes, *compressed, None, None),
+        Recovery::Text(text) => {
+            let options = GraphWriteOptions {
+                compress: compressed,
+                password,
+                ..GraphWriteOptions::default()
+            };
+            write_graph_file_document(name, text.as_bytes(), options)
+        }
+    };
+    if let Err(error) = written {
+        let _ = std::fs::rename(&corrupt_path, &file_path);
+        return Err(error);
+    }
+    if let Some(temp_path) = temp_path {
+        let _ = std::fs::remove_file(temp_path);
+    }
+    result.corrupt_path = Some(corrupt_path.to_string_lossy().to_string());
+    Ok(result)
+}
+
+/// 加密文档需提供 password；修复期间持有文档锁，避免与保存交错
+#[tauri::command]
+pub(crate) async fn repair_graph(name: String, password: Option<String>) -> Result<RepairReport, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        storage::with_document_lock(&graph_name, || repair_document(&graph_name, password.as_deref()))
+    })
+    .await
+}
@@ -0,0 +1,192 @@
+// 内容寻址的修订记录：每次保存把文档内容 gzip 压缩后存为 revisions/<sha256>.json.gz，相同内容只存一份，
+// 并在 revisions/logs/<name>.jsonl 追加一行 { timestamp, hash, size }，任何保存过的版本都可取回
+// 与撤销历史一样，加密文档不记录修订，避免内容绕过加密落盘；删除文档时移除其日志并清理不再被引用的内容
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::error::AppError;
+use crate::{
+    checksum, compress_graph_bytes, decompress_graph_bytes, now_ms, resolve_app_data_dir, run_blocking,
+    sanitize_required_graph_name, write_file_atomic,
+};
+
+const REVISIONS_DIR_NAME: &str = "revisions";
+const REVISION_LOG_DIR_NAME: &str = "logs";
+const REVISION_EXTENSION: &str = ".json.gz";
+const REVISION_LOG_EXTENSION: &str = ".jsonl";
+
+// 日志追加、重命名与清理串行执行，清理时不会误删刚写入的内容
+static REVISIONS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RevisionEntry {
+    timestamp: u64,
+    hash: String,
+    // 未压缩的文档字节数
+    size: usize,
+}
+
+fn resolve_revisions_dir() -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(REVISIONS_DIR_NAME))
+}
+
+fn resolve_log_dir() -> Result<PathBuf, AppError> {
+    Ok(resolve_revisions_dir()?.join(REVISION_LOG_DIR_NAME))
+}
+
+fn resolve_log_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_log_dir()?.join(format!("{}{}", name, REVISION_LOG_EXTENSION)))
+}
+
+fn resolve_revision_path(hash: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_revisions_dir()?.join(format!("{}{}", hash, REVISION_EXTENSION)))
+}
+
+fn with_revisions_lock<T>(task: impl FnOnce() -> Result<T, AppError>) -> Result<T, AppError> {
+    let _guard = REVISIONS_LOCK
+        .lock()
+        .map_err(|_| AppError::Io(String::from("修订记录状态不可用")))?;
+    task()
+}
+
+fn is_revision_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte))
+}
+
+/// 按时间从旧到新；写入中断留下的残缺行跳过
+fn read_log(name: &str) -> Result<Vec<RevisionEntry>, AppError> {
+    match std::fs::read_to_string(resolve_log_path(name)?) {
+        Ok(contents) => Ok(contents.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(AppError::io("读取修订日志失败", e)),
+    }
+}
+
+fn append_log(name: &str, entry: &RevisionEntry) -> Result<(), AppError> {
+    let mut line =
+        serde_json::to_string(entry).map_err(|e| AppError::Serialization(format!("序列化修订记录失败: {}", e)))?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(resolve_log_path(name)?)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| AppError::io("写入修订日志失败", e))
+}
+
+pub(crate) fn latest_revision_hash(name: &str) -> Result<Option<String>, AppError> {
+    with_revisions_lock(|| Ok(read_log(name)?.pop().map(|entry| entry.hash)))
+}
+
+/// 保存成功后调用：内容已存在时只追加日志
+pub(crate) fn record_revision(name: &str, contents: &[u8]) -> Result<(), AppError> {
+    let hash = checksum::sha256_hex(contents);
+    with_revisions_lock(|| {
+        // 日志目录位于修订目录之下，一并创建
+        std::fs::create_dir_all(resolve_log_dir()?).map_err(|e| AppError::io("创建修订目录失败", e))?;
+        let revision_path = resolve_revision_path(&hash)?;
+        if !revision_path.is_file() {
+            write_file_atomic(&revision_path, &compress_graph_bytes(contents)?)?;
+        }
+        append_log(
+            name,
+            &RevisionEntry {
+                timestamp: now_ms(),
+                hash,
+                size: contents.len(),
+            },
+        )
+    })
+}
+
+/// 删除所有日志都不再引用的内容文件
+fn collect_garbage() -> Result<usize, AppError> {
+    let mut referenced = HashSet::new();
+    if let Ok(entries) = std::fs::read_dir(resolve_log_dir()?) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(name) = file_name.strip_suffix(REVISION_LOG_EXTENSION) else {
+                continue;
+            };
+            referenced.extend(read_log(name)?.into_iter().map(|entry| entry.hash));
+        }
+    }
+    let Ok(entries) = std::fs::read_dir(resolve_revisions_dir()?) else {
+        return Ok(0);
+    };
+    let mut removed = 0;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(hash) = file_name.strip_suffix(REVISION_EXTENSION).filter(|hash| is_revision_hash(hash)) else {
+            continue;
+        };
+        if !referenced.contains(hash) && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+pub(crate) fn delete_revisions(name: &str) -> Result<(), AppError> {
+    with_revisions_lock(|| {
+        match std::fs::remove_file(resolve_log_path(name)?) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(AppError::io("删除修订日志失败", e)),
+        }
+        collect_garbage().map(|_| ())
+    })
+}
+
+pub(crate) fn rename_revisions(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    with_revisions_lock(|| {
+        let old_path = resolve_log_path(old_name)?;
+        let new_path = resolve_log_path(new_name)?;
+        if old_path.is_file() && !new_path.exists() {
+            std::fs::rename(old_path, new_path).map_err(|e| AppError::io("重命名修订日志失败", e))?;
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn list_revisions(name: String) -> Result<Vec<RevisionEntry>, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        read_log(&graph_name)
+    })
+    .await
+}
+
+/// 只能取回该文档日志中出现过的修订；读出的内容与哈希不一致时报错
+#[tauri::command]
+pub(crate) async fn load_revision(name: String, hash: String) -> Result<String, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        let hash = hash.trim().to_ascii_lowercase();
+        if !is_revision_hash(&hash) {
+            return Err(AppError::InvalidInput(format!("修订哈希无效: {}", hash)));
+        }
+        if !read_log(&graph_name)?.iter().any(|entry| entry.hash == hash) {
+            return Err(AppError::NotFound(format!("修订不存在: {}", hash)));
+        }
+        let bytes = match std::fs::read(resolve_revision_path(&hash)?) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AppError::NotFound(format!("修订内容已丢失: {}", hash)))
+            }
+            Err(e) => return Err(AppError::io("读取修订失败", e)),
+        };
+        let contents = decompress_graph_bytes(&bytes)?;
+        if checksum::sha256_hex(&contents) != hash {
+            return Err(AppError::Serialization(format!("修订内容已损坏: {}", hash)));
+        }
+        String::from_utf8(contents).map_err(|e| AppError::Serialization(format!("修订内容不是有效的 UTF-8: {}", e)))
+    })
+    .await
+}
@@ -0,0 +1,225 @@
+// 文档内搜索：在已保存文档的节点属性与表格单元格中查找文本，默认不区分大小写的子串匹配，regex 为 true 时按正则匹配
+// 逐个字段匹配，不把整份文档拼成一个大字符串；结果按匹配质量排序并截断到 limit 条
+
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::Value;
+use std::cmp::Reverse;
+
+use crate::error::AppError;
+use crate::table::{cell_text, parse_table_value};
+use crate::{read_graph_document, run_blocking, sanitize_required_graph_name};
+
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 10_000;
+// 只影响画布摆放或界面状态的字段不参与搜索
+const SKIPPED_NODE_KEYS: [&str; 3] = ["position", "style", "measured"];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NodeMatch {
+    id: String,
+    // 命中的字段路径，如 data.label
+    field: String,
+    score: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CellMatch {
+    row: usize,
+    column: String,
+    score: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SearchResults {
+    nodes: Vec<NodeMatch>,
+    cells: Vec<CellMatch>,
+    // 命中数超过 limit 被截断时为 true
+    truncated: bool,
+}
+
+enum Matcher {
+    Substring(String),
+    Pattern(Regex),
+}
+
+impl Matcher {
+    fn new(query: &str, regex: bool) -> Result<Self, AppError> {
+        if regex {
+            return RegexBuilder::new(query)
+                .case_insensitive(true)
+                .build()
+                .map(Matcher::Pattern)
+                .map_err(|e| AppError::InvalidInput(format!("正则表达式无效: {}", e)));
+        }
+        Ok(Matcher::Substring(query.to_lowercase()))
+    }
+
+    /// 匹配质量：完全相同 3，开头匹配 2，其余位置 1，未命中 0
+    fn score(&self, text: &str) -> u32 {
+        match self {
+            Matcher::Substring(needle) => {
+                let haystack = text.to_lowercase();
+                match haystack.find(needle.as_str()) {
+                    None => 0,
+                    Some(_) if haystack.len() == needle.len() => 3,
+                    Some(0) => 2,
+                    Some(_) => 1,
+                }
+            }
+            Matcher::Pattern(pattern) => match pattern.find(text) {
+                None => 0,
+                Some(found) if found.start() == 0 && found.end() == text.len() => 3,
+                Some(found) if found.start() == 0 => 2,
+                Some(_) => 1,
+            },
+        }
+    }
+}
+
+/// 递归匹配属性值中的字符串与数字，返回最佳得分及其字段路径
+fn best_in_value(matcher: &Matcher, value: &Value, path: &mut String, best: &mut Option<(u32, String)>) {
+    let score = match value {
+        Value::String(text) => matcher.score(text),
+        Value::Number(number) => matcher.score(&number.to_string()),
+        Value::Array(items) => {
+            for item in items {
+                best_in_value(matcher, item, path, best);
+            }
+            return;
+        }
+        Value::Object(object) => {
+            for (key, item) in object {
+                let length = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+                best_in_value(matcher, item, path, best);
+                path.truncate(length);
+            }
+            return;
+        }
+        Value::Null | Value::Bool(_) => 0,
+    };
+    if score > best.as_ref().map_or(0, |(best_score, _)| *best_score) {
+        *best = Some((score, path.clone()));
+    }
+}
+
+fn is_label_field(field: &str) -> bool {
+    field == "label" || field == "data.label"
+}
+
+/// 标题上的命中排在其他属性之前：得分 = 匹配质量 × 2 + 是否为标题
+fn search_nodes(matcher: &Matcher, document: &Value) -> Vec<NodeMatch> {
+    let Some(nodes) = document.get("nodes").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let mut matches = Vec::new();
+    let mut path = String::new();
+    for node in nodes {
+        let Some(id) = node.get("id").and_then(Value::as_str) else {
+            continue;
+        };
+        let Some(object) = node.as_object() else {
+            continue;
+        };
+        let mut best = None;
+        for (key, value) in object {
+            if key == "id" || SKIPPED_NODE_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            path.clear();
+            path.push_str(key);
+            best_in_value(matcher, value, &mut path, &mut best);
+        }
+        if let Some((quality, field)) = best {
+            matches.push(NodeMatch {
+                id: String::from(id),
+                score: quality * 2 + u32::from(is_label_field(&field)),
+                field,
+            });
+        }
+    }
+    matches
+}
+
+/// 只搜索文档中显式的表格；没有 table 时表格视图由节点派生，命中已包含在节点结果中
+fn search_cells(matcher: &Matcher, document: &Value) -> Result<Vec<CellMatch>, AppError> {
+    if !document.get("table").is_some_and(Value::is_object) {
+        return Ok(Vec::new());
+    }
+    let table = parse_table_value(document)?;
+    let mut matches = Vec::new();
+    for (row_index, row) in table.rows.iter().enumerate() {
+        for column in &table.columns {
+            let Some(value) = row.get(&column.id) else {
+                continue;
+            };
+            let score = matcher.score(&cell_text(value, column.column_type));
+            if score > 0 {
+                matches.push(CellMatch {
+                    row: row_index,
+                    column: column.id.clone(),
+                    score: score * 2,
+                });
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// 同分时保持文档顺序（sort_by_key 是稳定排序）
+fn rank<T>(items: &mut Vec<T>, score: impl Fn(&T) -> u32, limit: usize) -> bool {
+    items.sort_by_key(|item| Reverse(score(item)));
+    let truncated = items.len() > limit;
+    items.truncate(limit);
+    truncated
+}
+
+pub(crate) fn search_document(
+    document: &Value,
+    query: &str,
+    regex: bool,
+    limit: usize,
+) -> Result<SearchResults, AppError> {
+    if query.is_empty() {
+        return Err(AppError::InvalidInput(String::from("搜索内容不能为空")));
+    }
+    let matcher = Matcher::new(query, regex)?;
+    let mut nodes = search_nodes(&matcher, document);
+    let mut cells = search_cells(&matcher, document)?;
+    let nodes_truncated = rank(&mut nodes, |item| item.score, limit);
+    let cells_truncated = rank(&mut cells, |item| item.score, limit);
+    Ok(SearchResults {
+        nodes,
+        cells,
+        truncated: nodes_truncated || cells_truncated,
+    })
+}
+
+/// limit 分别作用于节点与单元格结果，缺省 200，最多 10000
+#[tauri::command]
+pub(crate) async fn search_graph(
+    name: String,
+    query: String,
+    regex: Option<bool>,
+    limit: Option<usize>,
+    password: Option<String>,
+) -> Result<SearchResults, AppError> {
+    run_blocking(move || {
+        let graph_name = sanitize_required_graph_name(&name)?;
+        let Some(contents) = read_graph_document(&graph_name, password.as_deref())? else {
+            return Err(AppError::NotFound(format!("文档不存在: {}", graph_name)));
+        };
+        let document: Value = serde_json::from_str(&contents)
+            .map_err(|e| AppError::Serialization(format!("解析文档失败: {}", e)))?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        search_document(&document, &query, regex.unwrap_or(false), limit)
+    })
+    .await
+}
@@ -0,0 +1,177 @@
+// 应用设置：默认数据目录下的 settings.json，顶层为键值对象，各模块按各自的键读写，前端通过 get_setting / set_setting 存取
+// 首次访问时整份读入内存，之后读取只查内存；修改先更新内存，再由后台线程合并短时间内的连续修改，一次原子写盘
+// 读取失败或文件损坏时视为空设置，各设置项使用自己的默认值；退出前调用 flush 写出尚未落盘的修改
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::{Condvar, LazyLock, Mutex, MutexGuard, Once};
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::{resolve_default_data_dir, write_file_atomic};
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+// 连续修改（如拖动滑块）在这段时间内合并为一次写入
+const COALESCE_DELAY: Duration = Duration::from_millis(300);
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+struct SettingsStore {
+    values: Map<String, Value>,
+    dirty: bool,
+}
+
+static STORE: LazyLock<Mutex<SettingsStore>> = LazyLock::new(|| {
+    Mutex::new(SettingsStore {
+        values: read_settings_file(),
+        dirty: false,
+    })
+});
+static DIRTY: Condvar = Condvar::new();
+// 取快照到写完为止都持有，保证后取的快照后落盘，文件内容不会回退
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+static WRITER: Once = Once::new();
+
+fn resolve_settings_path() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(SETTINGS_FILE_NAME))
+}
+
+fn read_settings_file() -> Map<String, Value> {
+    resolve_settings_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// 自检用：重新读取磁盘上的设置文件，返回顶层键的数量；文件不存在时为 None，读取失败或不是 JSON 对象时返回错误
+pub(crate) fn check_settings_file() -> Result<Option<usize>, AppError> {
+    let contents = match std::fs::read_to_string(resolve_settings_path()?) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AppError::io("读取设置文件失败", e)),
+    };
+    let values: Map<String, Value> = serde_json::from_str(&contents)
+        .map_err(|e| AppError::Serialization(format!("设置文件不是有效的 JSON 对象: {}", e)))?;
+    Ok(Some(values.len()))
+}
+
+fn lock_store() -> Result<MutexGuard<'static, SettingsStore>, AppError> {
+    STORE.lock().map_err(|_| AppError::Io(String::from("设置状态不可用")))
+}
+
+/// 写出当前内存中的设置；没有未落盘的修改时什么也不做
+fn write_snapshot() -> Result<(), AppError> {
+    let _writes = WRITE_LOCK
+        .lock()
+        .map_err(|_| AppError::Io(String::from("设置状态不可用")))?;
+    let snapshot = {
+        let mut store = lock_store()?;
+        if !store.dirty {
+            return Ok(());
+        }
+        store.dirty = false;
+        store.values.clone()
+    };
+    let result = serde_json::to_vec_pretty(&snapshot)
+        .map_err(|e| AppError::Serialization(format!("序列化设置失败: {}", e)))
+        .and_then(|contents| write_file_atomic(&resolve_settings_path()?, &contents));
+    if result.is_err() {
+        // 写入失败时保留未落盘标记，稍后重试
+        if let Ok(mut store) = lock_store() {
+            store.dirty = true;
+        }
+    }
+    result
+}
+
+fn run_writer() {
+    loop {
+        {
+            let Ok(mut store) = lock_store() else {
+                return;
+            };
+            while !store.dirty {
+                store = match DIRTY.wait(store) {
+                    Ok(store) => store,
+                    Err(_) => return,
+                };
+            }
+        }
+        std::thread::sleep(COALESCE_DELAY);
+        if let Err(error) = write_snapshot() {
+            log::error!("写入设置失败: {}", error);
+            std::thread::sleep(RETRY_DELAY);
+        }
+    }
+}
+
+fn mark_dirty(store: &mut SettingsStore) {
+    store.dirty = true;
+    WRITER.call_once(|| {
+        if let Err(error) = std::thread::Builder::new().name(String::from("settings")).spawn(run_writer) {
+            log::error!("启动设置写入线程失败: {}", error);
+        }
+    });
+    DIRTY.notify_one();
+}
+
+/// 在 setup 中调用：启动时读入设置文件，后续读取不再访问磁盘
+pub(crate) fn load() {
+    LazyLock::force(&STORE);
+}
+
+/// 同步写出尚未落盘的修改；退出与重启前调用
+pub(crate) fn flush() -> Result<(), AppError> {
+    write_snapshot()
+}
+
+/// 键不存在或值的类型不符时返回 None
+pub(crate) fn read_setting<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let store = lock_store().ok()?;
+    store
+        .values
+        .get(key)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+pub(crate) fn write_setting(key: &str, value: impl Serialize) -> Result<(), AppError> {
+    let value =
+        serde_json::to_value(value).map_err(|e| AppError::Serialization(format!("序列化设置失败: {}", e)))?;
+    let mut store = lock_store()?;
+    store.values.insert(String::from(key), value);
+    mark_dirty(&mut store);
+    Ok(())
+}
+
+fn require_key(key: &str) -> Result<&str, AppError> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(AppError::InvalidInput(String::from("设置键不能为空")));
+    }
+    Ok(key)
+}
+
+/// 设置项不限定结构，未知的键原样保存
+#[tauri::command]
+pub(crate) fn get_setting(key: String) -> Result<Option<Value>, AppError> {
+    let key = require_key(&key)?;
+    Ok(lock_store()?.values.get(key).cloned())
+}
+
+/// value 为 null 时删除该键
+#[tauri::command]
+pub(crate) fn set_setting(key: String, value: Value) -> Result<(), AppError> {
+    let key = require_key(&key)?;
+    let mut store = lock_store()?;
+    if value.is_null() {
+        if store.values.remove(key).is_none() {
+            return Ok(());
+        }
+    } else {
+        store.values.insert(String::from(key), value);
+    }
+    mark_dirty(&mut store);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// 退出流程：收到 ExitRequested（托盘退出、关闭最后一个窗口、app.exit）时先阻止退出，
+// 在后台线程中停止文件监听、同步写出自动保存、释放本进程持有的文档锁并写出设置，完成后再真正退出
+// 整个过程最多等待 SHUTDOWN_TIMEOUT；写出失败或超时只记录日志，不会让退出卡住
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, ExitRequestApi, Manager};
+
+use crate::{autosave, file_lock, settings, watcher};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+// 自动保存写出后，等待其他进行中的保存释放文档锁的最长时间
+const LOCK_RELEASE_WAIT: Duration = Duration::from_secs(3);
+
+const IDLE: u8 = 0;
+const RUNNING: u8 = 1;
+const FINISHED: u8 = 2;
+
+static STAGE: AtomicU8 = AtomicU8::new(IDLE);
+
+fn flush_and_release(app: &AppHandle) {
+    if let Some(state) = app.try_state::<watcher::WatchState>() {
+        watcher::stop_all(&state);
+    }
+    if let Err(error) = autosave::flush_pending() {
+        log::error!("退出前写出自动保存失败: {}", error);
+    }
+    let forced = file_lock::release_all(LOCK_RELEASE_WAIT);
+    if !forced.is_empty() {
+        log::warn!("退出时强制释放文档锁: {}", forced.join(", "));
+    }
+    if let Err(error) = settings::flush() {
+        log::error!("退出前写出设置失败: {}", error);
+    }
+}
+
+/// 在 RunEvent::ExitRequested 中调用；清理完成后以原退出码重新请求退出，第二次请求直接放行
+pub(crate) fn handle_exit_requested(app: &AppHandle, api: &ExitRequestApi, code: Option<i32>) {
+    match STAGE.compare_exchange(IDLE, RUNNING, Ordering::SeqCst, Ordering::SeqCst) {
+        Ok(_) => {}
+        Err(FINISHED) => return,
+        // 清理进行中再次请求退出（如重复点击托盘退出），等待本轮清理结束
+        Err(_) => {
+            api.prevent_exit();
+            return;
+        }
+    }
+    api.prevent_exit();
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let (done, receiver) = mpsc::channel();
+        let worker = app.clone();
+        std::thread::spawn(move || {
+            flush_and_release(&worker);
+            let _ = done.send(());
+        });
+        match receiver.recv_timeout(SHUTDOWN_TIMEOUT) {
+            Ok(()) => {}
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                log::error!("退出前的清理超过 {} 秒未完成，直接退出", SHUTDOWN_TIMEOUT.as_secs())
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => log::error!("退出前的清理意外中断，直接退出"),
+        }
+        STAGE.store(FINISHED, Ordering::SeqCst);
+        app.exit(code.unwrap_or(0));
+    });
+}
@@ -0,0 +1,55 @@
+// 单实例：再次启动应用时不创建新进程，而是聚焦已有主窗口并把启动参数转发给前端
+// 前端监听 SECOND_INSTANCE_EVENT 获取完整参数；其中的 .gtgraph 文件同时交给 file_open 读取并发 open-file 事件
+
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+
+use crate::file_open;
+
+pub(crate) const SECOND_INSTANCE_EVENT: &str = "second-instance";
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SecondInstancePayload {
+    args: Vec<String>,
+    cwd: String,
+}
+
+/// 统一为本地文件路径：file:// 形式（Windows 上可能带百分号编码）解码为普通路径，
+/// 相对路径按第二个实例的工作目录补全；命令行开关原样保留
+pub(crate) fn normalize_arg(arg: &str, cwd: &str) -> String {
+    if arg.starts_with("file://") {
+        if let Some(path) = Url::parse(arg).ok().and_then(|url| url.to_file_path().ok()) {
+            return path.to_string_lossy().to_string();
+        }
+    }
+    if arg.starts_with('-') || cwd.is_empty() || Path::new(arg).is_absolute() {
+        return String::from(arg);
+    }
+    let joined = Path::new(cwd).join(arg);
+    if joined.exists() {
+        joined.to_string_lossy().to_string()
+    } else {
+        String::from(arg)
+    }
+}
+
+fn focus_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 需要作为第一个插件注册：后续启动的实例会在插件初始化时直接退出
+pub(crate) fn plugin<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    tauri_plugin_single_instance::init(|app, argv, cwd| {
+        focus_main_window(app);
+        // argv[0] 是可执行文件本身，不转发
+        let args: Vec<String> = argv.iter().skip(1).map(|arg| normalize_arg(arg, &cwd)).collect();
+        file_open::open_paths(app, None, file_open::graph_paths_from_args(args.clone()));
+        let _ = app.emit(SECOND_INSTANCE_EVENT, SecondInstancePayload { args, cwd });
+    })
+}
@@ -0,0 +1,130 @@
+// 定时快照：前端用 mark_dirty 标记有未保存编辑的文档，后台线程每隔 snapshotIntervalMinutes 分钟（设置项，默认 5，
+// 0 表示关闭）把这些文档的当前内容写入修订记录，不改动文档文件本身，作为两次手动保存之间的兜底
+// 内容与最新一条修订相同时跳过；加密文档与修订记录一样不做快照，避免内容以明文落盘
+
+use std::collections::HashMap;
+use std::sync::{Condvar, LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+use crate::{autosave, checksum, graph_document_is_encrypted, revisions, sanitize_graph_name, settings};
+
+const SNAPSHOT_INTERVAL_SETTING: &str = "snapshotIntervalMinutes";
+const DEFAULT_SNAPSHOT_INTERVAL_MINUTES: u64 = 5;
+const MAX_SNAPSHOT_INTERVAL_MINUTES: u64 = 24 * 60;
+
+struct Snapshots {
+    // 文档名 -> mark_dirty 时附带的内容；未附带时取自动保存的待写内容
+    dirty: Mutex<HashMap<String, Option<String>>>,
+    // 间隔变更时唤醒后台线程重新计时
+    wake: Condvar,
+}
+
+static SNAPSHOTS: LazyLock<Snapshots> = LazyLock::new(|| Snapshots {
+    dirty: Mutex::new(HashMap::new()),
+    wake: Condvar::new(),
+});
+
+fn lock_dirty() -> Result<MutexGuard<'static, HashMap<String, Option<String>>>, AppError> {
+    SNAPSHOTS
+        .dirty
+        .lock()
+        .map_err(|_| AppError::Io(String::from("快照状态不可用")))
+}
+
+fn snapshot_interval_minutes() -> u64 {
+    settings::read_setting(SNAPSHOT_INTERVAL_SETTING)
+        .unwrap_or(DEFAULT_SNAPSHOT_INTERVAL_MINUTES)
+        .min(MAX_SNAPSHOT_INTERVAL_MINUTES)
+}
+
+fn take_snapshot(name: &str, data: Option<String>) -> Result<bool, AppError> {
+    let Some(data) = data.or_else(|| autosave::pending_plain_data(name)) else {
+        return Ok(false);
+    };
+    if graph_document_is_encrypted(name)? {
+        return Ok(false);
+    }
+    if revisions::latest_revision_hash(name)?.is_some_and(|hash| hash == checksum::sha256_hex(data.as_bytes())) {
+        return Ok(false);
+    }
+    revisions::record_revision(name, data.as_bytes())?;
+    Ok(true)
+}
+
+fn run_worker() {
+    let mut last_run = Instant::now();
+    loop {
+        let Ok(dirty) = lock_dirty() else {
+            return;
+        };
+        let minutes = snapshot_interval_minutes();
+        if minutes == 0 {
+            drop(SNAPSHOTS.wake.wait(dirty));
+            last_run = Instant::now();
+            continue;
+        }
+        let deadline = last_run + Duration::from_secs(minutes * 60);
+        let now = Instant::now();
+        if now < deadline {
+            drop(SNAPSHOTS.wake.wait_timeout(dirty, deadline - now));
+            continue;
+        }
+        last_run = now;
+        let taken = {
+            let mut dirty = dirty;
+            std::mem::take(&mut *dirty)
+        };
+        for (name, data) in taken {
+            match take_snapshot(&name, data) {
+                Ok(true) => log::info!("已写入定时快照: name={}", name),
+                Ok(false) => {}
+                Err(error) => log::warn!("写入定时快照失败 {}: {}", name, error),
+            }
+        }
+    }
+}
+
+/// 在 setup 中调用：启动快照线程
+pub(crate) fn install() {
+    if let Err(error) = std::thread::Builder::new()
+        .name(String::from("snapshot"))
+        .spawn(run_worker)
+    {
+        log::error!("启动快照线程失败: {}", error);
+    }
+}
+
+/// 文档保存、删除或重命名后调用：已落盘的内容不必再做快照，待快照的旧内容也不应排在新保存之后
+pub(crate) fn forget(name: &str) {
+    if let Ok(mut dirty) = lock_dirty() {
+        dirty.remove(name);
+    }
+}
+
+/// data 为当前的文档内容；省略时快照取该文档在自动保存中的待写内容，两者都没有则本轮跳过
+#[tauri::command]
+pub(crate) fn mark_dirty(name: String, data: Option<String>) -> Result<(), AppError> {
+    let name = sanitize_graph_name(Some(&name))?;
+    lock_dirty()?.insert(name, data);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_snapshot_interval() -> u64 {
+    snapshot_interval_minutes()
+}
+
+/// 立即生效并从现在起重新计时；0 表示关闭定时快照
+#[tauri::command]
+pub(crate) fn set_snapshot_interval(minutes: u64) -> Result<(), AppError> {
+    if minutes > MAX_SNAPSHOT_INTERVAL_MINUTES {
+        return Err(AppError::InvalidInput(format!(
+            "快照间隔不能超过 {} 分钟",
+            MAX_SNAPSHOT_INTERVAL_MINUTES
+        )));
+    }
+    settings::write_setting(SNAPSHOT_INTERVAL_SETTING, minutes)?;
+    SNAPSHOTS.wake.notify_all();
+    Ok(())
+}
@@ -0,0 +1,147 @@
+// 表格列统计：表格只解析一次，逐列累加数值，替代前端每次渲染时的全表遍历
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::run_blocking;
+use crate::table::{cell_matches_type, cell_number, is_empty_cell, parse_table, TableColumn, TableData};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ColumnStats {
+    column: String,
+    count: usize,
+    sum: f64,
+    // 没有任何数值时为 null，而不是 NaN
+    mean: Option<f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    null_count: usize,
+    // 非空但不符合列类型的单元格数，判断规则与保存时的类型检查相同
+    invalid_count: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Stats {
+    row_count: usize,
+    columns: Vec<ColumnStats>,
+}
+
+/// 统计单列：空单元格计入 null_count，非数值单元格跳过，不计入 count
+fn column_stats(table: &TableData, column: &TableColumn) -> ColumnStats {
+    let mut stats = ColumnStats {
+        column: column.id.clone(),
+        count: 0,
+        sum: 0.0,
+        mean: None,
+        min: None,
+        max: None,
+        null_count: 0,
+        invalid_count: 0,
+    };
+    for row in &table.rows {
+        let value = row.get(&column.id).unwrap_or(&Value::Null);
+        if is_empty_cell(value) {
+            stats.null_count += 1;
+            continue;
+        }
+        if !cell_matches_type(value, column.column_type) {
+            stats.invalid_count += 1;
+        }
+        let Some(number) = cell_number(value) else {
+            continue;
+        };
+        stats.count += 1;
+        stats.sum += number;
+        stats.min = Some(stats.min.map_or(number, |min| min.min(number)));
+        stats.max = Some(stats.max.map_or(number, |max| max.max(number)));
+    }
+    if stats.count > 0 {
+        stats.mean = Some(stats.sum / stats.count as f64);
+    }
+    stats
+}
+
+pub(crate) fn compute_table_stats(table: &TableData, columns: &[String]) -> Result<Stats, AppError> {
+    let mut selected = Vec::with_capacity(columns.len());
+    let mut missing = Vec::new();
+    for id in columns {
+        match table.columns.iter().find(|column| &column.id == id) {
+            Some(column) => selected.push(column),
+            None => missing.push(id.as_str()),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(AppError::NotFound(format!("表格中不存在列: {}", missing.join(", "))));
+    }
+    Ok(Stats {
+        row_count: table.rows.len(),
+        columns: selected.into_iter().map(|column| column_stats(table, column)).collect(),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn compute_stats(data: String, columns: Vec<String>) -> Result<Stats, AppError> {
+    run_blocking(move || compute_table_stats(&parse_table(&data)?, &columns)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ROW_COUNT: usize = 100_000;
+
+    /// n 列：每 10 行一个空值、一个非数值文本，其余为行号；empty 列全为空
+    fn synthetic_table() -> String {
+        let rows: Vec<Value> = (0..ROW_COUNT)
+            .map(|index| match index % 10 {
+                0 => json!({ "n": null, "empty": "" }),
+                5 => json!({ "n": "n/a", "empty": null }),
+                _ => json!({ "n": index, "empty": null }),
+            })
+            .collect();
+        json!({
+            "table": {
+                "columns": [{ "id": "n", "type": "number" }, { "id": "empty", "type": "number" }],
+                "rows": rows,
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn stats_over_100k_rows() {
+        let data = synthetic_table();
+        let stats = compute_table_stats(&parse_table(&data).unwrap(), &[String::from("n"), String::from("empty")])
+            .unwrap();
+
+        let numbers: Vec<f64> = (0..ROW_COUNT)
+            .filter(|index| !matches!(index % 10, 0 | 5))
+            .map(|index| index as f64)
+            .collect();
+        let sum: f64 = numbers.iter().sum();
+        assert_eq!(stats.row_count, ROW_COUNT);
+        let n = &stats.columns[0];
+        assert_eq!(n.count, numbers.len());
+        assert_eq!(n.sum, sum);
+        assert_eq!(n.mean, Some(sum / numbers.len() as f64));
+        assert_eq!(n.min, Some(1.0));
+        assert_eq!(n.max, Some((ROW_COUNT - 1) as f64));
+        assert_eq!(n.null_count, ROW_COUNT / 10);
+        assert_eq!(n.invalid_count, ROW_COUNT / 10);
+
+        let empty = &stats.columns[1];
+        assert_eq!((empty.count, empty.null_count), (0, ROW_COUNT));
+        assert_eq!((empty.mean, empty.min, empty.max), (None, None, None));
+    }
+
+    #[test]
+    fn unknown_columns_are_reported() {
+        let table = parse_table(r#"{"table": {"columns": [{"id": "a"}], "rows": []}}"#).unwrap();
+        let error = compute_table_stats(&table, &[String::from("a"), String::from("b")]).err().unwrap();
+        assert_eq!(error.code(), "NOT_FOUND");
+    }
+}
@@ -0,0 +1,801 @@
+// 文档存储后端：默认整份 JSON 文件（可压缩/加密），超大文档可切换为 SQLite，节点、连线、表格行分表存放，
+// patch_graph（以及 update_nodes / delete_nodes）在 SQLite 上只改动涉及的行，在 JSON 上读改写整份文档
+// 补丁应用后按 danglingEdges 设置检查悬空连线，reject 时整个补丁不生效
+// 同一文档的保存与补丁通过 with_document_lock 串行执行，每次保存或补丁使文档的 revision 加一，供前端检测并发修改
+// revision 不写进用户文档，两种后端都记录在附属文件 `<name>.json.rev` 中，切换后端后继续递增
+// 每个文档使用哪种后端记录在设置项 storageBackends（文档名 -> "json" | "sqlite"），未记录的文档使用 JSON
+// SQLite 库文件为 `<name>.json.sqlite`，属于文档的附属文件，随文档一起删除和重命名
+// 切换到 SQLite 后删除原 JSON 文件及其校验文件，文档只有一份：存在性、元数据与文件监听都经 resolve_document_file
+// 取当前后端的文件；SQLite 文档整份保存前把旧内容以 JSON 写入备份，加载时用 PRAGMA quick_check 代替校验文件
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::{backups, checksum, file_lock, watcher};
+use crate::json_format;
+use crate::migrations::{CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY};
+use crate::progress::ProgressFn;
+use crate::validate::{self, DanglingEdgePolicy};
+use crate::{
+    graph_document_is_encrypted, is_compressed_graph_path, normalize_password, read_graph_file_document,
+    resolve_app_data_dir, resolve_compressed_graph_path, resolve_existing_graph_path, resolve_named_graph_path,
+    run_blocking, sanitize_required_graph_name, settings, write_file_atomic, write_graph_file_document,
+    GraphWriteOptions,
+};
+
+const BACKENDS_SETTING: &str = "storageBackends";
+pub(crate) const SQLITE_FILE_SUFFIX: &str = ".json.sqlite";
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const REVISION_FILE_SUFFIX: &str = ".json.rev";
+
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS nodes (id TEXT PRIMARY KEY, position INTEGER NOT NULL, data TEXT NOT NULL);
+CREATE TABLE IF NOT EXISTS edges (position INTEGER PRIMARY KEY, id TEXT, source TEXT, target TEXT, data TEXT NOT NULL);
+CREATE UNIQUE INDEX IF NOT EXISTS edges_id ON edges (id);
+CREATE INDEX IF NOT EXISTS edges_source ON edges (source);
+CREATE INDEX IF NOT EXISTS edges_target ON edges (target);
+CREATE TABLE IF NOT EXISTS table_rows (position INTEGER PRIMARY KEY, data TEXT NOT NULL);
+";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum StorageBackend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+/// 依次执行：删除节点（连同以其为端点的连线）、删除连线、按 id 更新或追加节点、按 id 更新或追加连线
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct GraphPatch {
+    upsert_nodes: Vec<Value>,
+    delete_nodes: Vec<String>,
+    upsert_edges: Vec<Value>,
+    delete_edges: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PatchOutcome {
+    revision: u64,
+    schema_version: u64,
+    // 按 danglingEdges 设置为 drop 时被剔除的悬空连线
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    dropped_edges: Vec<String>,
+}
+
+/// 命名文档的读写接口；load 在文档不存在时返回 None
+pub(crate) trait GraphStorage {
+    fn load(&self, name: &str, password: Option<&str>, progress: Option<ProgressFn>) -> Result<Option<String>, AppError>;
+    fn save(&self, name: &str, contents: &[u8], options: GraphWriteOptions) -> Result<PathBuf, AppError>;
+    /// expected_revision 与文档当前 revision 不一致时返回 REVISION_CONFLICT，不做任何修改
+    fn apply_patch(
+        &self,
+        name: &str,
+        patch: GraphPatch,
+        expected_revision: Option<u64>,
+        password: Option<&str>,
+    ) -> Result<PatchOutcome, AppError>;
+}
+
+pub(crate) struct JsonFileStorage;
+pub(crate) struct SqliteStorage;
+
+static DOCUMENT_LOCKS: LazyLock<Mutex<HashMap<String, Arc<Mutex<()>>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn document_mutex(name: &str) -> Result<Arc<Mutex<()>>, AppError> {
+    Ok(DOCUMENT_LOCKS
+        .lock()
+        .map_err(|_| AppError::Io(String::from("文档锁不可用")))?
+        .entry(String::from(name))
+        .or_default()
+        .clone())
+}
+
+/// 同一文档的写入（保存、补丁、切换后端、删除、重命名）串行执行，避免读改写交错导致修改丢失；
+/// 进程内用互斥锁，跨进程用 file_lock 的文件锁
+pub(crate) fn with_document_lock<T>(name: &str, task: impl FnOnce() -> Result<T, AppError>) -> Result<T, AppError> {
+    let lock = document_mutex(name)?;
+    let _guard = lock.lock().map_err(|_| AppError::Io(String::from("文档锁不可用")))?;
+    let _file_lock = file_lock::acquire(name)?;
+    task()
+}
+
+/// 删除或重命名文档使用：与 with_document_lock 相同，task 成功后连同 `<name>.lock` 一起删除
+pub(crate) fn with_retiring_document_lock<T>(
+    name: &str,
+    task: impl FnOnce() -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let lock = document_mutex(name)?;
+    let _guard = lock.lock().map_err(|_| AppError::Io(String::from("文档锁不可用")))?;
+    let file_lock = file_lock::acquire(name)?;
+    let result = task()?;
+    file_lock.remove();
+    Ok(result)
+}
+
+fn read_backends() -> HashMap<String, StorageBackend> {
+    settings::read_setting(BACKENDS_SETTING).unwrap_or_default()
+}
+
+fn write_backends(backends: &HashMap<String, StorageBackend>) -> Result<(), AppError> {
+    settings::write_setting(BACKENDS_SETTING, backends)
+}
+
+pub(crate) fn backend_for(name: &str) -> StorageBackend {
+    read_backends().get(name).copied().unwrap_or_default()
+}
+
+fn storage_of(backend: StorageBackend) -> &'static dyn GraphStorage {
+    match backend {
+        StorageBackend::Json => &JsonFileStorage,
+        StorageBackend::Sqlite => &SqliteStorage,
+    }
+}
+
+pub(crate) fn storage_for(name: &str) -> &'static dyn GraphStorage {
+    storage_of(backend_for(name))
+}
+
+/// 文档重命名后后端记录跟随新名称
+pub(crate) fn rename_backend(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    let mut backends = read_backends();
+    let Some(backend) = backends.remove(old_name) else {
+        return Ok(());
+    };
+    backends.insert(String::from(new_name), backend);
+    write_backends(&backends)
+}
+
+/// 复制文档时调用：SQLite 文档连同库文件一起复制，并为新文档记录同样的后端
+pub(crate) fn copy_backend(src_name: &str, dest_name: &str) -> Result<(), AppError> {
+    if backend_for(src_name) != StorageBackend::Sqlite {
+        return Ok(());
+    }
+    let bytes = std::fs::read(resolve_sqlite_path(src_name)?).map_err(|e| AppError::io("读取 SQLite 存储失败", e))?;
+    write_file_atomic(&resolve_sqlite_path(dest_name)?, &bytes)?;
+    let mut backends = read_backends();
+    backends.insert(String::from(dest_name), StorageBackend::Sqlite);
+    write_backends(&backends)
+}
+
+pub(crate) fn forget_backend(name: &str) -> Result<(), AppError> {
+    let mut backends = read_backends();
+    if backends.remove(name).is_none() {
+        return Ok(());
+    }
+    write_backends(&backends)
+}
+
+fn node_id(node: &Value) -> Result<String, AppError> {
+    node.get("id")
+        .and_then(Value::as_str)
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .ok_or_else(|| AppError::InvalidInput(String::from("节点缺少 id")))
+}
+
+fn edge_id(edge: &Value) -> Result<String, AppError> {
+    edge.get("id")
+        .and_then(Value::as_str)
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .ok_or_else(|| AppError::InvalidInput(String::from("连线缺少 id")))
+}
+
+fn edge_endpoint<'a>(edge: &'a Value, key: &str) -> Option<&'a str> {
+    edge.get(key).and_then(Value::as_str)
+}
+
+fn parse_document(contents: &[u8]) -> Result<Map<String, Value>, AppError> {
+    match serde_json::from_slice(contents) {
+        Ok(Value::Object(document)) => Ok(document),
+        Ok(_) => Err(AppError::Serialization(String::from("文档顶层必须是 JSON 对象"))),
+        Err(e) => Err(AppError::Serialization(format!("解析文档失败: {}", e))),
+    }
+}
+
+fn to_json(value: &impl Serialize, label: &str) -> Result<String, AppError> {
+    serde_json::to_string(value).map_err(|e| AppError::Serialization(format!("序列化{}失败: {}", label, e)))
+}
+
+fn from_json(text: &str, label: &str) -> Result<Value, AppError> {
+    serde_json::from_str(text).map_err(|e| AppError::Serialization(format!("解析{}失败: {}", label, e)))
+}
+
+fn resolve_revision_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}{}", name, REVISION_FILE_SUFFIX)))
+}
+
+/// 从未保存过或附属文件无法解析时为 0
+pub(crate) fn current_revision(name: &str) -> Result<u64, AppError> {
+    match std::fs::read_to_string(resolve_revision_path(name)?) {
+        Ok(text) => Ok(text.trim().parse().unwrap_or(0)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(AppError::io("读取文档 revision 失败", e)),
+    }
+}
+
+/// 文档内容写入成功后调用，返回新的 revision
+pub(crate) fn bump_revision(name: &str) -> Result<u64, AppError> {
+    let revision = current_revision(name)? + 1;
+    write_file_atomic(&resolve_revision_path(name)?, revision.to_string().as_bytes())?;
+    Ok(revision)
+}
+
+fn check_revision(current: u64, expected: Option<u64>) -> Result<(), AppError> {
+    match expected {
+        Some(expected) if expected != current => Err(AppError::RevisionConflict(format!(
+            "文档已被修改（当前 revision {}，期望 {}），请重新加载后再试",
+            current, expected
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn schema_version_of(value: Option<&Value>) -> u64 {
+    value.and_then(Value::as_u64).unwrap_or(CURRENT_SCHEMA_VERSION)
+}
+
+/// 补丁中的元素必须带 id，先全部校验再动手，避免改到一半失败
+fn keyed(items: Vec<Value>, id_of: fn(&Value) -> Result<String, AppError>) -> Result<Vec<(String, Value)>, AppError> {
+    items.into_iter().map(|item| Ok((id_of(&item)?, item))).collect()
+}
+
+/// 已有 id 原位替换，新 id 追加到末尾
+fn upsert_by_id(items: &mut Vec<Value>, updates: Vec<(String, Value)>) {
+    let mut positions: HashMap<String, usize> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| item.get("id").and_then(Value::as_str).map(|id| (String::from(id), index)))
+        .collect();
+    for (id, item) in updates {
+        match positions.get(&id) {
+            Some(&index) => items[index] = item,
+            None => {
+                positions.insert(id, items.len());
+                items.push(item);
+            }
+        }
+    }
+}
+
+fn array_field<'a>(document: &'a mut Map<String, Value>, key: &str) -> Result<&'a mut Vec<Value>, AppError> {
+    document
+        .entry(key)
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+        .ok_or_else(|| AppError::Serialization(format!("文档字段 {} 必须是数组", key)))
+}
+
+fn patch_document(document: &mut Map<String, Value>, patch: GraphPatch) -> Result<(), AppError> {
+    let node_updates = keyed(patch.upsert_nodes, node_id)?;
+    let edge_updates = keyed(patch.upsert_edges, edge_id)?;
+    let removed_nodes: HashSet<&str> = patch.delete_nodes.iter().map(String::as_str).collect();
+    let removed_edges: HashSet<&str> = patch.delete_edges.iter().map(String::as_str).collect();
+
+    let nodes = array_field(document, "nodes")?;
+    nodes.retain(|node| !node.get("id").and_then(Value::as_str).is_some_and(|id| removed_nodes.contains(id)));
+    upsert_by_id(nodes, node_updates);
+    let edges = array_field(document, "edges")?;
+    edges.retain(|edge| {
+        let incident = ["source", "target"]
+            .iter()
+            .any(|key| edge_endpoint(edge, key).is_some_and(|id| removed_nodes.contains(id)));
+        let removed = edge.get("id").and_then(Value::as_str).is_some_and(|id| removed_edges.contains(id));
+        !incident && !removed
+    });
+    upsert_by_id(edges, edge_updates);
+    Ok(())
+}
+
+impl GraphPatch {
+    /// 补丁中节点与连线序列化后的字节数
+    fn serialized_len(&self) -> usize {
+        validate::serialized_len(&self.upsert_nodes)
+            + validate::serialized_len(&self.delete_nodes)
+            + validate::serialized_len(&self.upsert_edges)
+            + validate::serialized_len(&self.delete_edges)
+    }
+}
+
+impl JsonFileStorage {
+    /// 读 - 改 - 写回整份文档，保持原有的压缩与加密格式；加密文档没有密码时在读取前返回 PASSWORD_REQUIRED
+    fn rewrite<T>(
+        &self,
+        name: &str,
+        password: Option<&str>,
+        patch: impl FnOnce(&mut Map<String, Value>) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let password = normalize_password(password);
+        if password.is_none() && graph_document_is_encrypted(name)? {
+            return Err(AppError::PasswordRequired(format!("文档已加密，修改需要密码: {}", name)));
+        }
+        let Some(contents) = self.load(name, password, None)? else {
+            return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+        };
+        let mut document = parse_document(contents.as_bytes())?;
+        let result = patch(&mut document)?;
+        let compress = resolve_existing_graph_path(name)?
+            .map(|path| is_compressed_graph_path(&path))
+            .unwrap_or(true);
+        let options = GraphWriteOptions {
+            compress,
+            password,
+            ..GraphWriteOptions::default()
+        };
+        let contents = json_format::render_value(Value::Object(document), json_format::default_format())?;
+        // 补丁本身未超限，但叠加后的整份文档仍可能超限
+        validate::check_document_size(contents.len())?;
+        self.save(name, contents.as_bytes(), options)?;
+        Ok(result)
+    }
+}
+
+impl GraphStorage for JsonFileStorage {
+    fn load(&self, name: &str, password: Option<&str>, progress: Option<ProgressFn>) -> Result<Option<String>, AppError> {
+        read_graph_file_document(name, password, progress)
+    }
+
+    fn save(&self, name: &str, contents: &[u8], options: GraphWriteOptions) -> Result<PathBuf, AppError> {
+        write_graph_file_document(name, contents, options)
+    }
+
+    /// 写回整份文档时由 write_graph_document_bytes 递增 revision
+    fn apply_patch(
+        &self,
+        name: &str,
+        patch: GraphPatch,
+        expected_revision: Option<u64>,
+        password: Option<&str>,
+    ) -> Result<PatchOutcome, AppError> {
+        check_revision(current_revision(name)?, expected_revision)?;
+        let (schema_version, dropped_edges) = self.rewrite(name, password, |document| {
+            patch_document(document, patch)?;
+            let dropped_edges = validate::apply_dangling_edge_policy(document, validate::dangling_edge_policy())?;
+            Ok((schema_version_of(document.get(SCHEMA_VERSION_KEY)), dropped_edges))
+        })?;
+        Ok(PatchOutcome {
+            revision: current_revision(name)?,
+            schema_version,
+            dropped_edges,
+        })
+    }
+}
+
+fn sqlite_error(context: &str) -> impl Fn(rusqlite::Error) -> AppError + '_ {
+    move |error| AppError::Io(format!("{}: {}", context, error))
+}
+
+pub(crate) fn resolve_sqlite_path(name: &str) -> Result<PathBuf, AppError> {
+    Ok(resolve_app_data_dir()?.join(format!("{}{}", name, SQLITE_FILE_SUFFIX)))
+}
+
+/// 按文档当前的后端返回保存内容的文件：JSON 为 `.json.gz` 或 `.json`，SQLite 为库文件；文档不存在时返回 None
+pub(crate) fn resolve_document_file(name: &str) -> Result<Option<PathBuf>, AppError> {
+    match backend_for(name) {
+        StorageBackend::Json => resolve_existing_graph_path(name),
+        StorageBackend::Sqlite => {
+            let path = resolve_sqlite_path(name)?;
+            Ok(path.is_file().then_some(path))
+        }
+    }
+}
+
+fn open_database(name: &str) -> Result<Connection, AppError> {
+    let connection = Connection::open(resolve_sqlite_path(name)?).map_err(sqlite_error("打开 SQLite 存储失败"))?;
+    connection
+        .busy_timeout(SQLITE_BUSY_TIMEOUT)
+        .map_err(sqlite_error("配置 SQLite 存储失败"))?;
+    connection
+        .execute_batch(SQLITE_SCHEMA)
+        .map_err(sqlite_error("初始化 SQLite 存储失败"))?;
+    Ok(connection)
+}
+
+/// 与 validate::apply_dangling_edge_policy 相同的规则，直接在库中查找端点不在 nodes 表中的连线
+fn apply_sqlite_dangling_edge_policy(
+    transaction: &Transaction,
+    policy: DanglingEdgePolicy,
+) -> Result<Vec<String>, AppError> {
+    if policy == DanglingEdgePolicy::Keep {
+        return Ok(Vec::new());
+    }
+    let mut statement = transaction
+        .prepare(
+            "SELECT position, id FROM edges WHERE source IS NULL OR target IS NULL \
+             OR source NOT IN (SELECT id FROM nodes WHERE id IS NOT NULL) \
+             OR target NOT IN (SELECT id FROM nodes WHERE id IS NOT NULL) ORDER BY position",
+        )
+        .map_err(sqlite_error("读取连线失败"))?;
+    let dangling = statement
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?)))
+        .map_err(sqlite_error("读取连线失败"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(sqlite_error("读取连线失败"))?;
+    if dangling.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ids: Vec<String> = dangling
+        .iter()
+        .map(|(position, id)| match id {
+            Some(id) if !id.is_empty() => id.clone(),
+            _ => format!("#{}", position),
+        })
+        .collect();
+    if policy == DanglingEdgePolicy::Reject {
+        return Err(validate::dangling_edges_error(ids));
+    }
+    for (position, _) in &dangling {
+        transaction
+            .execute("DELETE FROM edges WHERE position = ?1", params![position])
+            .map_err(sqlite_error("删除连线失败"))?;
+    }
+    Ok(ids)
+}
+
+/// 库文件损坏时返回 CHECKSUM_MISMATCH，与 JSON 文档校验失败的错误码一致
+fn check_integrity(connection: &Connection, name: &str) -> Result<(), AppError> {
+    let result: String = connection
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(sqlite_error("校验 SQLite 存储失败"))?;
+    if result == "ok" {
+        return Ok(());
+    }
+    Err(AppError::ChecksumMismatch(format!("SQLite 存储已损坏: {}: {}", name, result)))
+}
+
+/// verify_graph 使用：库文件通过完整性检查时返回 true
+pub(crate) fn verify_sqlite(name: &str) -> Result<bool, AppError> {
+    if !resolve_sqlite_path(name)?.is_file() {
+        return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+    }
+    match check_integrity(&open_database(name)?, name) {
+        Ok(()) => Ok(true),
+        Err(AppError::ChecksumMismatch(_)) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+fn next_position(transaction: &Transaction, table: &str) -> Result<i64, AppError> {
+    transaction
+        .query_row(&format!("SELECT COALESCE(MAX(position), -1) + 1 FROM {}", table), [], |row| row.get(0))
+        .map_err(sqlite_error("读取文档失败"))
+}
+
+fn read_meta(transaction: &Transaction, key: &str) -> Result<Option<Value>, AppError> {
+    let text: Option<String> = transaction
+        .query_row("SELECT value FROM meta WHERE key = ?1", params![key], |row| row.get(0))
+        .optional()
+        .map_err(sqlite_error("读取文档字段失败"))?;
+    text.map(|text| from_json(&text, "文档字段")).transpose()
+}
+
+/// 表格行单独成表，其余表格字段（列定义等）与顶层字段一起存为 meta
+fn write_document(transaction: &Transaction, mut document: Map<String, Value>) -> Result<(), AppError> {
+    for table in ["meta", "nodes", "edges", "table_rows"] {
+        transaction
+            .execute(&format!("DELETE FROM {}", table), [])
+            .map_err(sqlite_error("清空 SQLite 存储失败"))?;
+    }
+
+    let nodes = match document.remove("nodes") {
+        Some(Value::Array(nodes)) => nodes,
+        _ => Vec::new(),
+    };
+    let edges = match document.remove("edges") {
+        Some(Value::Array(edges)) => edges,
+        _ => Vec::new(),
+    };
+    let rows = match document.get_mut("table").and_then(Value::as_object_mut) {
+        Some(table) => match table.remove("rows") {
+            Some(Value::Array(rows)) => rows,
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+
+    let mut insert_node = transaction
+        .prepare("INSERT INTO nodes (id, position, data) VALUES (?1, ?2, ?3)")
+        .map_err(sqlite_error("写入节点失败"))?;
+    let mut seen = HashSet::new();
+    for (position, node) in nodes.iter().enumerate() {
+        let id = node_id(node)?;
+        if !seen.insert(id.clone()) {
+            return Err(AppError::InvalidInput(format!("节点 id 重复: {}", id)));
+        }
+        insert_node
+            .execute(params![id, position as i64, to_json(node, "节点")?])
+            .map_err(sqlite_error("写入节点失败"))?;
+    }
+    let mut insert_edge = transaction
+        .prepare("INSERT INTO edges (position, id, source, target, data) VALUES (?1, ?2, ?3, ?4, ?5)")
+        .map_err(sqlite_error("写入连线失败"))?;
+    let mut seen = HashSet::new();
+    for (position, edge) in edges.iter().enumerate() {
+        let id = edge.get("id").and_then(Value::as_str);
+        if let Some(id) = id {
+            if !seen.insert(id) {
+                return Err(AppError::InvalidInput(format!("连线 id 重复: {}", id)));
+            }
+        }
+        insert_edge
+            .execute(params![
+                position as i64,
+                id,
+                edge_endpoint(edge, "source"),
+                edge_endpoint(edge, "target"),
+                to_json(edge, "连线")?
+            ])
+            .map_err(sqlite_error("写入连线失败"))?;
+    }
+    let mut insert_row = transaction
+        .prepare("INSERT INTO table_rows (position, data) VALUES (?1, ?2)")
+        .map_err(sqlite_error("写入表格行失败"))?;
+    for (position, row) in rows.iter().enumerate() {
+        insert_row
+            .execute(params![position as i64, to_json(row, "表格行")?])
+            .map_err(sqlite_error("写入表格行失败"))?;
+    }
+    let mut insert_meta = transaction
+        .prepare("INSERT INTO meta (key, value) VALUES (?1, ?2)")
+        .map_err(sqlite_error("写入文档字段失败"))?;
+    for (key, value) in &document {
+        insert_meta
+            .execute(params![key, to_json(value, "文档字段")?])
+            .map_err(sqlite_error("写入文档字段失败"))?;
+    }
+    Ok(())
+}
+
+fn read_column(connection: &Connection, sql: &str, label: &str) -> Result<Vec<Value>, AppError> {
+    let context = format!("读取{}失败", label);
+    let mut statement = connection.prepare(sql).map_err(sqlite_error(&context))?;
+    let texts = statement
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(sqlite_error(&context))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(sqlite_error(&context))?;
+    texts.iter().map(|text| from_json(text, label)).collect()
+}
+
+fn read_document(connection: &Connection) -> Result<Map<String, Value>, AppError> {
+    let mut document = Map::new();
+    let mut statement = connection
+        .prepare("SELECT key, value FROM meta ORDER BY key")
+        .map_err(sqlite_error("读取文档字段失败"))?;
+    let entries = statement
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(sqlite_error("读取文档字段失败"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(sqlite_error("读取文档字段失败"))?;
+    for (key, value) in entries {
+        document.insert(key, from_json(&value, "文档字段")?);
+    }
+    document.insert(
+        String::from("nodes"),
+        Value::Array(read_column(connection, "SELECT data FROM nodes ORDER BY position", "节点")?),
+    );
+    document.insert(
+        String::from("edges"),
+        Value::Array(read_column(connection, "SELECT data FROM edges ORDER BY position", "连线")?),
+    );
+    if let Some(table) = document.get_mut("table").and_then(Value::as_object_mut) {
+        let rows = read_column(connection, "SELECT data FROM table_rows ORDER BY position", "表格行")?;
+        table.insert(String::from("rows"), Value::Array(rows));
+    }
+    Ok(document)
+}
+
+impl GraphStorage for SqliteStorage {
+    /// SQLite 存储不加密，password 被忽略；进度回调同样不适用
+    fn load(&self, name: &str, _password: Option<&str>, _progress: Option<ProgressFn>) -> Result<Option<String>, AppError> {
+        if !resolve_sqlite_path(name)?.is_file() {
+            return Ok(None);
+        }
+        let connection = open_database(name)?;
+        check_integrity(&connection, name)?;
+        to_json(&read_document(&connection)?, "文档").map(Some)
+    }
+
+    fn save(&self, name: &str, contents: &[u8], options: GraphWriteOptions) -> Result<PathBuf, AppError> {
+        if normalize_password(options.password).is_some() {
+            return Err(AppError::InvalidInput(String::from("SQLite 存储不支持加密")));
+        }
+        let document = parse_document(contents)?;
+        let path = resolve_sqlite_path(name)?;
+        let existed = path.is_file();
+        let mut connection = open_database(name)?;
+        // 与 JSON 文档一样，覆盖前备份旧内容
+        if existed {
+            let previous = to_json(&read_document(&connection)?, "文档")?;
+            backups::backup_contents(name, previous.as_bytes(), options.backup_interval)?;
+        }
+        let transaction = connection.transaction().map_err(sqlite_error("开始事务失败"))?;
+        write_document(&transaction, document)?;
+        transaction.commit().map_err(sqlite_error("提交事务失败"))?;
+        watcher::note_self_write(name, &path);
+        bump_revision(name)?;
+        Ok(path)
+    }
+
+    fn apply_patch(
+        &self,
+        name: &str,
+        patch: GraphPatch,
+        expected_revision: Option<u64>,
+        _password: Option<&str>,
+    ) -> Result<PatchOutcome, AppError> {
+        if !resolve_sqlite_path(name)?.is_file() {
+            return Err(AppError::NotFound(format!("文档不存在: {}", name)));
+        }
+        let node_updates = keyed(patch.upsert_nodes, node_id)?;
+        let edge_updates = keyed(patch.upsert_edges, edge_id)?;
+        let mut connection = open_database(name)?;
+        let transaction = connection.transaction().map_err(sqlite_error("开始事务失败"))?;
+        check_revision(current_revision(name)?, expected_revision)?;
+
+        for id in &patch.delete_nodes {
+            transaction
+                .execute("DELETE FROM nodes WHERE id = ?1", params![id])
+                .map_err(sqlite_error("删除节点失败"))?;
+            transaction
+                .execute("DELETE FROM edges WHERE source = ?1 OR target = ?1", params![id])
+                .map_err(sqlite_error("删除连线失败"))?;
+        }
+        for id in &patch.delete_edges {
+            transaction
+                .execute("DELETE FROM edges WHERE id = ?1", params![id])
+                .map_err(sqlite_error("删除连线失败"))?;
+        }
+        let mut position = next_position(&transaction, "nodes")?;
+        for (id, node) in node_updates {
+            let data = to_json(&node, "节点")?;
+            let updated = transaction
+                .execute("UPDATE nodes SET data = ?2 WHERE id = ?1", params![id, data])
+                .map_err(sqlite_error("更新节点失败"))?;
+            if updated == 0 {
+                transaction
+                    .execute("INSERT INTO nodes (id, position, data) VALUES (?1, ?2, ?3)", params![id, position, data])
+                    .map_err(sqlite_error("写入节点失败"))?;
+                position += 1;
+            }
+        }
+        let mut position = next_position(&transaction, "edges")?;
+        for (id, edge) in edge_updates {
+            let data = to_json(&edge, "连线")?;
+            let source = edge_endpoint(&edge, "source");
+            let target = edge_endpoint(&edge, "target");
+            let updated = transaction
+                .execute(
+                    "UPDATE edges SET source = ?2, target = ?3, data = ?4 WHERE id = ?1",
+                    params![id, source, target, data],
+                )
+                .map_err(sqlite_error("更新连线失败"))?;
+            if updated == 0 {
+                transaction
+                    .execute(
+                        "INSERT INTO edges (position, id, source, target, data) VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![position, id, source, target, data],
+                    )
+                    .map_err(sqlite_error("写入连线失败"))?;
+                position += 1;
+            }
+        }
+
+        let dropped_edges = apply_sqlite_dangling_edge_policy(&transaction, validate::dangling_edge_policy())?;
+
+        let schema_version = schema_version_of(read_meta(&transaction, SCHEMA_VERSION_KEY)?.as_ref());
+        transaction.commit().map_err(sqlite_error("提交事务失败"))?;
+        watcher::note_self_write(name, &resolve_sqlite_path(name)?);
+        Ok(PatchOutcome {
+            revision: bump_revision(name)?,
+            schema_version,
+            dropped_edges,
+        })
+    }
+}
+
+/// 切换前先把现有内容写入新后端，成功后才更新设置，失败时文档仍由原后端提供
+fn switch_backend(name: &str, backend: StorageBackend) -> Result<(), AppError> {
+    let current = backend_for(name);
+    if current == backend {
+        return Ok(());
+    }
+    if let Some(contents) = storage_of(current).load(name, None, None)? {
+        storage_of(backend).save(name, contents.as_bytes(), GraphWriteOptions::default())?;
+    }
+    let mut backends = read_backends();
+    match backend {
+        StorageBackend::Json => backends.remove(name),
+        StorageBackend::Sqlite => backends.insert(String::from(name), backend),
+    };
+    write_backends(&backends)?;
+    // 切换后旧后端的文件不再使用，删除以免元数据、文件监听或下次切换时误读旧数据
+    match backend {
+        StorageBackend::Json => {
+            let _ = std::fs::remove_file(resolve_sqlite_path(name)?);
+        }
+        StorageBackend::Sqlite => {
+            for path in [resolve_compressed_graph_path(name)?, resolve_named_graph_path(name)?] {
+                let _ = std::fs::remove_file(path);
+            }
+            checksum::delete_checksum(name);
+        }
+    }
+    Ok(())
+}
+
+fn run_patch(
+    name: &str,
+    patch: GraphPatch,
+    expected_revision: Option<u64>,
+    password: Option<&str>,
+) -> Result<PatchOutcome, AppError> {
+    validate::check_document_size(patch.serialized_len())?;
+    let name = sanitize_required_graph_name(name)?;
+    with_document_lock(&name, || storage_for(&name).apply_patch(&name, patch, expected_revision, password))
+}
+
+/// 返回补丁应用后的 revision 与 schemaVersion；传入 expected_revision 时与当前不一致则拒绝
+/// 加密文档需提供 password，补丁写回后仍以该密码加密
+#[tauri::command]
+pub(crate) async fn patch_graph(
+    name: String,
+    patch: GraphPatch,
+    expected_revision: Option<u64>,
+    password: Option<String>,
+) -> Result<PatchOutcome, AppError> {
+    run_blocking(move || run_patch(&name, patch, expected_revision, password.as_deref())).await
+}
+
+/// 按 id 更新节点，不存在的 id 追加到末尾
+#[tauri::command]
+pub(crate) async fn update_nodes(
+    name: String,
+    nodes: Vec<Value>,
+    password: Option<String>,
+) -> Result<PatchOutcome, AppError> {
+    let patch = GraphPatch {
+        upsert_nodes: nodes,
+        ..GraphPatch::default()
+    };
+    run_blocking(move || run_patch(&name, patch, None, password.as_deref())).await
+}
+
+/// 删除节点及以其为端点的连线
+#[tauri::command]
+pub(crate) async fn delete_nodes(
+    name: String,
+    ids: Vec<String>,
+    password: Option<String>,
+) -> Result<PatchOutcome, AppError> {
+    let patch = GraphPatch {
+        delete_nodes: ids,
+        ..GraphPatch::default()
+    };
+    run_blocking(move || run_patch(&name, patch, None, password.as_deref())).await
+}
+
+#[tauri::command]
+pub(crate) fn get_storage_backend(name: String) -> Result<StorageBackend, AppError> {
+    Ok(backend_for(&sanitize_required_graph_name(&name)?))
+}
+
+/// 切换到 sqlite 时把现有 JSON 文档导入 SQLite 库；加密文档需先取消加密
+#[tauri::command]
+pub(crate) async fn set_storage_backend(name: String, backend: StorageBackend) -> Result<(), AppError> {
+    run_blocking(move || {
+        let name = sanitize_required_graph_name(&name)?;
+        with_document_lock(&name, || switch_backend(&name, backend))
+    })
+    .await
+}
@@ -0,0 +1,117 @@
+// 文档存储目录配置：用户可把文档放到同步盘等自定义目录，选择结果记录在默认数据目录下的 storage.json
+// 配置文件、bridge manifest 与窗口状态始终留在默认目录，MCP Server 按固定位置查找 manifest
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::{resolve_app_data_dir, resolve_default_data_dir, sanitize_required_graph_name, storage, write_file_atomic};
+
+const STORAGE_CONFIG_FILE_NAME: &str = "storage.json";
+const WRITE_PROBE_FILE_NAME: &str = ".graphandtable-write-test";
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    storage_dir: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StorageDirInfo {
+    path: String,
+    default_path: String,
+    configured_path: Option<String>,
+    // 已配置目录但当前不可用，临时回退到默认目录
+    using_fallback: bool,
+}
+
+fn resolve_storage_config_path() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(STORAGE_CONFIG_FILE_NAME))
+}
+
+fn read_storage_config() -> StorageConfig {
+    resolve_storage_config_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_storage_config(config: &StorageConfig) -> Result<(), AppError> {
+    let contents = serde_json::to_vec_pretty(config)
+        .map_err(|e| AppError::Serialization(format!("序列化存储目录配置失败: {}", e)))?;
+    write_file_atomic(&resolve_storage_config_path()?, &contents)
+}
+
+/// 已配置且当前存在的存储目录；未配置或目录不可用时返回 None，由调用方回退到默认目录
+pub(crate) fn configured_storage_dir() -> Option<PathBuf> {
+    let path = PathBuf::from(read_storage_config().storage_dir?);
+    path.is_dir().then_some(path)
+}
+
+fn ensure_writable(dir: &Path) -> Result<(), AppError> {
+    let probe_path = dir.join(WRITE_PROBE_FILE_NAME);
+    std::fs::write(&probe_path, b"").map_err(|e| AppError::io("存储目录不可写", e))?;
+    let _ = std::fs::remove_file(probe_path);
+    Ok(())
+}
+
+fn build_storage_dir_info() -> Result<StorageDirInfo, AppError> {
+    let default_path = resolve_default_data_dir()?;
+    let configured_path = read_storage_config().storage_dir;
+    let active_path = configured_storage_dir();
+    Ok(StorageDirInfo {
+        using_fallback: configured_path.is_some() && active_path.is_none(),
+        path: active_path
+            .unwrap_or_else(|| default_path.clone())
+            .to_string_lossy()
+            .to_string(),
+        default_path: default_path.to_string_lossy().to_string(),
+        configured_path,
+    })
+}
+
+#[tauri::command]
+pub(crate) fn get_storage_dir() -> Result<StorageDirInfo, AppError> {
+    build_storage_dir_info()
+}
+
+/// 传入空字符串恢复为默认目录；只切换之后的读写位置，不迁移已有文档
+#[tauri::command]
+pub(crate) fn set_storage_dir(path: String) -> Result<StorageDirInfo, AppError> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        write_storage_config(&StorageConfig::default())?;
+        return build_storage_dir_info();
+    }
+    let dir = PathBuf::from(trimmed);
+    if !dir.is_absolute() {
+        return Err(AppError::InvalidInput(format!("存储目录必须为绝对路径: {}", trimmed)));
+    }
+    if !dir.is_dir() {
+        return Err(AppError::NotFound(format!("存储目录不存在: {}", trimmed)));
+    }
+    ensure_writable(&dir)?;
+    write_storage_config(&StorageConfig {
+        storage_dir: Some(dir.to_string_lossy().to_string()),
+    })?;
+    build_storage_dir_info()
+}
+
+/// 在系统文件管理器中打开当前的存储目录；给出文档名时打开其所在目录并选中该文档文件
+#[tauri::command]
+pub(crate) fn reveal_in_explorer(name: Option<String>) -> Result<(), AppError> {
+    let Some(name) = name.filter(|name| !name.trim().is_empty()) else {
+        let dir = resolve_app_data_dir()?;
+        return tauri_plugin_opener::open_path(&dir, None::<&str>)
+            .map_err(|e| AppError::Io(format!("打开存储目录失败: {}", e)));
+    };
+    let graph_name = sanitize_required_graph_name(&name)?;
+    let Some(file_path) = storage::resolve_document_file(&graph_name)? else {
+        return Err(AppError::NotFound(format!("文档不存在: {}", graph_name)));
+    };
+    tauri_plugin_opener::reveal_item_in_dir(&file_path)
+        .map_err(|e| AppError::Io(format!("在文件管理器中显示文档失败: {}", e)))
+}
@@ -0,0 +1,413 @@
+// 大文档流式加载：超过 STREAM_THRESHOLD 的文件不先读成字符串，而是经缓冲读取器边读边解析，
+// table.rows 中的行在解析过程中攒满一批即通过 graph-rows 事件发出，内存中只保留一批行与去掉行数据的文档骨架，
+// 命令返回骨架，前端不必再解析一整份大字符串；迁移步骤不涉及表格行，只作用于骨架
+// 加密文档、SQLite 后端与小文件仍走普通加载路径，返回完整文档；文档中途格式错误时报告出错处的字节偏移
+// 最后一批（没有行时为空批次）带 done 标记，在整个文件通过校验后才发出；命令出错时不会收到 done，
+// 前端应丢弃已收到的行。行事件在命令返回前发出，前端需先订阅 graph-rows
+
+use flate2::read::GzDecoder;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::AppError;
+use crate::progress::{self, ProgressFn, PROGRESS_CHUNK_SIZE};
+use crate::storage::{self, StorageBackend};
+use crate::{
+    checksum, describe_progress_name, graph_document_is_encrypted, is_compressed_graph_path, load_named_graph,
+    migrations, recent, resolve_existing_graph_path, run_blocking, sanitize_graph_name,
+};
+
+pub(crate) const GRAPH_ROWS_EVENT: &str = "graph-rows";
+const STREAM_THRESHOLD: u64 = 32 * 1024 * 1024;
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+const DEFAULT_BATCH_SIZE: usize = 1000;
+const MAX_BATCH_SIZE: usize = 50_000;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphRowsPayload<'a> {
+    name: &'a str,
+    offset: usize,
+    rows: &'a [Value],
+    done: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StreamedGraph {
+    // streamed 为 true 时 table.rows 为空数组，行数据经事件送达
+    document: String,
+    streamed: bool,
+    row_count: usize,
+}
+
+/// 磁盘字节的读取进度与增量摘要，解析结束后用于校验
+struct SourceState {
+    hasher: Sha256,
+    read: u64,
+}
+
+struct SourceReader<'a> {
+    file: File,
+    total: u64,
+    state: Rc<RefCell<SourceState>>,
+    progress: Option<ProgressFn<'a>>,
+}
+
+impl Read for SourceReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.file.read(buf)?;
+        let mut state = self.state.borrow_mut();
+        state.hasher.update(&buf[..count]);
+        let previous = state.read;
+        state.read += count as u64;
+        // 每跨过一个进度块报告一次，避免按缓冲区大小频繁发事件
+        let chunk = PROGRESS_CHUNK_SIZE as u64;
+        if let Some(progress) = self.progress {
+            if previous / chunk != state.read / chunk || (count == 0 && previous > 0) {
+                progress(state.read, self.total.max(state.read));
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// 记录解析器已消费的（解压后的）字节数，出错时据此给出偏移
+struct OffsetReader<R> {
+    inner: R,
+    offset: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for OffsetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.offset.set(self.offset.get() + count as u64);
+        Ok(count)
+    }
+}
+
+/// 行攒满 batch_size 后，在下一行到来时交给 sink；剩余的行由 finish 带 done 标记发出
+struct RowBatcher<'a> {
+    batch_size: usize,
+    batch: Vec<Value>,
+    offset: usize,
+    count: usize,
+    sink: &'a mut dyn FnMut(usize, &[Value], bool),
+}
+
+impl<'a> RowBatcher<'a> {
+    fn new(batch_size: usize, sink: &'a mut dyn FnMut(usize, &[Value], bool)) -> Self {
+        Self {
+            batch_size,
+            batch: Vec::with_capacity(batch_size),
+            offset: 0,
+            count: 0,
+            sink,
+        }
+    }
+
+    fn push(&mut self, row: Value) {
+        if self.batch.len() >= self.batch_size {
+            self.flush(false);
+        }
+        self.batch.push(row);
+        self.count += 1;
+    }
+
+    fn flush(&mut self, done: bool) {
+        (self.sink)(self.offset, &self.batch, done);
+        self.offset += self.batch.len();
+        self.batch.clear();
+    }
+
+    /// 返回行总数
+    fn finish(mut self) -> usize {
+        self.flush(true);
+        self.count
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Document,
+    Table,
+    Rows,
+}
+
+/// 按位置解析：文档顶层的 table 字段、其中的 rows 数组逐层下钻，rows 的元素交给 RowBatcher，
+/// 在骨架中留下空数组；其余位置与结构不符（如 rows 不是数组）时按普通 JSON 值保留
+struct StreamVisitor<'b, 'a> {
+    batcher: &'b mut RowBatcher<'a>,
+    level: Level,
+}
+
+impl<'de> DeserializeSeed<'de> for StreamVisitor<'_, '_> {
+    type Value = Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for StreamVisitor<'_, '_> {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("JSON 值")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        if self.level == Level::Rows {
+            while let Some(row) = seq.next_element::<Value>()? {
+                self.batcher.push(row);
+            }
+            return Ok(Value::Array(Vec::new()));
+        }
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element::<Value>()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let child = match (self.level, key.as_str()) {
+                (Level::Document, "table") => Some(Level::Table),
+                (Level::Table, "rows") => Some(Level::Rows),
+                _ => None,
+            };
+            let value = match child {
+                Some(level) => map.next_value_seed(StreamVisitor {
+                    batcher: &mut *self.batcher,
+                    level,
+                })?,
+                None => map.next_value::<Value>()?,
+            };
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+fn parse_error(error: serde_json::Error, offset: u64) -> AppError {
+    if error.is_io() {
+        return AppError::io("读取文件失败", std::io::Error::from(error));
+    }
+    AppError::Serialization(format!("文档格式无效（第 {} 字节附近）: {}", offset.saturating_sub(1), error))
+}
+
+/// 只有 JSON 文件后端上未加密的大文件才流式加载
+fn stream_source(name: &str) -> Result<Option<PathBuf>, AppError> {
+    if storage::backend_for(name) != StorageBackend::Json {
+        return Ok(None);
+    }
+    let Some(file_path) = resolve_existing_graph_path(name)? else {
+        return Ok(None);
+    };
+    let size = std::fs::metadata(&file_path).map_err(|e| AppError::io("读取文件信息失败", e))?.len();
+    if size < STREAM_THRESHOLD || graph_document_is_encrypted(name)? {
+        return Ok(None);
+    }
+    Ok(Some(file_path))
+}
+
+/// 返回去掉表格行的文档骨架；行在解析过程中交给 batcher，校验通过后由调用方 finish
+fn parse_document_stream(
+    name: &str,
+    file_path: &Path,
+    progress: Option<ProgressFn>,
+    batcher: &mut RowBatcher,
+) -> Result<Value, AppError> {
+    let file = File::open(file_path).map_err(|e| AppError::io("读取文件失败", e))?;
+    let total = file.metadata().map_err(|e| AppError::io("读取文件信息失败", e))?.len();
+    let state = Rc::new(RefCell::new(SourceState {
+        hasher: Sha256::new(),
+        read: 0,
+    }));
+    let source = SourceReader {
+        file,
+        total,
+        state: Rc::clone(&state),
+        progress,
+    };
+    let decoded: Box<dyn Read + '_> = if is_compressed_graph_path(file_path) {
+        Box::new(GzDecoder::new(BufReader::with_capacity(READ_BUFFER_SIZE, source)))
+    } else {
+        Box::new(source)
+    };
+    let offset = Rc::new(Cell::new(0));
+    let mut reader = OffsetReader {
+        inner: BufReader::with_capacity(READ_BUFFER_SIZE, decoded),
+        offset: Rc::clone(&offset),
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_reader(&mut reader);
+    let seed = StreamVisitor {
+        batcher,
+        level: Level::Document,
+    };
+    let document = seed
+        .deserialize(&mut deserializer)
+        .and_then(|document| deserializer.end().map(|_| document))
+        .map_err(|e| parse_error(e, offset.get()))?;
+    // 读完剩余字节（gzip 尾部等），摘要覆盖整个文件
+    std::io::copy(&mut reader, &mut std::io::sink()).map_err(|e| AppError::io("读取文件失败", e))?;
+    drop(reader);
+    let hasher = state.borrow().hasher.clone();
+    checksum::ensure_digest(name, hasher)?;
+    Ok(document)
+}
+
+fn load_streamed(
+    app: &AppHandle,
+    name: Option<&str>,
+    password: Option<&str>,
+    batch_size: usize,
+) -> Result<StreamedGraph, AppError> {
+    let graph_name = sanitize_graph_name(name)?;
+    let report_progress = progress::emitter(app.clone(), "load", describe_progress_name(name));
+    let Some(file_path) = stream_source(&graph_name)? else {
+        let document = load_named_graph(Some(&graph_name), password, Some(&report_progress))?;
+        return Ok(StreamedGraph {
+            document,
+            streamed: false,
+            row_count: 0,
+        });
+    };
+
+    let mut emit = |offset: usize, rows: &[Value], done: bool| {
+        let _ = app.emit(
+            GRAPH_ROWS_EVENT,
+            GraphRowsPayload {
+                name: &graph_name,
+                offset,
+                rows,
+                done,
+            },
+        );
+    };
+    let mut batcher = RowBatcher::new(batch_size, &mut emit);
+    let skeleton = parse_document_stream(&graph_name, &file_path, Some(&report_progress), &mut batcher)?;
+    let skeleton = serde_json::to_string(&migrations::migrate(skeleton))
+        .map_err(|e| AppError::Serialization(format!("序列化文档失败: {}", e)))?;
+    // 没有行时同样发出一个空的 done 批次
+    let row_count = batcher.finish();
+    let _ = recent::touch(&graph_name);
+    Ok(StreamedGraph {
+        document: skeleton,
+        streamed: true,
+        row_count,
+    })
+}
+
+/// batch_size 为每个 graph-rows 事件携带的行数，缺省 1000
+#[tauri::command]
+pub(crate) async fn load_graph_streamed(
+    app: AppHandle,
+    name: Option<String>,
+    password: Option<String>,
+    batch_size: Option<usize>,
+) -> Result<StreamedGraph, AppError> {
+    let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE).clamp(1, MAX_BATCH_SIZE);
+    run_blocking(move || load_streamed(&app, name.as_deref(), password.as_deref(), batch_size)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (offset, rows, done)
+    type Batch = (usize, Vec<Value>, bool);
+
+    fn stream(json: &str, batch_size: usize) -> (Value, Vec<Batch>, usize) {
+        let mut batches = Vec::new();
+        let mut sink = |offset: usize, rows: &[Value], done: bool| batches.push((offset, rows.to_vec(), done));
+        let mut batcher = RowBatcher::new(batch_size, &mut sink);
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let skeleton = StreamVisitor {
+            batcher: &mut batcher,
+            level: Level::Document,
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        deserializer.end().unwrap();
+        let count = batcher.finish();
+        (skeleton, batches, count)
+    }
+
+    #[test]
+    fn rows_are_batched_and_removed_from_skeleton() {
+        let json = r#"{"table":{"columns":["a"],"rows":[{"a":1},{"a":2},{"a":3}]},"edges":[1]}"#;
+        let (skeleton, batches, count) = stream(json, 2);
+        assert_eq!(count, 3);
+        assert_eq!(
+            skeleton,
+            serde_json::json!({"table": {"columns": ["a"], "rows": []}, "edges": [1]})
+        );
+        assert_eq!(
+            batches,
+            vec![
+                (0, vec![serde_json::json!({"a": 1}), serde_json::json!({"a": 2})], false),
+                (2, vec![serde_json::json!({"a": 3})], true),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_rows_still_sends_empty_done_batch() {
+        let (skeleton, batches, count) = stream(r#"{"table":null,"rows":[1]}"#, 2);
+        assert_eq!(count, 0);
+        assert_eq!(skeleton, serde_json::json!({"table": null, "rows": [1]}));
+        assert_eq!(batches, vec![(0, Vec::new(), true)]);
+    }
+
+    #[test]
+    fn rows_outside_table_are_kept() {
+        let (skeleton, _, count) = stream(r#"{"table":{"rows":"x"},"other":{"rows":[1]}}"#, 2);
+        assert_eq!(count, 0);
+        assert_eq!(skeleton, serde_json::json!({"table": {"rows": "x"}, "other": {"rows": [1]}}));
+    }
+}
@@ -0,0 +1,122 @@
+// 子图导出：按选中的节点（可连带直接相邻的节点）从文档中取出一份新的图 JSON
+// 只保留两端都在子图中的连线；表格通过 nodeId（没有时为 id）列与节点关联，只保留关联到子图节点的行
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::error::AppError;
+use crate::run_blocking;
+
+// 按顺序查找表格中与节点关联的列
+const TABLE_LINK_COLUMNS: [&str; 2] = ["nodeId", "id"];
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SubgraphExport {
+    // 子图文档的 JSON 文本，其余顶层字段与原文档相同
+    document: String,
+    node_count: usize,
+    edge_count: usize,
+    // 选择中在文档里找不到的节点 id 数，选择过期时仍可导出
+    unknown_count: usize,
+}
+
+fn edge_endpoints(edge: &Value) -> Option<(&str, &str)> {
+    Some((edge.get("source")?.as_str()?, edge.get("target")?.as_str()?))
+}
+
+/// 没有关联列的表格原样保留
+fn filter_table(table: &mut Value, included: &HashSet<String>) {
+    let Some(link_column) = table.get("columns").and_then(Value::as_array).and_then(|columns| {
+        TABLE_LINK_COLUMNS.into_iter().find(|key| {
+            columns
+                .iter()
+                .any(|column| column.get("id").and_then(Value::as_str) == Some(*key))
+        })
+    }) else {
+        return;
+    };
+    if let Some(rows) = table.get_mut("rows").and_then(Value::as_array_mut) {
+        rows.retain(|row| {
+            row.get(link_column)
+                .and_then(Value::as_str)
+                .is_some_and(|id| included.contains(id))
+        });
+    }
+}
+
+pub(crate) fn extract_subgraph(
+    data: &str,
+    node_ids: &[String],
+    include_neighbors: bool,
+) -> Result<SubgraphExport, AppError> {
+    let mut document: Value =
+        serde_json::from_str(data).map_err(|e| AppError::Serialization(format!("解析图数据失败: {}", e)))?;
+    let Some(nodes) = document.get("nodes").and_then(Value::as_array) else {
+        return Err(AppError::Serialization(String::from("图数据缺少 nodes 数组")));
+    };
+    let existing: HashSet<&str> = nodes.iter().filter_map(|node| node.get("id").and_then(Value::as_str)).collect();
+    let selected: HashSet<&str> = node_ids.iter().map(String::as_str).collect();
+    let unknown_count = selected.iter().filter(|id| !existing.contains(*id)).count();
+    let mut included: HashSet<String> = selected
+        .iter()
+        .filter(|id| existing.contains(*id))
+        .map(|id| String::from(*id))
+        .collect();
+    let edges = document.get("edges").and_then(Value::as_array).cloned().unwrap_or_default();
+    if include_neighbors {
+        let neighbors: Vec<String> = edges
+            .iter()
+            .filter_map(edge_endpoints)
+            .filter_map(|(source, target)| {
+                match (selected.contains(source), selected.contains(target)) {
+                    (true, false) => Some(target),
+                    (false, true) => Some(source),
+                    _ => None,
+                }
+            })
+            .filter(|id| existing.contains(id))
+            .map(String::from)
+            .collect();
+        included.extend(neighbors);
+    }
+
+    let kept_nodes: Vec<Value> = nodes
+        .iter()
+        .filter(|node| node.get("id").and_then(Value::as_str).is_some_and(|id| included.contains(id)))
+        .cloned()
+        .collect();
+    let kept_edges: Vec<Value> = edges
+        .into_iter()
+        .filter(|edge| {
+            edge_endpoints(edge).is_some_and(|(source, target)| included.contains(source) && included.contains(target))
+        })
+        .collect();
+    let (node_count, edge_count) = (kept_nodes.len(), kept_edges.len());
+    document["nodes"] = Value::Array(kept_nodes);
+    if document.get("edges").is_some() {
+        document["edges"] = Value::Array(kept_edges);
+    }
+    if let Some(table) = document.get_mut("table").filter(|table| table.is_object()) {
+        filter_table(table, &included);
+    }
+    let document =
+        serde_json::to_string(&document).map_err(|e| AppError::Serialization(format!("序列化图数据失败: {}", e)))?;
+    Ok(SubgraphExport {
+        document,
+        node_count,
+        edge_count,
+        unknown_count,
+    })
+}
+
+/// include_neighbors 为 true 时连带选中节点的直接相邻节点（不区分方向），相邻节点之间的连线也保留
+#[tauri::command]
+pub(crate) async fn export_subgraph(
+    data: String,
+    node_ids: Vec<String>,
+    include_neighbors: Option<bool>,
+) -> Result<SubgraphExport, AppError> {
+    run_blocking(move || extract_subgraph(&data, &node_ids, include_neighbors.unwrap_or(false))).await
+}
@@ -0,0 +1,227 @@
+// 表格模型：文档可在顶层携带 table { columns, rows }；没有 table 时由节点列表派生出“节点表”
+// 列类型决定导出与统计时如何解释单元格，而不是逐个单元格猜测
+// 保存时与统计时用同一套规则检查单元格是否符合列类型：空单元格视为 null，对任何类型都有效
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ColumnType {
+    #[default]
+    Text,
+    Number,
+    Date,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TableColumn {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) title: String,
+    #[serde(default, rename = "type")]
+    pub(crate) column_type: ColumnType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) align: Option<String>,
+}
+
+impl TableColumn {
+    pub(crate) fn new(id: &str, title: &str, column_type: ColumnType) -> Self {
+        Self {
+            id: String::from(id),
+            title: String::from(title),
+            column_type,
+            align: None,
+        }
+    }
+
+    /// 表头显示名：未设置 title 时使用列 id
+    pub(crate) fn header(&self) -> &str {
+        if self.title.is_empty() {
+            &self.id
+        } else {
+            &self.title
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TableData {
+    #[serde(default)]
+    pub(crate) columns: Vec<TableColumn>,
+    #[serde(default)]
+    pub(crate) rows: Vec<Map<String, Value>>,
+}
+
+impl TableData {
+    pub(crate) fn cell(&self, row_index: usize, column: &TableColumn) -> &Value {
+        self.rows
+            .get(row_index)
+            .and_then(|row| row.get(&column.id))
+            .unwrap_or(&Value::Null)
+    }
+}
+
+/// 由图节点派生的节点表：每个节点一行
+fn derive_node_table(document: &Value) -> TableData {
+    let columns = vec![
+        TableColumn::new("id", "ID", ColumnType::Text),
+        TableColumn::new("label", "标题", ColumnType::Text),
+        TableColumn::new("tags", "标签", ColumnType::Text),
+        TableColumn::new("color", "颜色", ColumnType::Text),
+        TableColumn::new("createdAt", "创建时间", ColumnType::Date),
+        TableColumn::new("updatedAt", "更新时间", ColumnType::Date),
+    ];
+    let rows = document
+        .get("nodes")
+        .and_then(Value::as_array)
+        .map(|nodes| {
+            nodes
+                .iter()
+                .map(|node| {
+                    let data = node.get("data").cloned().unwrap_or(Value::Null);
+                    let mut row = Map::new();
+                    row.insert(String::from("id"), node.get("id").cloned().unwrap_or(Value::Null));
+                    for key in ["label", "tags", "color", "createdAt", "updatedAt"] {
+                        row.insert(String::from(key), data.get(key).cloned().unwrap_or(Value::Null));
+                    }
+                    row
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    TableData { columns, rows }
+}
+
+/// 解析表格数据：接受完整文档（顶层 table 或 nodes）或单独的 table 对象
+pub(crate) fn parse_table(data: &str) -> Result<TableData, AppError> {
+    let document: Value =
+        serde_json::from_str(data).map_err(|e| AppError::Serialization(format!("解析表格数据失败: {}", e)))?;
+    parse_table_value(&document)
+}
+
+pub(crate) fn parse_table_value(document: &Value) -> Result<TableData, AppError> {
+    let table_value = match document.get("table") {
+        Some(table) if table.is_object() => table,
+        _ if document.get("columns").is_some() => document,
+        _ => return Ok(derive_node_table(document)),
+    };
+    serde_json::from_value::<TableData>(table_value.clone())
+        .map_err(|e| AppError::Serialization(format!("表格数据格式无效: {}", e)))
+}
+
+pub(crate) fn format_timestamp_ms(ms: i64) -> Option<String> {
+    Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|datetime| datetime.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// 日期列单元格解析为本地时间：接受毫秒时间戳、RFC 3339 以及 `YYYY-MM-DD[ HH:MM:SS]` 文本
+pub(crate) fn cell_datetime(value: &Value) -> Option<NaiveDateTime> {
+    match value {
+        Value::Number(number) => Local
+            .timestamp_millis_opt(number.as_i64()?)
+            .single()
+            .map(|datetime| datetime.naive_local()),
+        Value::String(text) => {
+            let text = text.trim();
+            DateTime::parse_from_rfc3339(text)
+                .map(|datetime| datetime.with_timezone(&Local).naive_local())
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S"))
+                .or_else(|_| NaiveDate::parse_from_str(text, "%Y-%m-%d").map(|date| date.and_time(Default::default())))
+                .ok()
+        }
+        _ => None,
+    }
+}
+
+/// 数字列单元格的数值：数字原样使用，文本按数字解析
+pub(crate) fn cell_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse::<f64>().ok().filter(|number| number.is_finite()),
+        _ => None,
+    }
+}
+
+/// null 或只有空白的文本
+pub(crate) fn is_empty_cell(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+/// 数字列要求 cell_number 能解析，日期列要求 cell_datetime 能解析，文本列接受任何值
+pub(crate) fn cell_matches_type(value: &Value, column_type: ColumnType) -> bool {
+    if is_empty_cell(value) {
+        return true;
+    }
+    match column_type {
+        ColumnType::Text => true,
+        ColumnType::Number => cell_number(value).is_some(),
+        ColumnType::Date => cell_datetime(value).is_some(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TypeViolation {
+    // 从 0 开始的行号
+    pub(crate) row: usize,
+    pub(crate) column: String,
+    pub(crate) value: Value,
+}
+
+/// 返回违规总数与按行序的前 limit 条
+pub(crate) fn find_type_violations(table: &TableData, limit: usize) -> (usize, Vec<TypeViolation>) {
+    let mut total = 0;
+    let mut violations = Vec::new();
+    for (row_index, row) in table.rows.iter().enumerate() {
+        for column in &table.columns {
+            let value = row.get(&column.id).unwrap_or(&Value::Null);
+            if cell_matches_type(value, column.column_type) {
+                continue;
+            }
+            total += 1;
+            if violations.len() < limit {
+                violations.push(TypeViolation {
+                    row: row_index,
+                    column: column.id.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+    }
+    (total, violations)
+}
+
+/// 单元格的文本形式：数组用逗号连接，日期列中的毫秒时间戳格式化为本地时间
+pub(crate) fn cell_text(value: &Value, column_type: ColumnType) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Number(number) => {
+            if column_type == ColumnType::Date {
+                if let Some(text) = number.as_i64().and_then(format_timestamp_ms) {
+                    return text;
+                }
+            }
+            number.to_string()
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| cell_text(item, ColumnType::Text))
+            .collect::<Vec<String>>()
+            .join(", "),
+        Value::Object(_) => value.to_string(),
+    }
+}
@@ -0,0 +1,110 @@
+// 文档标签：默认数据目录的 tags.json 记录 文档名 -> 标签列表，标签统一去掉首尾空白并转为小写后去重
+// 文档删除时移除其标签，重命名时标签跟随新名称，复制时可选择一并复制
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::error::AppError;
+use crate::{resolve_default_data_dir, sanitize_required_graph_name, write_file_atomic};
+
+const TAGS_FILE_NAME: &str = "tags.json";
+
+// tags.json 的读改写串行执行，避免并发修改互相覆盖
+static TAGS_LOCK: Mutex<()> = Mutex::new(());
+
+type TagMap = BTreeMap<String, BTreeSet<String>>;
+
+fn resolve_tags_path() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(TAGS_FILE_NAME))
+}
+
+fn read_tag_map() -> TagMap {
+    resolve_tags_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_tag_map(tags: &TagMap) -> Result<(), AppError> {
+    let contents =
+        serde_json::to_vec_pretty(tags).map_err(|e| AppError::Serialization(format!("序列化标签失败: {}", e)))?;
+    write_file_atomic(&resolve_tags_path()?, &contents)
+}
+
+fn update_tag_map(update: impl FnOnce(&mut TagMap) -> bool) -> Result<(), AppError> {
+    let _guard = TAGS_LOCK.lock().map_err(|_| AppError::Io(String::from("标签锁不可用")))?;
+    let mut tags = read_tag_map();
+    if !update(&mut tags) {
+        return Ok(());
+    }
+    write_tag_map(&tags)
+}
+
+fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// 文档删除后调用；没有标签时不改动文件
+pub(crate) fn delete_tags(name: &str) -> Result<(), AppError> {
+    update_tag_map(|tags| tags.remove(name).is_some())
+}
+
+pub(crate) fn rename_tags(old_name: &str, new_name: &str) -> Result<(), AppError> {
+    update_tag_map(|tags| {
+        let Some(entry) = tags.remove(old_name) else {
+            return false;
+        };
+        tags.insert(String::from(new_name), entry);
+        true
+    })
+}
+
+pub(crate) fn copy_tags(src_name: &str, dest_name: &str) -> Result<(), AppError> {
+    update_tag_map(|tags| {
+        let Some(entry) = tags.get(src_name).cloned() else {
+            return false;
+        };
+        tags.insert(String::from(dest_name), entry);
+        true
+    })
+}
+
+/// 整体替换文档的标签；空白标签被忽略，tags 为空时清除该文档的记录
+#[tauri::command]
+pub(crate) fn set_tags(name: String, tags: Vec<String>) -> Result<Vec<String>, AppError> {
+    let graph_name = sanitize_required_graph_name(&name)?;
+    let normalized: BTreeSet<String> = tags.iter().filter_map(|tag| normalize_tag(tag)).collect();
+    let result = normalized.iter().cloned().collect();
+    update_tag_map(|map| {
+        if normalized.is_empty() {
+            map.remove(&graph_name).is_some()
+        } else {
+            map.insert(graph_name, normalized);
+            true
+        }
+    })?;
+    Ok(result)
+}
+
+/// 按字母顺序返回
+#[tauri::command]
+pub(crate) fn get_tags(name: String) -> Result<Vec<String>, AppError> {
+    let graph_name = sanitize_required_graph_name(&name)?;
+    Ok(read_tag_map().remove(&graph_name).map(|tags| tags.into_iter().collect()).unwrap_or_default())
+}
+
+/// 带有该标签的文档名，按名称排序
+#[tauri::command]
+pub(crate) fn list_graphs_by_tag(tag: String) -> Result<Vec<String>, AppError> {
+    let Some(tag) = normalize_tag(&tag) else {
+        return Err(AppError::InvalidInput(String::from("标签不能为空")));
+    };
+    Ok(read_tag_map()
+        .into_iter()
+        .filter(|(_, tags)| tags.contains(&tag))
+        .map(|(name, _)| name)
+        .collect())
+}
@@ -0,0 +1,160 @@
+// 系统托盘：左键单击切换主窗口显示/隐藏，菜单提供显示/隐藏、最近文档与退出
+// “关闭时最小化到托盘”默认关闭，开启后记录在默认数据目录的 tray.json；托盘创建失败时照常关闭，避免窗口再也找不回来
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItemBuilder, SubmenuBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{App, AppHandle, Emitter, Manager, WebviewWindow, WindowEvent, Wry};
+
+use crate::error::AppError;
+use crate::{recent, resolve_default_data_dir, write_file_atomic};
+
+pub(crate) const TRAY_MENU_ID_PREFIX: &str = "tray.";
+pub(crate) const TRAY_OPEN_RECENT_EVENT: &str = "tray-open-recent";
+
+const TRAY_ID: &str = "main";
+const TRAY_SETTINGS_FILE_NAME: &str = "tray.json";
+const MENU_TOGGLE_ID: &str = "tray.toggle";
+const MENU_QUIT_ID: &str = "tray.quit";
+const MENU_RECENT_PREFIX: &str = "tray.recent.";
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraySettings {
+    #[serde(default)]
+    minimize_to_tray: bool,
+}
+
+fn resolve_tray_settings_path() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(TRAY_SETTINGS_FILE_NAME))
+}
+
+fn read_tray_settings() -> TraySettings {
+    resolve_tray_settings_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_tray_settings(settings: &TraySettings) -> Result<(), AppError> {
+    let contents = serde_json::to_vec_pretty(settings)
+        .map_err(|e| AppError::Serialization(format!("序列化托盘设置失败: {}", e)))?;
+    write_file_atomic(&resolve_tray_settings_path()?, &contents)
+}
+
+fn main_window(app: &AppHandle) -> Option<WebviewWindow> {
+    app.get_webview_window("main")
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = main_window(app) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+pub(crate) fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = main_window(app) else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        show_main_window(app);
+    }
+}
+
+/// 最近文档子菜单在每次打开托盘菜单前重建，保证与 recent.json 一致
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let mut recent_menu = SubmenuBuilder::new(app, "最近打开");
+    let names = recent::recent_names();
+    if names.is_empty() {
+        recent_menu = recent_menu.item(&MenuItemBuilder::new("（无）").enabled(false).build(app)?);
+    }
+    for name in names {
+        recent_menu = recent_menu.item(&MenuItemBuilder::with_id(format!("{}{}", MENU_RECENT_PREFIX, name), &name).build(app)?);
+    }
+    MenuBuilder::new(app)
+        .item(&MenuItemBuilder::with_id(MENU_TOGGLE_ID, "显示/隐藏窗口").build(app)?)
+        .item(&recent_menu.build()?)
+        .separator()
+        .item(&MenuItemBuilder::with_id(MENU_QUIT_ID, "退出").build(app)?)
+        .build()
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    if id == MENU_TOGGLE_ID {
+        toggle_main_window(app);
+    } else if id == MENU_QUIT_ID {
+        app.exit(0);
+    } else if let Some(name) = id.strip_prefix(MENU_RECENT_PREFIX) {
+        show_main_window(app);
+        let _ = app.emit(TRAY_OPEN_RECENT_EVENT, name);
+    }
+}
+
+fn handle_tray_event(tray: &TrayIcon, event: TrayIconEvent) {
+    match event {
+        TrayIconEvent::Click {
+            button: MouseButton::Left,
+            button_state: MouseButtonState::Up,
+            ..
+        } => toggle_main_window(tray.app_handle()),
+        // 鼠标移入或右键按下时菜单即将弹出，趁此刷新最近文档
+        TrayIconEvent::Enter { .. }
+        | TrayIconEvent::Click {
+            button: MouseButton::Right,
+            button_state: MouseButtonState::Down,
+            ..
+        } => {
+            if let Ok(menu) = build_tray_menu(tray.app_handle()) {
+                let _ = tray.set_menu(Some(menu));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 在 setup 中调用：创建托盘图标，并在设置开启时把主窗口的关闭改为隐藏到托盘
+pub(crate) fn install(app: &mut App) -> tauri::Result<()> {
+    let handle = app.handle().clone();
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(app.package_info().name.clone())
+        .menu(&build_tray_menu(&handle)?)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(handle_tray_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    if let Some(window) = main_window(&handle) {
+        let target = window.clone();
+        window.on_window_event(move |event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                if read_tray_settings().minimize_to_tray && target.app_handle().tray_by_id(TRAY_ID).is_some() {
+                    api.prevent_close();
+                    let _ = target.hide();
+                }
+            }
+        });
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_minimize_to_tray() -> bool {
+    read_tray_settings().minimize_to_tray
+}
+
+#[tauri::command]
+pub(crate) fn set_minimize_to_tray(enabled: bool) -> Result<(), AppError> {
+    write_tray_settings(&TraySettings {
+        minimize_to_tray: enabled,
+    })
+}
@@ -0,0 +1,137 @@
+// 应用内更新：启动时与 check_for_updates 查询 tauri.conf.json 中配置的更新地址，有新版本时发 update-available 事件
+// install_update 下载并安装上次检查到的版本，下载过程发 update-progress 事件，安装完成后重启应用
+// 任何失败都以结构化错误返回（启动时的自动检查只记录日志），不会静默忽略
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::error::AppError;
+use crate::{autosave, settings};
+
+pub(crate) const UPDATE_AVAILABLE_EVENT: &str = "update-available";
+pub(crate) const UPDATE_PROGRESS_EVENT: &str = "update-progress";
+
+/// 最近一次检查到的更新，install_update 直接使用，避免再次请求
+#[derive(Default)]
+pub(crate) struct UpdateState {
+    pending: Mutex<Option<Update>>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UpdateInfo {
+    version: String,
+    current_version: String,
+    notes: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateProgress {
+    downloaded: u64,
+    // 服务器未返回 Content-Length 时为空
+    total: Option<u64>,
+}
+
+fn map_updater_error(context: &str, error: tauri_plugin_updater::Error) -> AppError {
+    use tauri_plugin_updater::Error;
+
+    let message = format!("{}: {}", context, error);
+    match error {
+        Error::Reqwest(_) | Error::Network(_) => AppError::UpdateNetwork(message),
+        Error::Minisign(_)
+        | Error::Base64(_)
+        | Error::SignatureUtf8(_)
+        | Error::SignedVersionMismatch { .. }
+        | Error::MissingSignedVersion => AppError::UpdateSignatureInvalid(message),
+        _ => AppError::UpdateFailed(message),
+    }
+}
+
+fn lock_pending(state: &UpdateState) -> Result<std::sync::MutexGuard<'_, Option<Update>>, AppError> {
+    state
+        .pending
+        .lock()
+        .map_err(|_| AppError::UpdateFailed(String::from("更新状态锁已损坏")))
+}
+
+async fn check(app: &AppHandle, state: &UpdateState) -> Result<Option<UpdateInfo>, AppError> {
+    let update = app
+        .updater()
+        .map_err(|e| map_updater_error("初始化更新检查失败", e))?
+        .check()
+        .await
+        .map_err(|e| map_updater_error("检查更新失败", e))?;
+    let info = update.as_ref().map(|update| UpdateInfo {
+        version: update.version.clone(),
+        current_version: update.current_version.clone(),
+        notes: update.body.clone(),
+    });
+    *lock_pending(state)? = update;
+    if let Some(info) = &info {
+        let _ = app.emit(UPDATE_AVAILABLE_EVENT, info.clone());
+    }
+    Ok(info)
+}
+
+/// 在 setup 中调用：后台检查一次更新，失败只记录日志，不打扰启动
+pub(crate) fn check_on_startup(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = tauri::Manager::state::<UpdateState>(&app);
+        if let Err(error) = check(&app, &state).await {
+            log::warn!("启动时检查更新失败: {}", error);
+        }
+    });
+}
+
+/// 没有新版本时返回 null
+#[tauri::command]
+pub(crate) async fn check_for_updates(
+    app: AppHandle,
+    state: State<'_, UpdateState>,
+) -> Result<Option<UpdateInfo>, AppError> {
+    check(&app, &state).await
+}
+
+#[tauri::command]
+pub(crate) async fn install_update(app: AppHandle, state: State<'_, UpdateState>) -> Result<(), AppError> {
+    let pending = lock_pending(&state)?.take();
+    let update = match pending {
+        Some(update) => update,
+        None => {
+            check(&app, &state).await?;
+            lock_pending(&state)?
+                .take()
+                .ok_or_else(|| AppError::NotFound(String::from("当前已是最新版本")))?
+        }
+    };
+
+    let mut downloaded = 0u64;
+    let progress_app = app.clone();
+    let result = update
+        .download_and_install(
+            move |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = progress_app.emit(UPDATE_PROGRESS_EVENT, UpdateProgress { downloaded, total });
+            },
+            || {},
+        )
+        .await;
+    if let Err(error) = result {
+        // 失败后保留待安装的版本，用户可直接重试
+        *lock_pending(&state)? = Some(update);
+        return Err(map_updater_error("安装更新失败", error));
+    }
+    log::info!("已安装版本 {}，正在重启", update.version);
+    // restart 不经过 RunEvent::Exit，需要先写出自动保存与设置
+    if let Err(error) = autosave::flush_pending() {
+        log::error!("重启前写出自动保存失败: {}", error);
+    }
+    if let Err(error) = settings::flush() {
+        log::error!("重启前写出设置失败: {}", error);
+    }
+    app.restart()
+}
@@ -0,0 +1,325 @@
+// 写入前的文档结构校验：把前端 bug 造成的坏数据挡在保存阶段，而不是等到下次启动加载失败
+// 悬空连线（source/target 指向不存在的节点）按设置项 danglingEdges 处理：reject 拒绝保存（缺省）、drop 剔除后保存、keep 原样保留
+// 文档大小（UTF-8 字节数）不得超过设置项 maxDocumentSize（缺省 512MB），在任何 IO 之前检查
+// 表格单元格与列类型不符时按设置项 columnTypeViolations 处理：warn 照常保存并报告（缺省），reject 拒绝保存
+// 显式保存与自动保存都经 prepare_document 处理，两条路径的校验规则一致；文档只解析一次，各项检查与输出共用同一个 Value
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::io::Write;
+
+use crate::error::AppError;
+use crate::json_format::{self, JsonOutputFormat};
+use crate::settings;
+use crate::table::{find_type_violations, parse_table_value, TableData, TypeViolation};
+
+const DANGLING_EDGES_SETTING: &str = "danglingEdges";
+const MAX_DOCUMENT_SIZE_SETTING: &str = "maxDocumentSize";
+const DEFAULT_MAX_DOCUMENT_SIZE: u64 = 512 * 1024 * 1024;
+// 设置值低于该值时按该值处理，避免误设的过小上限让所有保存失败
+const MIN_MAX_DOCUMENT_SIZE: u64 = 1024 * 1024;
+pub(crate) const DANGLING_EDGES_DROPPED_EVENT: &str = "dangling-edges-dropped";
+// 错误消息中最多列出的连线数，完整列表在 edgeIds 字段中
+const MAX_LISTED_EDGES: usize = 20;
+const COLUMN_TYPES_SETTING: &str = "columnTypeViolations";
+pub(crate) const COLUMN_TYPE_VIOLATIONS_EVENT: &str = "column-type-violations";
+// 报告中最多带的违规单元格数，总数另行给出
+const MAX_REPORTED_VIOLATIONS: usize = 100;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DanglingEdgePolicy {
+    #[default]
+    Reject,
+    Drop,
+    Keep,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DanglingEdgesDropped {
+    pub(crate) name: String,
+    pub(crate) edge_ids: Vec<String>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ColumnTypePolicy {
+    #[default]
+    Warn,
+    Reject,
+}
+
+/// warn 策略下保存成功后随事件发给前端
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ColumnTypeViolations {
+    pub(crate) name: String,
+    pub(crate) total: usize,
+    pub(crate) violations: Vec<TypeViolation>,
+}
+
+/// serde_json 报告的是行列号（列按字节计），换算成从 0 开始的字节偏移便于定位
+fn byte_offset(data: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = data
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column.saturating_sub(1)).min(data.len())
+}
+
+fn parse_document(data: &str) -> Result<Value, AppError> {
+    serde_json::from_str(data).map_err(|e| {
+        AppError::Serialization(format!(
+            "文档不是合法的 JSON（字节偏移 {}，第 {} 行第 {} 列）: {}",
+            byte_offset(data, e.line(), e.column()),
+            e.line(),
+            e.column(),
+            e
+        ))
+    })
+}
+
+fn require_array(document: &Value, key: &str) -> Result<(), AppError> {
+    match document.get(key) {
+        Some(Value::Array(_)) => Ok(()),
+        Some(_) => Err(AppError::Serialization(format!("文档字段 {} 必须是数组", key))),
+        None => Err(AppError::Serialization(format!("文档缺少 {} 字段", key))),
+    }
+}
+
+/// 顶层必须是包含 nodes 与 edges 数组的对象；table 为可选段，存在时需符合表格模型，返回解析出的表格
+fn check_structure(document: &Value) -> Result<Option<TableData>, AppError> {
+    if !document.is_object() {
+        return Err(AppError::Serialization(String::from("文档顶层必须是 JSON 对象")));
+    }
+    require_array(document, "nodes")?;
+    require_array(document, "edges")?;
+    match document.get("table") {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Object(_)) => parse_table_value(document).map(Some),
+        Some(_) => Err(AppError::Serialization(String::from("文档字段 table 必须是对象"))),
+    }
+}
+
+pub(crate) fn validate_document(data: &str) -> Result<(), AppError> {
+    check_structure(&parse_document(data)?).map(|_| ())
+}
+
+pub(crate) fn max_document_size() -> u64 {
+    settings::read_setting(MAX_DOCUMENT_SIZE_SETTING)
+        .unwrap_or(DEFAULT_MAX_DOCUMENT_SIZE)
+        .max(MIN_MAX_DOCUMENT_SIZE)
+}
+
+/// size 为 UTF-8 字节数（即 String::len），多字节字符按实际字节计
+pub(crate) fn check_document_size(size: usize) -> Result<(), AppError> {
+    let size = size as u64;
+    let limit = max_document_size();
+    if size <= limit {
+        return Ok(());
+    }
+    Err(AppError::TooLarge {
+        message: format!("文档大小 {} 字节超过上限 {} 字节，可在设置项 {} 中调整", size, limit, MAX_DOCUMENT_SIZE_SETTING),
+        size,
+        limit,
+    })
+}
+
+/// 只计数不保存的写入器，用于在不分配内存的情况下得到序列化后的字节数
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) fn serialized_len(value: &impl Serialize) -> usize {
+    let mut counter = ByteCounter(0);
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+pub(crate) fn dangling_edge_policy() -> DanglingEdgePolicy {
+    settings::read_setting(DANGLING_EDGES_SETTING).unwrap_or_default()
+}
+
+/// 没有 id 的连线用 `#<序号>` 表示
+fn edge_label(index: usize, edge: &Value) -> String {
+    match edge.get("id").and_then(Value::as_str) {
+        Some(id) if !id.is_empty() => String::from(id),
+        _ => format!("#{}", index),
+    }
+}
+
+pub(crate) fn dangling_edges_error(ids: Vec<String>) -> AppError {
+    let mut listed = ids.iter().take(MAX_LISTED_EDGES).cloned().collect::<Vec<_>>().join(", ");
+    if ids.len() > MAX_LISTED_EDGES {
+        listed.push_str(" …");
+    }
+    AppError::DanglingEdges {
+        message: format!("{} 条连线引用了不存在的节点: {}", ids.len(), listed),
+        ids,
+    }
+}
+
+/// 端点缺失或不是字符串的连线同样视为悬空；返回悬空连线在 edges 中的下标
+fn find_dangling_edges(nodes: &[Value], edges: &[Value]) -> Vec<usize> {
+    let node_ids: HashSet<&str> = nodes.iter().filter_map(|node| node.get("id").and_then(Value::as_str)).collect();
+    edges
+        .iter()
+        .enumerate()
+        .filter(|(_, edge)| {
+            ["source", "target"]
+                .iter()
+                .any(|key| !edge.get(*key).and_then(Value::as_str).is_some_and(|id| node_ids.contains(id)))
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// 按策略处理文档中的悬空连线，返回被剔除的连线；reject 时返回 DanglingEdges 错误，文档不变
+pub(crate) fn apply_dangling_edge_policy(
+    document: &mut Map<String, Value>,
+    policy: DanglingEdgePolicy,
+) -> Result<Vec<String>, AppError> {
+    if policy == DanglingEdgePolicy::Keep {
+        return Ok(Vec::new());
+    }
+    let (Some(Value::Array(nodes)), Some(Value::Array(edges))) = (document.get("nodes"), document.get("edges")) else {
+        return Ok(Vec::new());
+    };
+    let dangling = find_dangling_edges(nodes, edges);
+    if dangling.is_empty() {
+        return Ok(Vec::new());
+    }
+    let ids: Vec<String> = dangling.iter().map(|&index| edge_label(index, &edges[index])).collect();
+    if policy == DanglingEdgePolicy::Reject {
+        return Err(dangling_edges_error(ids));
+    }
+    if let Some(Value::Array(edges)) = document.get_mut("edges") {
+        let dangling: HashSet<usize> = dangling.into_iter().collect();
+        let mut index = 0;
+        edges.retain(|_| {
+            let keep = !dangling.contains(&index);
+            index += 1;
+            keep
+        });
+    }
+    Ok(ids)
+}
+
+pub(crate) fn column_type_policy() -> ColumnTypePolicy {
+    settings::read_setting(COLUMN_TYPES_SETTING).unwrap_or_default()
+}
+
+/// 只检查文档中显式保存的 table；返回违规总数与前若干条，reject 策略下有违规时返回 TypeMismatch 错误
+fn check_column_types(table: Option<&TableData>) -> Result<(usize, Vec<TypeViolation>), AppError> {
+    let Some(table) = table else {
+        return Ok((0, Vec::new()));
+    };
+    let (total, violations) = find_type_violations(table, MAX_REPORTED_VIOLATIONS);
+    if total > 0 && column_type_policy() == ColumnTypePolicy::Reject {
+        let first = &violations[0];
+        return Err(AppError::TypeMismatch {
+            message: format!(
+                "表格中有 {} 个单元格不符合列类型，首个位于第 {} 行 {} 列: {}",
+                total,
+                first.row + 1,
+                first.column,
+                first.value
+            ),
+            violations,
+        });
+    }
+    Ok((total, violations))
+}
+
+/// prepare_document 的结果；dropped_edges 与 type_violations 由调用方决定如何告知前端
+pub(crate) struct PreparedDocument {
+    pub(crate) data: String,
+    pub(crate) dropped_edges: Vec<String>,
+    pub(crate) type_violations: (usize, Vec<TypeViolation>),
+}
+
+/// 保存前的统一处理：结构校验、列类型检查、按 danglingEdges 设置处理悬空连线，最后按 format 输出；
+/// preserve 格式下没有剔除连线时原样返回 data
+pub(crate) fn prepare_document(data: String, format: JsonOutputFormat) -> Result<PreparedDocument, AppError> {
+    let mut document = parse_document(&data)?;
+    let table = check_structure(&document)?;
+    let type_violations = check_column_types(table.as_ref())?;
+    let dropped_edges = match &mut document {
+        Value::Object(object) => apply_dangling_edge_policy(object, dangling_edge_policy())?,
+        _ => Vec::new(),
+    };
+    let data = if format == JsonOutputFormat::Preserve && dropped_edges.is_empty() {
+        data
+    } else {
+        json_format::render_value(document, format)?
+    };
+    Ok(PreparedDocument {
+        data,
+        dropped_edges,
+        type_violations,
+    })
+}
+
+#[tauri::command]
+pub(crate) fn get_column_type_policy() -> ColumnTypePolicy {
+    column_type_policy()
+}
+
+#[tauri::command]
+pub(crate) fn set_column_type_policy(policy: ColumnTypePolicy) -> Result<(), AppError> {
+    settings::write_setting(COLUMN_TYPES_SETTING, policy)
+}
+
+#[tauri::command]
+pub(crate) fn get_dangling_edge_policy() -> DanglingEdgePolicy {
+    dangling_edge_policy()
+}
+
+#[tauri::command]
+pub(crate) fn set_dangling_edge_policy(policy: DanglingEdgePolicy) -> Result<(), AppError> {
+    settings::write_setting(DANGLING_EDGES_SETTING, policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r#"{"nodes":[{"id":"a"},{"id":"b"}],"edges":[{"id":"e1","source":"a","target":"b"}]}"#;
+
+    #[test]
+    fn preserve_keeps_original_bytes() {
+        let data = format!(" {} ", DOCUMENT);
+        let prepared = prepare_document(data.clone(), JsonOutputFormat::Preserve).unwrap();
+        assert_eq!(prepared.data, data);
+        assert!(prepared.dropped_edges.is_empty());
+        assert_eq!(prepared.type_violations.0, 0);
+    }
+
+    #[test]
+    fn pretty_renders_the_parsed_document() {
+        let prepared = prepare_document(String::from(DOCUMENT), JsonOutputFormat::Pretty).unwrap();
+        assert!(prepared.data.starts_with("{\n  \"edges\""));
+        let reparsed: Value = serde_json::from_str(&prepared.data).unwrap();
+        assert_eq!(reparsed, serde_json::from_str::<Value>(DOCUMENT).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_structure() {
+        for data in [r#"[]"#, r#"{"nodes":[]}"#, r#"{"nodes":[],"edges":{}}"#, r#"{"nodes":[],"edges":[],"table":1}"#] {
+            let error = prepare_document(String::from(data), JsonOutputFormat::Preserve).err().unwrap();
+            assert_eq!(error.code(), "SERIALIZATION_ERROR", "{}", data);
+        }
+    }
+}
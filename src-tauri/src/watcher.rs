@@ -0,0 +1,156 @@
+// 文档文件监听：外部编辑器或同步盘修改当前文档时向前端发送 graph-file-changed 事件
+// 监听数据目录而不是单个文件，因为编辑器与本应用的原子写入都是“写临时文件再 rename”，
+// 文件被替换后针对旧 inode 的监听会失效
+// 一次保存会产生一串事件（写临时文件、rename、改权限），安静期结束后合并为一次；
+// 应用自身写入后记下文件的大小与修改时间，安静期结束时文件仍是这个状态就说明变更来自自身，不通知前端
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{mpsc, LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::AppError;
+use crate::{resolve_app_data_dir, sanitize_required_graph_name, storage};
+
+pub(crate) const GRAPH_FILE_CHANGED_EVENT: &str = "graph-file-changed";
+// 连续的变更通知在安静期结束后合并为一次事件
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct FileState {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+// 文档名 -> 应用最后一次写入后的文件状态
+static SELF_WRITES: LazyLock<Mutex<HashMap<String, FileState>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn file_state(path: &Path) -> Option<FileState> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some(FileState {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+/// 写入文档后调用，记下预期的文件状态
+pub(crate) fn note_self_write(name: &str, path: &Path) {
+    let Some(state) = file_state(path) else {
+        return;
+    };
+    if let Ok(mut writes) = SELF_WRITES.lock() {
+        writes.insert(String::from(name), state);
+    }
+}
+
+/// 文件已被删除或与最后一次自身写入的状态不同，都视为外部修改
+fn matches_self_write(name: &str) -> bool {
+    let Some(current) = storage::resolve_document_file(name)
+        .ok()
+        .flatten()
+        .and_then(|path| file_state(&path))
+    else {
+        return false;
+    };
+    SELF_WRITES
+        .lock()
+        .ok()
+        .and_then(|writes| writes.get(name).copied())
+        .is_some_and(|expected| expected == current)
+}
+
+struct ActiveWatch {
+    name: String,
+    // 丢弃 watcher 会关闭事件通道，去抖线程随之退出
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Default)]
+pub(crate) struct WatchState {
+    active: Mutex<Option<ActiveWatch>>,
+}
+
+fn is_document_event(event: &notify::Event, name: &str) -> bool {
+    if matches!(event.kind, EventKind::Access(_) | EventKind::Other) {
+        return false;
+    }
+    let candidates = [
+        format!("{}.json", name),
+        format!("{}.json.gz", name),
+        format!("{}{}", name, storage::SQLITE_FILE_SUFFIX),
+    ];
+    event.paths.iter().any(|path| {
+        path.file_name()
+            .map(|file_name| candidates.iter().any(|candidate| file_name == candidate.as_str()))
+            .unwrap_or(false)
+    })
+}
+
+fn spawn_debounce_thread(app: AppHandle, name: String, rx: mpsc::Receiver<notify::Result<notify::Event>>) {
+    thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut pending = matches!(&first, Ok(event) if is_document_event(event, &name));
+            loop {
+                match rx.recv_timeout(DEBOUNCE_WINDOW) {
+                    Ok(Ok(event)) => pending |= is_document_event(&event, &name),
+                    Ok(Err(_)) => {}
+                    Err(mpsc::RecvTimeoutError::Timeout) => break,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if pending && !matches_self_write(&name) {
+                let _ = app.emit(GRAPH_FILE_CHANGED_EVENT, &name);
+            }
+        }
+    });
+}
+
+fn start_watch(app: AppHandle, name: String, dir: &Path) -> Result<ActiveWatch, AppError> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| AppError::Io(format!("创建文件监听失败: {}", e)))?;
+    watcher
+        .watch(dir, RecursiveMode::NonRecursive)
+        .map_err(|e| AppError::Io(format!("监听数据目录失败: {}", e)))?;
+    spawn_debounce_thread(app, name.clone(), rx);
+    Ok(ActiveWatch {
+        name,
+        _watcher: watcher,
+    })
+}
+
+/// 同一时间只监听一个文档；切换文档时新的监听会替换旧的
+#[tauri::command]
+pub(crate) fn watch_graph(app: AppHandle, state: State<WatchState>, name: String) -> Result<(), AppError> {
+    let graph_name = sanitize_required_graph_name(&name)?;
+    let mut active = state
+        .active
+        .lock()
+        .map_err(|_| AppError::Io(String::from("文件监听状态不可用")))?;
+    if active.as_ref().map(|watch| watch.name == graph_name).unwrap_or(false) {
+        return Ok(());
+    }
+    *active = None;
+    *active = Some(start_watch(app, graph_name, &resolve_app_data_dir()?)?);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn stop_watch(state: State<WatchState>) -> Result<(), AppError> {
+    let mut active = state
+        .active
+        .lock()
+        .map_err(|_| AppError::Io(String::from("文件监听状态不可用")))?;
+    *active = None;
+    Ok(())
+}
+
+/// 退出前调用：停止当前的监听
+pub(crate) fn stop_all(state: &WatchState) {
+    if let Ok(mut active) = state.active.lock() {
+        *active = None;
+    }
+}
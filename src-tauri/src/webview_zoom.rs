@@ -0,0 +1,208 @@
+// 触控板捏合缩放：各平台的 webview 对捏合手势的处理不同，macOS / Linux 统一转换成前端已处理的 Ctrl+滚轮事件，
+// 前端无需区分操作系统；Windows 上由 WebView2 自身缩放，前端通过 visualViewport 检测
+// 是否启用由设置项 pinchZoomEnabled 决定（默认开启），set_pinch_zoom 修改后立即对已打开的窗口生效
+// 页面缩放比例保存在设置项 zoomLevel 中，启动时恢复到主窗口
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::error::AppError;
+use crate::settings;
+
+const PLUGIN_NAME: &str = "webview-zoom";
+const PINCH_ZOOM_SETTING: &str = "pinchZoomEnabled";
+const ZOOM_LEVEL_SETTING: &str = "zoomLevel";
+const MIN_ZOOM_LEVEL: f64 = 0.25;
+const MAX_ZOOM_LEVEL: f64 = 5.0;
+const DEFAULT_ZOOM_LEVEL: f64 = 1.0;
+
+// 进程内的当前状态；Linux 的手势回调与 macOS 的初始化脚本据此决定是否缩放
+static PINCH_ZOOM_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// 读取设置并同步到进程内状态，在构建插件时调用一次
+fn load_pinch_zoom_setting() -> bool {
+    let enabled = settings::read_setting(PINCH_ZOOM_SETTING).unwrap_or(true);
+    PINCH_ZOOM_ENABLED.store(enabled, Ordering::Relaxed);
+    enabled
+}
+
+pub(crate) fn pinch_zoom_enabled() -> bool {
+    PINCH_ZOOM_ENABLED.load(Ordering::Relaxed)
+}
+
+#[cfg(target_os = "macos")]
+const PINCH_ZOOM_FLAG: &str = "window.__GRAPH_AND_TABLE_PINCH_ZOOM__";
+
+// 与前端 visualViewport 检测使用的阈值一致：缩放比例变化超过 2% 才触发一次缩放
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const SCALE_STEP: f64 = 0.02;
+
+/// 合成一次 Ctrl+滚轮事件：deltaY 为负表示放大，与浏览器的 Ctrl+滚轮约定一致
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn synthetic_wheel_script(zoom_in: &str) -> String {
+    format!(
+        "document.dispatchEvent(new WheelEvent('wheel', {{ ctrlKey: true, deltaY: ({}) ? -1 : 1, bubbles: true, cancelable: true }}));",
+        zoom_in
+    )
+}
+
+/// macOS 的 WKWebView 不把捏合转成 Ctrl+滚轮，而是派发 Safari 的 gesture 事件；
+/// 初始化脚本拦截这些事件（阻止整页缩放），按比例变化合成 Ctrl+滚轮事件
+#[cfg(target_os = "macos")]
+pub(crate) fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    let script = format!(
+        r#"(function () {{
+  {flag} = {enabled};
+  var lastScale = 1;
+  var options = {{ passive: false }};
+  document.addEventListener('gesturestart', function (event) {{
+    event.preventDefault();
+    lastScale = 1;
+  }}, options);
+  document.addEventListener('gesturechange', function (event) {{
+    event.preventDefault();
+    var scale = event.scale;
+    if ({flag} && Math.abs(scale - lastScale) > {step} * lastScale) {{
+      var zoomIn = scale > lastScale;
+      {dispatch}
+      lastScale = scale;
+    }}
+  }}, options);
+  document.addEventListener('gestureend', function (event) {{
+    event.preventDefault();
+  }}, options);
+}})();"#,
+        flag = PINCH_ZOOM_FLAG,
+        enabled = load_pinch_zoom_setting(),
+        step = SCALE_STEP,
+        dispatch = synthetic_wheel_script("zoomIn"),
+    );
+    tauri::plugin::Builder::new(PLUGIN_NAME).js_init_script(script).build()
+}
+
+/// WebKitGTK 自行处理触控板捏合并缩放整页；在捕获阶段挂一个 GestureZoom 抢先认领手势序列，
+/// 再按比例变化向页面派发 Ctrl+滚轮事件。关闭时仍认领手势，只是不派发，整页也不会被缩放
+#[cfg(target_os = "linux")]
+pub(crate) fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    load_pinch_zoom_setting();
+    tauri::plugin::Builder::new(PLUGIN_NAME)
+        .on_webview_ready(|webview| {
+            let target = webview.clone();
+            let _ = webview.with_webview(move |platform| {
+                use gtk::prelude::*;
+                use std::cell::Cell;
+                use std::rc::Rc;
+
+                let view = platform.inner();
+                let gesture = gtk::GestureZoom::new(&view);
+                gesture.set_propagation_phase(gtk::PropagationPhase::Capture);
+                let last_scale = Rc::new(Cell::new(1.0f64));
+                let begin_scale = Rc::clone(&last_scale);
+                gesture.connect_begin(move |gesture, _| {
+                    begin_scale.set(1.0);
+                    gesture.set_state(gtk::EventSequenceState::Claimed);
+                });
+                gesture.connect_scale_changed(move |_, scale| {
+                    let last = last_scale.get();
+                    if pinch_zoom_enabled() && (scale - last).abs() > SCALE_STEP * last {
+                        let zoom_in = if scale > last { "true" } else { "false" };
+                        let _ = target.eval(synthetic_wheel_script(zoom_in));
+                        last_scale.set(scale);
+                    }
+                });
+                // GtkGesture 不归控件所有，挂到 webview 上与其同生命周期，否则离开作用域即被销毁
+                // SAFETY: 该键只在这里写入，且不会以其他类型读取
+                unsafe {
+                    view.set_data("graphandtable-zoom-gesture", gesture);
+                }
+            });
+        })
+        .build()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+pub(crate) fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    load_pinch_zoom_setting();
+    tauri::plugin::Builder::new(PLUGIN_NAME).build()
+}
+
+/// 把当前状态应用到窗口：Windows 上切换 WebView2 的 IsPinchZoomEnabled，macOS 上更新页面内的开关
+#[cfg(target_os = "windows")]
+pub(crate) fn apply_pinch_zoom<R: Runtime>(window: &WebviewWindow<R>, enabled: bool) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Settings5;
+    use windows_core::Interface;
+
+    let _ = window.with_webview(move |webview| unsafe {
+        let Ok(core) = webview.controller().CoreWebView2() else {
+            return;
+        };
+        let Ok(settings) = core.Settings() else {
+            return;
+        };
+        if let Ok(settings5) = settings.cast::<ICoreWebView2Settings5>() {
+            let _ = settings5.SetIsPinchZoomEnabled(enabled);
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn apply_pinch_zoom<R: Runtime>(window: &WebviewWindow<R>, enabled: bool) {
+    let _ = window.eval(format!("{} = {};", PINCH_ZOOM_FLAG, enabled));
+}
+
+// Linux 的手势回调直接读取进程内状态，无需逐窗口应用
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub(crate) fn apply_pinch_zoom<R: Runtime>(_window: &WebviewWindow<R>, _enabled: bool) {}
+
+#[tauri::command]
+pub(crate) fn set_pinch_zoom(app: AppHandle, enabled: bool) -> Result<(), AppError> {
+    settings::write_setting(PINCH_ZOOM_SETTING, enabled)?;
+    PINCH_ZOOM_ENABLED.store(enabled, Ordering::Relaxed);
+    for window in app.webview_windows().values() {
+        apply_pinch_zoom(window, enabled);
+    }
+    Ok(())
+}
+
+/// 超出范围的值钳制到边界而不是报错，兼容旧版本写入的设置；非有限值视为默认比例
+fn clamp_zoom_level(level: f64) -> f64 {
+    if level.is_finite() {
+        level.clamp(MIN_ZOOM_LEVEL, MAX_ZOOM_LEVEL)
+    } else {
+        DEFAULT_ZOOM_LEVEL
+    }
+}
+
+pub(crate) fn stored_zoom_level() -> f64 {
+    clamp_zoom_level(settings::read_setting(ZOOM_LEVEL_SETTING).unwrap_or(DEFAULT_ZOOM_LEVEL))
+}
+
+/// Windows 上直接设置 WebView2 控制器的 ZoomFactor，其他平台使用 webview 自带的页面缩放
+#[cfg(target_os = "windows")]
+pub(crate) fn apply_zoom_level<R: Runtime>(window: &WebviewWindow<R>, level: f64) {
+    let _ = window.with_webview(move |webview| unsafe {
+        let _ = webview.controller().SetZoomFactor(level);
+    });
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn apply_zoom_level<R: Runtime>(window: &WebviewWindow<R>, level: f64) {
+    let _ = window.set_zoom(level);
+}
+
+/// 返回实际保存的（钳制后的）比例
+#[tauri::command]
+pub(crate) fn save_zoom(level: f64) -> Result<f64, AppError> {
+    if !level.is_finite() {
+        return Err(AppError::InvalidInput(format!("缩放比例无效: {}", level)));
+    }
+    let level = clamp_zoom_level(level);
+    settings::write_setting(ZOOM_LEVEL_SETTING, level)?;
+    Ok(level)
+}
+
+#[tauri::command]
+pub(crate) fn load_zoom() -> f64 {
+    stored_zoom_level()
+}
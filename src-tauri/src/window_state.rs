@@ -0,0 +1,122 @@
+// 窗口几何状态：启动时从 window_state.json 恢复主窗口尺寸、位置与最大化状态，
+// 移动/缩放时更新内存中的状态，关闭窗口时落盘，避免拖动过程中频繁写文件
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{PhysicalPosition, PhysicalSize, WebviewWindow, WindowEvent};
+
+use crate::error::AppError;
+use crate::{resolve_default_data_dir, write_file_atomic};
+
+const WINDOW_STATE_FILE_NAME: &str = "window_state.json";
+// 过小的尺寸通常来自异常退出时的中间状态，不予恢复
+const MIN_RESTORE_SIZE: u32 = 200;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WindowState {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) maximized: bool,
+}
+
+fn resolve_window_state_path() -> Result<PathBuf, AppError> {
+    Ok(resolve_default_data_dir()?.join(WINDOW_STATE_FILE_NAME))
+}
+
+fn read_window_state() -> Option<WindowState> {
+    let path = resolve_window_state_path().ok()?;
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_window_state(state: &WindowState) -> Result<(), AppError> {
+    let contents =
+        serde_json::to_vec_pretty(state).map_err(|e| AppError::Serialization(format!("序列化窗口状态失败: {}", e)))?;
+    write_file_atomic(&resolve_window_state_path()?, &contents)
+}
+
+/// 保存的窗口矩形与任一当前显示器有交集才算可见；显示器拔掉后整窗落在屏幕外则放弃恢复位置
+fn is_visible_on_any_monitor(window: &WebviewWindow, state: &WindowState) -> bool {
+    let Ok(monitors) = window.available_monitors() else {
+        return false;
+    };
+    let right = state.x as i64 + state.width as i64;
+    let bottom = state.y as i64 + state.height as i64;
+    monitors.iter().any(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        let monitor_right = position.x as i64 + size.width as i64;
+        let monitor_bottom = position.y as i64 + size.height as i64;
+        (state.x as i64) < monitor_right
+            && right > position.x as i64
+            && (state.y as i64) < monitor_bottom
+            && bottom > position.y as i64
+    })
+}
+
+fn capture_window_state(window: &WebviewWindow, previous: WindowState) -> WindowState {
+    let maximized = window.is_maximized().unwrap_or(false);
+    // 最大化或最小化时保留上一次的普通窗口几何，还原后仍回到原来的大小和位置
+    if maximized || window.is_minimized().unwrap_or(false) {
+        return WindowState { maximized, ..previous };
+    }
+    let mut state = WindowState {
+        maximized: false,
+        ..previous
+    };
+    if let Ok(size) = window.inner_size() {
+        state.width = size.width;
+        state.height = size.height;
+    }
+    if let Ok(position) = window.outer_position() {
+        state.x = position.x;
+        state.y = position.y;
+    }
+    state
+}
+
+fn restore_window_state(window: &WebviewWindow, state: &WindowState) {
+    if state.width >= MIN_RESTORE_SIZE && state.height >= MIN_RESTORE_SIZE {
+        let _ = window.set_size(PhysicalSize::new(state.width, state.height));
+    }
+    if is_visible_on_any_monitor(window, state) {
+        let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+    } else {
+        let _ = window.center();
+    }
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// 在 setup 中调用：恢复上次的窗口状态，并注册事件处理器跟踪后续变化
+pub(crate) fn install(window: &WebviewWindow) {
+    let initial = match read_window_state() {
+        Some(state) => {
+            restore_window_state(window, &state);
+            state
+        }
+        None => capture_window_state(window, WindowState::default()),
+    };
+
+    let tracked = Arc::new(Mutex::new(initial));
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            if let Ok(mut state) = tracked.lock() {
+                *state = capture_window_state(&handle, *state);
+            }
+        }
+        WindowEvent::CloseRequested { .. } => {
+            if let Ok(mut state) = tracked.lock() {
+                *state = capture_window_state(&handle, *state);
+                let _ = write_window_state(&state);
+            }
+        }
+        _ => {}
+    });
+}